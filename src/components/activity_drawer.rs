@@ -0,0 +1,49 @@
+use dioxus::prelude::*;
+
+use crate::components::StatusPill;
+use crate::utils::AppState;
+
+/// Everything the app has done this session, newest first.
+#[component]
+pub fn ActivityDrawer(on_close: EventHandler<()>) -> Element {
+    let app_state = use_context::<AppState>();
+    let entries: Vec<_> = (app_state.activity)()
+        .entries()
+        .iter()
+        .rev()
+        .cloned()
+        .collect();
+
+    rsx! {
+        aside { class: "activity-drawer",
+            div { class: "activity-header",
+                h3 { "Activity" }
+                button {
+                    class: "button secondary",
+                    onclick: move |_| on_close.call(()),
+                    "Close"
+                }
+            }
+            if entries.is_empty() {
+                p { class: "row-subtitle", "Nothing has happened yet." }
+            }
+            ul { class: "activity-list",
+                for entry in entries {
+                    li { key: "{entry.id}", class: "activity-entry",
+                        div { class: "activity-line",
+                            span { class: "activity-time", "{entry.time_label()}" }
+                            span { class: "activity-summary", "{entry.summary()}" }
+                            span { class: "activity-duration", "{entry.duration_label()}" }
+                            if let Some(report) = entry.failure() {
+                                StatusPill { label: "Failed".to_string(), class_name: "stopped".to_string() }
+                                p { class: "activity-error", "{report.error}" }
+                            } else {
+                                StatusPill { label: "OK".to_string(), class_name: "running".to_string() }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashSet;
+
+use dioxus::prelude::*;
+
+use crate::components::StatusPill;
+use crate::utils::{AppState, BulkAction, TableKind};
+
+/// Select-all toggle and bulk actions for the rows currently shown in `table`,
+/// followed by the per-item results of the last bulk action on it.
+#[component]
+pub fn BulkBar(table: TableKind, visible: Vec<String>) -> Element {
+    let app_state = use_context::<AppState>();
+    let selected = app_state.selected(table);
+    let all_selected = !visible.is_empty() && visible.iter().all(|id| selected.contains(id));
+    let is_running = (app_state.bulk)().is_some_and(|run| run.is_running);
+    let count = visible.iter().filter(|id| selected.contains(*id)).count();
+    let hidden = selected.len() - count;
+    let shown = visible.len();
+
+    let app_state_for_all = app_state.clone();
+    let app_state_for_clear = app_state.clone();
+
+    rsx! {
+        div { class: "bulk-bar",
+            label { class: "checkbox-label",
+                input {
+                    r#type: "checkbox",
+                    checked: all_selected,
+                    disabled: visible.is_empty(),
+                    onchange: move |event| {
+                        let mut next = app_state_for_all.selected(table);
+                        if event.checked() {
+                            next.extend(visible.iter().cloned());
+                        } else {
+                            next.retain(|id| !visible.contains(id));
+                        }
+                        app_state_for_all.set_selected(table, next);
+                    }
+                }
+                "Select all {shown} shown"
+            }
+            if !selected.is_empty() {
+                span { class: "row-subtitle", "{count} selected" }
+                if hidden > 0 {
+                    span { class: "row-subtitle", "{hidden} more hidden by the filter, left out of bulk actions" }
+                }
+                div { class: "button-row compact",
+                    for action in BulkAction::for_table(table) {
+                        {
+                            let app_state = app_state.clone();
+                            let action = *action;
+                            rsx! {
+                                button {
+                                    class: "button secondary",
+                                    disabled: is_running || count == 0,
+                                    onclick: move |_| app_state.request_bulk(table, action),
+                                    "{action.label()}"
+                                }
+                            }
+                        }
+                    }
+                    button {
+                        class: "button secondary",
+                        onclick: move |_| app_state_for_clear.set_selected(table, HashSet::new()),
+                        "Clear selection"
+                    }
+                }
+            }
+        }
+        BulkResults { table }
+    }
+}
+
+/// Checkbox that adds a row to, or removes it from, the selection of `table`.
+#[component]
+pub fn SelectBox(table: TableKind, id: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let checked = app_state.selected(table).contains(&id);
+
+    rsx! {
+        input {
+            r#type: "checkbox",
+            class: "row-checkbox",
+            checked,
+            onchange: move |_| app_state.toggle_selected(table, id.clone())
+        }
+    }
+}
+
+#[component]
+fn BulkResults(table: TableKind) -> Element {
+    let app_state = use_context::<AppState>();
+    let Some(run) = (app_state.bulk)().filter(|run| run.table == table) else {
+        return rsx! {};
+    };
+    let finished = run.finished();
+    let failed = run.failed();
+    let total = run.items.len();
+
+    rsx! {
+        div { class: "card details-card",
+            div { class: "file-browser-header",
+                div {
+                    h3 { "{run.action.label()} {total} {table.noun()}" }
+                    p { class: "row-subtitle",
+                        if run.is_running {
+                            "{finished} of {total} done"
+                        } else if failed > 0 {
+                            "{failed} failed, still selected so you can retry them"
+                        } else {
+                            "All done"
+                        }
+                    }
+                }
+                button {
+                    class: "button secondary",
+                    disabled: run.is_running,
+                    onclick: move |_| app_state.close_bulk_results(),
+                    "Close"
+                }
+            }
+            for item in run.items {
+                div { key: "{item.id}", class: "row item bulk-row",
+                    span { "{item.label}" }
+                    StatusPill {
+                        label: item.progress.label(),
+                        class_name: item.progress.css_class().to_string()
+                    }
+                }
+            }
+        }
+    }
+}
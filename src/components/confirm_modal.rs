@@ -0,0 +1,72 @@
+use dioxus::prelude::*;
+
+use crate::utils::AppState;
+
+/// Modal for the operation waiting in `AppState::confirmation`. Confirm stays
+/// disabled until the typed text matches, when safe mode asks for it.
+#[component]
+pub fn ConfirmModal() -> Element {
+    let app_state = use_context::<AppState>();
+    let mut typed = use_signal(String::new);
+    let Some(confirmation) = (app_state.confirmation)() else {
+        return rsx! {};
+    };
+    let can_proceed = confirmation.can_proceed(&typed());
+    let app_state_for_cancel = app_state.clone();
+
+    rsx! {
+        div { class: "modal-backdrop",
+            div { class: "modal", role: "dialog",
+                h3 { "{confirmation.title}" }
+                p { class: "row-subtitle", "{confirmation.message}" }
+                if !confirmation.items.is_empty() {
+                    ul { class: "modal-items",
+                        for item in confirmation.items.iter() {
+                            li { key: "{item}", "{item}" }
+                        }
+                    }
+                }
+                if !confirmation.blocked.is_empty() {
+                    div { class: "modal-blocked",
+                        p { "Safe mode leaves these protected containers alone:" }
+                        ul { class: "modal-items",
+                            for item in confirmation.blocked.iter() {
+                                li { key: "{item}", "{item}" }
+                            }
+                        }
+                    }
+                }
+                if let Some(expected) = confirmation.typed.clone() {
+                    if !confirmation.items.is_empty() {
+                        label { class: "form-label", "Type \"{expected}\" to confirm" }
+                        input {
+                            class: "text-input",
+                            value: "{typed}",
+                            placeholder: "{expected}",
+                            oninput: move |event| typed.set(event.value()),
+                        }
+                    }
+                }
+                div { class: "modal-actions",
+                    button {
+                        class: "button secondary",
+                        onclick: move |_| {
+                            typed.set(String::new());
+                            app_state_for_cancel.cancel_confirmation();
+                        },
+                        "Cancel"
+                    }
+                    button {
+                        class: "button danger",
+                        disabled: !can_proceed,
+                        onclick: move |_| {
+                            typed.set(String::new());
+                            app_state.confirm();
+                        },
+                        "Confirm"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,10 +1,31 @@
 //! Shared UI building blocks for the app shell and pages.
 
+mod activity_drawer;
+pub use activity_drawer::ActivityDrawer;
+
+mod bulk_bar;
+pub use bulk_bar::{BulkBar, SelectBox};
+
+mod confirm_modal;
+pub use confirm_modal::ConfirmModal;
+
 mod metric_card;
 pub use metric_card::MetricCard;
 
+mod port_links;
+pub use port_links::PortLinks;
+
+mod resource_status_bar;
+pub use resource_status_bar::{ResourceStatusBar, SkeletonRows};
+
 mod section_header;
 pub use section_header::SectionHeader;
 
 mod status_pill;
 pub use status_pill::StatusPill;
+
+mod table_toolbar;
+pub use table_toolbar::TableToolbar;
+
+mod toast_stack;
+pub use toast_stack::ToastStack;
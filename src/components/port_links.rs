@@ -0,0 +1,22 @@
+use dioxus::prelude::*;
+
+use crate::services::PublishedPort;
+
+/// A container's ports, with published TCP ports linking to the host URL.
+#[component]
+pub fn PortLinks(ports: Vec<PublishedPort>) -> Element {
+    rsx! {
+        div { class: "port-list",
+            if ports.is_empty() {
+                span { "--" }
+            }
+            for port in ports {
+                if let Some(url) = port.url() {
+                    a { class: "port-link", href: "{url}", title: "Open {url}", "{port}" }
+                } else {
+                    span { "{port}" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use dioxus::prelude::*;
+
+use crate::utils::ResourceStatus;
+
+/// Sits in a page's action bar: when the list was last loaded, or why it failed.
+#[component]
+pub fn ResourceStatusBar(status: ResourceStatus, on_retry: EventHandler<()>) -> Element {
+    match status.error.clone() {
+        Some(error) => rsx! {
+            div { class: "inline-error",
+                div { class: "error-body",
+                    p { class: "error-title", "⚠️ {error}" }
+                    p { class: "error-hint", "{error.hint()}" }
+                }
+                button {
+                    class: "button secondary",
+                    disabled: status.is_loading,
+                    onclick: move |_| on_retry.call(()),
+                    "Retry"
+                }
+            }
+        },
+        None => rsx! {
+            span { class: "row-subtitle refresh-label", "{status.label()}" }
+        },
+    }
+}
+
+/// Placeholder rows shown while a list loads for the first time.
+#[component]
+pub fn SkeletonRows(row_class: String, columns: usize) -> Element {
+    rsx! {
+        for row in 0..3 {
+            div { key: "{row}", class: "row item skeleton {row_class}",
+                for column in 0..columns {
+                    span { key: "{column}", class: "skeleton-bar" }
+                }
+            }
+        }
+    }
+}
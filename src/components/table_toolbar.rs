@@ -0,0 +1,136 @@
+use dioxus::prelude::*;
+
+use crate::utils::{AppState, TableKind, TableQuery};
+
+/// Search, state and label filters, sorting and saved presets for one resource table.
+/// The query lives in `AppState`, so it survives navigating away and back.
+#[component]
+pub fn TableToolbar(table: TableKind) -> Element {
+    let app_state = use_context::<AppState>();
+    let mut preset_name = use_signal(String::new);
+    let mut selected_preset = use_signal(String::new);
+
+    let query = app_state.query(table)();
+    let presets: Vec<_> = (app_state.presets)()
+        .into_iter()
+        .filter(|preset| preset.table == table)
+        .collect();
+
+    let update = {
+        let app_state = app_state.clone();
+        let query = query.clone();
+        move |change: &dyn Fn(&mut TableQuery)| {
+            let mut next = query.clone();
+            change(&mut next);
+            app_state.set_query(table, next);
+        }
+    };
+    let on_search = update.clone();
+    let on_state = update.clone();
+    let on_label = update.clone();
+    let on_sort = update.clone();
+    let on_direction = update.clone();
+    let app_state_for_clear = app_state.clone();
+    let app_state_for_apply = app_state.clone();
+    let app_state_for_save = app_state.clone();
+    let app_state_for_delete = app_state.clone();
+    let presets_for_apply = presets.clone();
+
+    rsx! {
+        div { class: "table-toolbar",
+            input {
+                class: "text-input search-input",
+                value: "{query.search}",
+                placeholder: "Search…",
+                oninput: move |event| on_search(&|query| query.search = event.value())
+            }
+            select {
+                class: "text-input",
+                value: query.state.clone().unwrap_or_default(),
+                onchange: move |event| {
+                    let value = event.value();
+                    on_state(&|query| query.state = (!value.is_empty()).then(|| value.clone()))
+                },
+                option { value: "", "All states" }
+                for state in table.states() {
+                    option { value: *state, "{state}" }
+                }
+            }
+            // Apply the label filter once typing stops, not on every half-typed key=value
+            input {
+                class: "text-input",
+                value: "{query.label}",
+                placeholder: "label or key=value",
+                onchange: move |event| on_label(&|query| query.label = event.value())
+            }
+            select {
+                class: "text-input",
+                value: query.sort_by.clone().unwrap_or_default(),
+                onchange: move |event| {
+                    let value = event.value();
+                    on_sort(&|query| query.sort_by = (!value.is_empty()).then(|| value.clone()))
+                },
+                option { value: "", "Unsorted" }
+                for (key, label) in table.columns() {
+                    option { value: *key, "Sort by {label}" }
+                }
+            }
+            button {
+                class: "button secondary",
+                disabled: query.sort_by.is_none(),
+                onclick: move |_| on_direction(&|query| query.direction = query.direction.toggled()),
+                "{query.direction.arrow()}"
+            }
+            button {
+                class: "button secondary",
+                disabled: query == TableQuery::default(),
+                onclick: move |_| app_state_for_clear.set_query(table, TableQuery::default()),
+                "Clear"
+            }
+        }
+        div { class: "table-toolbar presets",
+            select {
+                class: "text-input",
+                value: selected_preset(),
+                onchange: move |event| {
+                    let name = event.value();
+                    if let Some(preset) = presets_for_apply.iter().find(|preset| preset.name == name) {
+                        app_state_for_apply.set_query(table, preset.query.clone());
+                    }
+                    selected_preset.set(name);
+                },
+                option { value: "", "Presets" }
+                for preset in presets {
+                    option { value: "{preset.name}", "{preset.name}" }
+                }
+            }
+            if !selected_preset().is_empty() {
+                button {
+                    class: "button secondary",
+                    onclick: move |_| {
+                        app_state_for_delete.delete_preset(table, selected_preset());
+                        selected_preset.set(String::new());
+                    },
+                    "Delete preset"
+                }
+            }
+            input {
+                class: "text-input",
+                value: preset_name,
+                placeholder: "Preset name",
+                oninput: move |event| preset_name.set(event.value())
+            }
+            button {
+                class: "button secondary",
+                disabled: preset_name().trim().is_empty(),
+                onclick: move |_| {
+                    let name = preset_name().trim().to_string();
+                    app_state_for_save.save_preset(table, name.clone());
+                    selected_preset.set(name);
+                    preset_name.set(String::new());
+                },
+                "Save preset"
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use dioxus::prelude::*;
+
+use crate::utils::{AppState, Outcome};
+
+/// Pops up recent activity in the corner. Failures carry the hint and a Retry
+/// button and stay until dismissed; successes fade on their own.
+#[component]
+pub fn ToastStack() -> Element {
+    let app_state = use_context::<AppState>();
+    let activity = app_state.activity.read();
+    let toasts: Vec<_> = (app_state.toasts)()
+        .into_iter()
+        .filter_map(|id| activity.get(id).cloned())
+        .collect();
+
+    rsx! {
+        div { class: "toast-stack",
+            for entry in toasts {
+                match entry.outcome.clone() {
+                    Outcome::Succeeded => {
+                        let app_state = app_state.clone();
+                        rsx! {
+                            div { key: "{entry.id}", class: "toast success",
+                                p { "✓ {entry.summary()}" }
+                                button {
+                                    class: "toast-close",
+                                    onclick: move |_| app_state.dismiss_toast(entry.id),
+                                    "×"
+                                }
+                            }
+                        }
+                    }
+                    Outcome::Failed(report) => {
+                        let retry_state = app_state.clone();
+                        let dismiss_state = app_state.clone();
+                        let retry_report = report.clone();
+                        rsx! {
+                            div { key: "{entry.id}", class: "toast failure",
+                                div { class: "error-body",
+                                    p { class: "error-title", "⚠️ {report.action}" }
+                                    p { "{report.error}" }
+                                    p { class: "error-hint", "{report.error.hint()}" }
+                                }
+                                div { class: "button-row compact",
+                                    if report.can_retry() {
+                                        button {
+                                            class: "button secondary",
+                                            onclick: move |_| {
+                                                retry_state.dismiss_toast(entry.id);
+                                                retry_report.retry();
+                                            },
+                                            "Retry"
+                                        }
+                                    }
+                                    button {
+                                        class: "button secondary",
+                                        onclick: move |_| dismiss_state.dismiss_toast(entry.id),
+                                        "Dismiss"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -3,7 +3,10 @@
 use dioxus::prelude::*;
 
 use utils::AppState;
-use views::{AppShell, Containers, Dashboard, Images, Settings, Volumes};
+use views::{
+    AppShell, Compose, ContainerDetail, Containers, Dashboard, Images, Networks, Settings,
+    Topology, Volumes,
+};
 
 /// Define a components module that contains all shared components for our app.
 mod components;
@@ -29,10 +32,18 @@ enum Route {
         Dashboard {},
         #[route("/containers")]
         Containers {},
+        #[route("/containers/:id")]
+        ContainerDetail { id: String },
         #[route("/images")]
         Images {},
         #[route("/volumes")]
         Volumes {},
+        #[route("/networks")]
+        Networks {},
+        #[route("/networks/topology")]
+        Topology {},
+        #[route("/compose")]
+        Compose {},
         #[route("/settings")]
         Settings {},
 }
@@ -0,0 +1,441 @@
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Result;
+use bytes::Bytes;
+use futures_util::Stream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::docker::format_size;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileKind {
+    Directory,
+    File,
+    Symlink,
+    Other,
+}
+
+impl FileKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileKind::Directory => "Directory",
+            FileKind::File => "File",
+            FileKind::Symlink => "Symlink",
+            FileKind::Other => "Other",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileEntry {
+    pub name: String,
+    pub path: String,
+    pub kind: FileKind,
+    pub size: String,
+}
+
+/// Largest long-name or PAX record accepted before an archive is treated as corrupt.
+const MAX_EXTENSION_SIZE: u64 = 1 << 20;
+/// Size of a tar header and the unit contents are padded to.
+const BLOCK: usize = 512;
+/// Size of the chunks an upload archive is handed to the engine in.
+const UPLOAD_CHUNK: usize = 64 * 1024;
+
+/// Lists the direct children of a directory from its archive as the archive
+/// streams in.
+///
+/// The archive endpoint returns the requested path as the top-level entry, so
+/// children are the entries exactly one component below it, except when the
+/// root is archived: its children are the top-level entries. Only headers are
+/// kept; file contents are skipped as they arrive, so memory stays bounded by
+/// the number of children however large the directory is.
+pub struct DirectoryLister {
+    dir: String,
+    /// How many path components a child's archive path has.
+    depth: usize,
+    /// A header, or the data of a long-name or PAX entry, being assembled.
+    block: Vec<u8>,
+    /// Contents (plus padding) of the current entry still to be skipped.
+    skip: u64,
+    /// Set while reading the data of a long-name or PAX entry of this size.
+    extension: Option<(tar::EntryType, u64)>,
+    long_path: Option<String>,
+    pax_size: Option<u64>,
+    entries: Vec<FileEntry>,
+    finished: bool,
+}
+
+impl DirectoryLister {
+    /// Lists the archive of `archived`, giving children paths under the browser
+    /// path `dir`. The two differ when a volume is read through a helper mount.
+    pub fn new(archived: &str, dir: &str) -> Self {
+        Self {
+            dir: dir.to_string(),
+            depth: if archived.trim_end_matches('/').is_empty() {
+                1
+            } else {
+                2
+            },
+            block: Vec::with_capacity(BLOCK),
+            skip: 0,
+            extension: None,
+            long_path: None,
+            pax_size: None,
+            entries: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// Whether the end-of-archive marker has been read.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn feed(&mut self, mut chunk: &[u8]) -> Result<()> {
+        while !chunk.is_empty() && !self.finished {
+            if self.skip > 0 {
+                let skipped = chunk.len().min(self.skip as usize);
+                self.skip -= skipped as u64;
+                chunk = &chunk[skipped..];
+                continue;
+            }
+
+            let wanted = match self.extension {
+                Some((_, size)) => padded(size) as usize,
+                None => BLOCK,
+            };
+            let taken = chunk.len().min(wanted - self.block.len());
+            self.block.extend_from_slice(&chunk[..taken]);
+            chunk = &chunk[taken..];
+            if self.block.len() < wanted {
+                continue;
+            }
+
+            let block = std::mem::replace(&mut self.block, Vec::with_capacity(BLOCK));
+            match self.extension.take() {
+                Some((kind, size)) => self.read_extension(kind, &block[..size as usize]),
+                None => self.read_header(&block)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// The children found so far, directories first.
+    pub fn finish(mut self) -> Vec<FileEntry> {
+        self.entries.sort_by(|a, b| {
+            (a.kind != FileKind::Directory)
+                .cmp(&(b.kind != FileKind::Directory))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        self.entries
+    }
+
+    fn read_header(&mut self, block: &[u8]) -> Result<()> {
+        // The archive ends with zeroed blocks
+        if block.iter().all(|byte| *byte == 0) {
+            self.finished = true;
+            return Ok(());
+        }
+
+        let header = tar::Header::from_byte_slice(block);
+        let entry_type = header.entry_type();
+        let size = self
+            .pax_size
+            .take()
+            .map_or_else(|| header.entry_size(), Ok)?;
+
+        if entry_type.is_gnu_longname() || entry_type.is_pax_local_extensions() {
+            if size > MAX_EXTENSION_SIZE {
+                anyhow::bail!("Archive has a {} byte extended header", size);
+            }
+            self.extension = Some((entry_type, size));
+            return Ok(());
+        }
+        self.skip = padded(size);
+        if entry_type.is_gnu_longlink() || entry_type.is_pax_global_extensions() {
+            return Ok(());
+        }
+
+        let path = match self.long_path.take() {
+            Some(path) => path,
+            None => header.path()?.to_string_lossy().into_owned(),
+        };
+        let components: Vec<String> = Path::new(&path)
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+
+        if components.len() != self.depth {
+            return Ok(());
+        }
+
+        let name = components[self.depth - 1].clone();
+        let kind = match entry_type {
+            tar::EntryType::Directory => FileKind::Directory,
+            tar::EntryType::Regular | tar::EntryType::Continuous => FileKind::File,
+            tar::EntryType::Symlink | tar::EntryType::Link => FileKind::Symlink,
+            _ => FileKind::Other,
+        };
+        let size = if kind == FileKind::Directory {
+            "--".to_string()
+        } else {
+            format_size(size as i64)
+        };
+
+        self.entries.push(FileEntry {
+            path: join_path(&self.dir, &name),
+            name,
+            kind,
+            size,
+        });
+        Ok(())
+    }
+
+    fn read_extension(&mut self, kind: tar::EntryType, data: &[u8]) {
+        if kind.is_gnu_longname() {
+            let name = data.split(|byte| *byte == 0).next().unwrap_or_default();
+            self.long_path = Some(String::from_utf8_lossy(name).into_owned());
+            return;
+        }
+        for extension in tar::PaxExtensions::new(data).flatten() {
+            match extension.key() {
+                Ok("path") => {
+                    self.long_path =
+                        Some(String::from_utf8_lossy(extension.value_bytes()).into_owned())
+                }
+                Ok("size") => {
+                    self.pax_size = extension.value().ok().and_then(|value| value.parse().ok())
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Rounds an entry's size up to whole blocks.
+fn padded(size: u64) -> u64 {
+    size.div_ceil(BLOCK as u64) * BLOCK as u64
+}
+
+/// Wraps local files into a tar archive suitable for the upload endpoint, each
+/// at the top level under its own name.
+///
+/// The archive is built on a blocking thread and streamed out in chunks as the
+/// files are read, so an upload never holds a whole file in memory. The handle
+/// reports whether every file made it into the archive.
+pub fn archive_files(
+    sources: Vec<PathBuf>,
+) -> (
+    impl Stream<Item = Bytes> + Send + 'static,
+    JoinHandle<Result<()>>,
+) {
+    let (sender, receiver) = mpsc::channel(4);
+    let building = tokio::task::spawn_blocking(move || {
+        let mut builder = tar::Builder::new(ChunkWriter::new(sender));
+        for source in &sources {
+            let name = source
+                .file_name()
+                .ok_or_else(|| anyhow::anyhow!("{} is not a file", source.display()))?;
+            let mut file = std::fs::File::open(source)?;
+
+            let mut header = tar::Header::new_gnu();
+            header.set_size(file.metadata()?.len());
+            header.set_mode(0o644);
+            builder.append_data(&mut header, name, &mut file)?;
+        }
+        builder.into_inner()?.flush()?;
+        Ok(())
+    });
+    let chunks = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    });
+    (chunks, building)
+}
+
+/// Collects written bytes into chunks of `UPLOAD_CHUNK` and sends them on.
+struct ChunkWriter {
+    sender: mpsc::Sender<Bytes>,
+    chunk: Vec<u8>,
+}
+
+impl ChunkWriter {
+    fn new(sender: mpsc::Sender<Bytes>) -> Self {
+        Self {
+            sender,
+            chunk: Vec::with_capacity(UPLOAD_CHUNK),
+        }
+    }
+
+    fn send(&mut self) -> std::io::Result<()> {
+        if self.chunk.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::replace(&mut self.chunk, Vec::with_capacity(UPLOAD_CHUNK));
+        self.sender
+            .blocking_send(Bytes::from(chunk))
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe))
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let taken = buf.len().min(UPLOAD_CHUNK - self.chunk.len());
+        self.chunk.extend_from_slice(&buf[..taken]);
+        if self.chunk.len() == UPLOAD_CHUNK {
+            self.send()?;
+        }
+        Ok(taken)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.send()
+    }
+}
+
+/// Joins a browser path (always absolute, `/` being the root) with a child name.
+pub fn join_path(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir.trim_end_matches('/'), name)
+}
+
+/// Returns the parent of a browser path, stopping at the root.
+pub fn parent_path(path: &str) -> String {
+    match path.trim_end_matches('/').rsplit_once('/') {
+        Some(("", _)) | None => "/".to_string(),
+        Some((parent, _)) => parent.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an archive of `dirs` (ending in `/`) and `files`, in that order.
+    fn archive(dirs: &[&str], files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for dir in dirs {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Directory);
+            header.set_size(0);
+            builder
+                .append_data(&mut header, dir, std::io::empty())
+                .unwrap();
+        }
+        for (path, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            builder
+                .append_data(&mut header, path, contents.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    /// Feeds `archive` in small chunks, the way the engine streams it.
+    fn stream_listing(archive: &[u8], archived: &str, dir: &str) -> Vec<FileEntry> {
+        let mut lister = DirectoryLister::new(archived, dir);
+        for chunk in archive.chunks(100) {
+            lister.feed(chunk).unwrap();
+        }
+        assert!(lister.is_finished());
+        lister.finish()
+    }
+
+    #[test]
+    fn list_directory_returns_direct_children_only() {
+        let long_name = format!("data/{}.txt", "l".repeat(120));
+        let archive = archive(
+            &["data/", "data/nested/"],
+            &[
+                ("data/seed.sql", "select 1;"),
+                ("data/nested/deep.txt", "x"),
+                (long_name.as_str(), "long"),
+            ],
+        );
+        let entries = stream_listing(&archive, "/mnt/volume/data", "/data");
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name, "nested");
+        assert_eq!(entries[0].kind, FileKind::Directory);
+        assert_eq!(entries[1].name.len(), 124);
+        assert_eq!(entries[1].size, "4B");
+        assert_eq!(entries[2].path, "/data/seed.sql");
+        assert_eq!(entries[2].size, "9B");
+    }
+
+    #[test]
+    fn list_directory_handles_root_and_nested_paths() {
+        let root = archive(
+            &["bin/", "etc/"],
+            &[
+                ("bin/sh", "#!"),
+                ("etc/hosts", "localhost"),
+                (".dockerenv", ""),
+            ],
+        );
+        let entries = stream_listing(&root, "/", "/");
+        let paths: Vec<&str> = entries.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(paths, vec!["/bin", "/etc", "/.dockerenv"]);
+
+        let nested = archive(
+            &["lib/", "lib/apt/", "lib/apt/lists/"],
+            &[("lib/apt/lists/lock", ""), ("lib/dpkg", "status")],
+        );
+        let entries = stream_listing(&nested, "/var/lib", "/var/lib");
+        let paths: Vec<&str> = entries.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(paths, vec!["/var/lib/apt", "/var/lib/dpkg"]);
+    }
+
+    #[tokio::test]
+    async fn uploads_stream_files_into_an_archive() {
+        use futures_util::StreamExt;
+
+        let dir = std::env::temp_dir().join(format!("doctainr-upload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let big = dir.join("big.bin");
+        let small = dir.join("notes.txt");
+        std::fs::write(&big, vec![7u8; UPLOAD_CHUNK * 3 + 5]).unwrap();
+        std::fs::write(&small, "hello").unwrap();
+
+        let (chunks, building) = archive_files(vec![big, small]);
+        let chunks: Vec<Bytes> = chunks.collect().await;
+        building.await.unwrap().unwrap();
+        assert!(chunks.iter().all(|chunk| chunk.len() <= UPLOAD_CHUNK));
+
+        let archive = chunks.concat();
+        let mut archive = tar::Archive::new(archive.as_slice());
+        let entries: Vec<(String, u64)> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let entry = entry.unwrap();
+                let path = entry.path().unwrap().to_string_lossy().into_owned();
+                (path, entry.size())
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("big.bin".to_string(), (UPLOAD_CHUNK * 3 + 5) as u64),
+                ("notes.txt".to_string(), 5),
+            ]
+        );
+
+        let (_, building) = archive_files(vec![dir.join("missing")]);
+        assert!(building.await.unwrap().is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn browser_paths_stay_rooted() {
+        assert_eq!(join_path("/", "etc"), "/etc");
+        assert_eq!(join_path("/etc", "hosts"), "/etc/hosts");
+        assert_eq!(parent_path("/etc/hosts"), "/etc");
+        assert_eq!(parent_path("/etc"), "/");
+        assert_eq!(parent_path("/"), "/");
+    }
+}
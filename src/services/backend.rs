@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::archive::FileEntry;
+use super::changes::PathChange;
+use super::compose_file::{ComposeProject, ServiceDefinition};
+use super::create::ContainerSpec;
+use super::disk_usage::DiskUsage;
+use super::docker::{ContainerInfo, DockerService, ImageInfo, VolumeInfo};
+use super::engine::EngineInfo;
+use super::fake::FakeEngine;
+use super::health::ContainerHealth;
+use super::limits::ContainerLimits;
+use super::networks::{NetworkDetails, NetworkInfo, NetworkSpec};
+use super::ports::PublishedPort;
+use super::processes::ProcessList;
+use super::snapshot::CommitSpec;
+use super::topology::NetworkTopology;
+
+/// Everything `AppState` asks of a Docker engine.
+///
+/// `DockerService` talks to a real daemon; `FakeEngine` keeps state in memory so the
+/// app can be demoed and tested without one. Every operation is required, so a
+/// backend that misses one does not compile.
+#[async_trait]
+pub trait DockerBackend: Send + Sync {
+    /// Where the backend is connected, shown in the UI.
+    fn host(&self) -> String;
+
+    async fn engine_info(&self) -> Result<EngineInfo>;
+    async fn disk_usage(&self) -> Result<DiskUsage>;
+
+    async fn list_containers(&self) -> Result<Vec<ContainerInfo>>;
+    async fn list_images(&self) -> Result<Vec<ImageInfo>>;
+    async fn list_volumes(&self) -> Result<Vec<VolumeInfo>>;
+    async fn list_networks(&self) -> Result<Vec<NetworkInfo>>;
+    /// The host ports each of `ids` was created with, which the container list
+    /// only reports while a container runs.
+    async fn port_bindings(&self, ids: &[String]) -> Result<HashMap<String, Vec<PublishedPort>>>;
+
+    async fn start_container(&self, id: &str) -> Result<()>;
+    async fn stop_container(&self, id: &str) -> Result<()>;
+    async fn restart_container(&self, id: &str) -> Result<()>;
+    async fn remove_container(&self, id: &str) -> Result<()>;
+    async fn remove_image(&self, id: &str) -> Result<()>;
+    async fn remove_volume(&self, name: &str) -> Result<()>;
+
+    async fn inspect_network(&self, id: &str) -> Result<NetworkDetails>;
+    async fn network_topology(&self) -> Result<NetworkTopology>;
+    async fn create_network(&self, spec: &NetworkSpec) -> Result<String>;
+    async fn remove_network(&self, id: &str) -> Result<()>;
+    async fn connect_network(
+        &self,
+        network: &str,
+        container: &str,
+        aliases: Vec<String>,
+    ) -> Result<()>;
+    async fn disconnect_network(&self, network: &str, container: &str) -> Result<()>;
+
+    async fn list_volume_files(&self, volume: &str, path: &str) -> Result<Vec<FileEntry>>;
+    async fn download_volume_path(
+        &self,
+        volume: &str,
+        path: &str,
+        destination: &Path,
+    ) -> Result<u64>;
+    async fn upload_to_volume(&self, volume: &str, dir: &str, sources: Vec<PathBuf>) -> Result<()>;
+    async fn list_container_files(&self, id: &str, path: &str) -> Result<Vec<FileEntry>>;
+    async fn download_container_path(
+        &self,
+        id: &str,
+        path: &str,
+        destination: &Path,
+    ) -> Result<u64>;
+    async fn upload_to_container(&self, id: &str, dir: &str, sources: Vec<PathBuf>) -> Result<()>;
+
+    async fn container_changes(&self, id: &str) -> Result<Vec<PathChange>>;
+    async fn commit_container(&self, id: &str, spec: &CommitSpec) -> Result<String>;
+    async fn export_container(&self, id: &str, destination: &Path) -> Result<u64>;
+    async fn top_processes(&self, id: &str, ps_args: &str) -> Result<ProcessList>;
+    async fn container_limits(&self, id: &str) -> Result<ContainerLimits>;
+    async fn update_limits(
+        &self,
+        id: &str,
+        current: &ContainerLimits,
+        limits: &ContainerLimits,
+    ) -> Result<()>;
+    async fn container_spec(&self, id: &str) -> Result<ContainerSpec>;
+    async fn create_container(&self, spec: &ContainerSpec) -> Result<String>;
+    async fn rename_container(&self, id: &str, name: &str) -> Result<()>;
+    async fn container_health(&self, id: &str) -> Result<ContainerHealth>;
+
+    async fn compose_prepare(&self, project: &ComposeProject) -> Result<()>;
+    async fn compose_up_service(
+        &self,
+        project: &ComposeProject,
+        service: &ServiceDefinition,
+    ) -> Result<&'static str>;
+    async fn compose_down_service(&self, service: &ServiceDefinition) -> Result<&'static str>;
+    async fn compose_restart_service(&self, service: &ServiceDefinition) -> Result<&'static str>;
+    async fn compose_teardown(&self, project: &ComposeProject) -> Result<()>;
+}
+
+/// Environment variable that picks the backend at startup; `fake` selects the
+/// in-memory engine, anything else (or nothing) the local Docker daemon.
+pub const ENGINE_ENV: &str = "DOCTAINR_ENGINE";
+
+pub fn connect() -> Result<Arc<dyn DockerBackend>> {
+    match std::env::var(ENGINE_ENV).as_deref() {
+        Ok("fake") => Ok(Arc::new(FakeEngine::demo()?)),
+        _ => Ok(Arc::new(DockerService::new()?)),
+    }
+}
+
+// Each call names the inherent method through `DockerService::` instead of relying
+// on method-call precedence over the trait method being defined.
+#[async_trait]
+impl DockerBackend for DockerService {
+    fn host(&self) -> String {
+        std::env::var("DOCKER_HOST").unwrap_or_else(|_| "unix:///var/run/docker.sock".to_string())
+    }
+
+    async fn engine_info(&self) -> Result<EngineInfo> {
+        DockerService::engine_info(self).await
+    }
+
+    async fn disk_usage(&self) -> Result<DiskUsage> {
+        DockerService::disk_usage(self).await
+    }
+
+    async fn list_containers(&self) -> Result<Vec<ContainerInfo>> {
+        DockerService::list_containers(self).await
+    }
+
+    async fn list_images(&self) -> Result<Vec<ImageInfo>> {
+        DockerService::list_images(self).await
+    }
+
+    async fn list_volumes(&self) -> Result<Vec<VolumeInfo>> {
+        DockerService::list_volumes(self).await
+    }
+
+    async fn list_networks(&self) -> Result<Vec<NetworkInfo>> {
+        DockerService::list_networks(self).await
+    }
+
+    async fn start_container(&self, id: &str) -> Result<()> {
+        DockerService::start_container(self, id).await
+    }
+
+    async fn stop_container(&self, id: &str) -> Result<()> {
+        DockerService::stop_container(self, id).await
+    }
+
+    async fn restart_container(&self, id: &str) -> Result<()> {
+        DockerService::restart_container(self, id).await
+    }
+
+    async fn remove_container(&self, id: &str) -> Result<()> {
+        DockerService::remove_container(self, id).await
+    }
+
+    async fn remove_image(&self, id: &str) -> Result<()> {
+        DockerService::remove_image(self, id).await
+    }
+
+    async fn remove_volume(&self, name: &str) -> Result<()> {
+        DockerService::remove_volume(self, name).await
+    }
+
+    async fn inspect_network(&self, id: &str) -> Result<NetworkDetails> {
+        DockerService::inspect_network(self, id).await
+    }
+
+    async fn network_topology(&self) -> Result<NetworkTopology> {
+        DockerService::network_topology(self).await
+    }
+
+    async fn port_bindings(&self, ids: &[String]) -> Result<HashMap<String, Vec<PublishedPort>>> {
+        DockerService::port_bindings(self, ids).await
+    }
+
+    async fn create_network(&self, spec: &NetworkSpec) -> Result<String> {
+        DockerService::create_network(self, spec).await
+    }
+
+    async fn remove_network(&self, id: &str) -> Result<()> {
+        DockerService::remove_network(self, id).await
+    }
+
+    async fn connect_network(
+        &self,
+        network: &str,
+        container: &str,
+        aliases: Vec<String>,
+    ) -> Result<()> {
+        DockerService::connect_network(self, network, container, aliases).await
+    }
+
+    async fn disconnect_network(&self, network: &str, container: &str) -> Result<()> {
+        DockerService::disconnect_network(self, network, container).await
+    }
+
+    async fn list_volume_files(&self, volume: &str, path: &str) -> Result<Vec<FileEntry>> {
+        DockerService::list_volume_files(self, volume, path).await
+    }
+
+    async fn download_volume_path(
+        &self,
+        volume: &str,
+        path: &str,
+        destination: &Path,
+    ) -> Result<u64> {
+        DockerService::download_volume_path(self, volume, path, destination).await
+    }
+
+    async fn upload_to_volume(&self, volume: &str, dir: &str, sources: Vec<PathBuf>) -> Result<()> {
+        DockerService::upload_to_volume(self, volume, dir, sources).await
+    }
+
+    async fn list_container_files(&self, id: &str, path: &str) -> Result<Vec<FileEntry>> {
+        DockerService::list_container_files(self, id, path).await
+    }
+
+    async fn download_container_path(
+        &self,
+        id: &str,
+        path: &str,
+        destination: &Path,
+    ) -> Result<u64> {
+        DockerService::download_container_path(self, id, path, destination).await
+    }
+
+    async fn upload_to_container(&self, id: &str, dir: &str, sources: Vec<PathBuf>) -> Result<()> {
+        DockerService::upload_to_container(self, id, dir, sources).await
+    }
+
+    async fn container_changes(&self, id: &str) -> Result<Vec<PathChange>> {
+        DockerService::container_changes(self, id).await
+    }
+
+    async fn commit_container(&self, id: &str, spec: &CommitSpec) -> Result<String> {
+        DockerService::commit_container(self, id, spec).await
+    }
+
+    async fn export_container(&self, id: &str, destination: &Path) -> Result<u64> {
+        DockerService::export_container(self, id, destination).await
+    }
+
+    async fn top_processes(&self, id: &str, ps_args: &str) -> Result<ProcessList> {
+        DockerService::top_processes(self, id, ps_args).await
+    }
+
+    async fn container_limits(&self, id: &str) -> Result<ContainerLimits> {
+        DockerService::container_limits(self, id).await
+    }
+
+    async fn update_limits(
+        &self,
+        id: &str,
+        current: &ContainerLimits,
+        limits: &ContainerLimits,
+    ) -> Result<()> {
+        DockerService::update_limits(self, id, current, limits).await
+    }
+
+    async fn container_spec(&self, id: &str) -> Result<ContainerSpec> {
+        DockerService::container_spec(self, id).await
+    }
+
+    async fn create_container(&self, spec: &ContainerSpec) -> Result<String> {
+        DockerService::create_container(self, spec).await
+    }
+
+    async fn rename_container(&self, id: &str, name: &str) -> Result<()> {
+        DockerService::rename_container(self, id, name).await
+    }
+
+    async fn container_health(&self, id: &str) -> Result<ContainerHealth> {
+        DockerService::container_health(self, id).await
+    }
+
+    async fn compose_prepare(&self, project: &ComposeProject) -> Result<()> {
+        DockerService::compose_prepare(self, project).await
+    }
+
+    async fn compose_up_service(
+        &self,
+        project: &ComposeProject,
+        service: &ServiceDefinition,
+    ) -> Result<&'static str> {
+        DockerService::compose_up_service(self, project, service).await
+    }
+
+    async fn compose_down_service(&self, service: &ServiceDefinition) -> Result<&'static str> {
+        DockerService::compose_down_service(self, service).await
+    }
+
+    async fn compose_restart_service(&self, service: &ServiceDefinition) -> Result<&'static str> {
+        DockerService::compose_restart_service(self, service).await
+    }
+
+    async fn compose_teardown(&self, project: &ComposeProject) -> Result<()> {
+        DockerService::compose_teardown(self, project).await
+    }
+}
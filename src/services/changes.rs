@@ -0,0 +1,153 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use bollard::models::{ChangeType, FilesystemChange};
+
+use super::docker::DockerService;
+
+/// How a path in a container's writable layer differs from its image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+impl ChangeKind {
+    pub const ALL: [ChangeKind; 3] = [ChangeKind::Added, ChangeKind::Modified, ChangeKind::Deleted];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "Added",
+            ChangeKind::Modified => "Modified",
+            ChangeKind::Deleted => "Deleted",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Modified => "modified",
+            ChangeKind::Deleted => "deleted",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathChange {
+    pub path: String,
+    pub kind: ChangeKind,
+}
+
+/// One line of the changes tree. Directories that only lead to a matching
+/// change have no `kind` of their own.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangeRow {
+    pub path: String,
+    pub name: String,
+    pub depth: usize,
+    pub kind: Option<ChangeKind>,
+}
+
+/// Lays out the changes whose kind is in `kinds` as a tree, parents first,
+/// keeping the directories above them so every row has its context.
+pub fn change_rows(changes: &[PathChange], kinds: &HashSet<ChangeKind>) -> Vec<ChangeRow> {
+    let by_path: HashMap<&str, ChangeKind> = changes
+        .iter()
+        .map(|change| (change.path.as_str(), change.kind))
+        .collect();
+
+    let mut included: HashSet<&str> = HashSet::new();
+    for change in changes.iter().filter(|change| kinds.contains(&change.kind)) {
+        let mut path = change.path.as_str();
+        included.insert(path);
+        while let Some((parent, _)) = path.rsplit_once('/')
+            && !parent.is_empty()
+        {
+            included.insert(parent);
+            path = parent;
+        }
+    }
+
+    let mut paths: Vec<Vec<&str>> = included
+        .into_iter()
+        .map(|path| path.split('/').filter(|part| !part.is_empty()).collect())
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|parts| {
+            let path = format!("/{}", parts.join("/"));
+            ChangeRow {
+                kind: by_path.get(path.as_str()).copied(),
+                name: parts.last().copied().unwrap_or("/").to_string(),
+                depth: parts.len().saturating_sub(1),
+                path,
+            }
+        })
+        .collect()
+}
+
+impl DockerService {
+    pub async fn container_changes(&self, id: &str) -> Result<Vec<PathChange>> {
+        let changes = self.docker.container_changes(id).await?;
+        Ok(changes
+            .unwrap_or_default()
+            .into_iter()
+            .map(path_change)
+            .collect())
+    }
+}
+
+pub(super) fn path_change(change: FilesystemChange) -> PathChange {
+    let kind = match change.kind {
+        ChangeType::_0 => ChangeKind::Modified,
+        ChangeType::_1 => ChangeKind::Added,
+        ChangeType::_2 => ChangeKind::Deleted,
+    };
+    PathChange {
+        path: change.path,
+        kind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_keep_parents_of_filtered_changes() {
+        let changes = [
+            ("/var", ChangeKind::Modified),
+            ("/var/log", ChangeKind::Modified),
+            ("/var/log/app.log", ChangeKind::Added),
+            ("/etc/motd", ChangeKind::Deleted),
+            ("/tmp", ChangeKind::Added),
+        ]
+        .map(|(path, kind)| PathChange {
+            path: path.to_string(),
+            kind,
+        });
+
+        let added = change_rows(&changes, &HashSet::from([ChangeKind::Added]));
+        let lines: Vec<_> = added
+            .iter()
+            .map(|row| (row.depth, row.name.as_str(), row.kind))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (0, "tmp", Some(ChangeKind::Added)),
+                (0, "var", Some(ChangeKind::Modified)),
+                (1, "log", Some(ChangeKind::Modified)),
+                (2, "app.log", Some(ChangeKind::Added)),
+            ]
+        );
+
+        let deleted = change_rows(&changes, &HashSet::from([ChangeKind::Deleted]));
+        assert_eq!(deleted[0].path, "/etc");
+        assert_eq!(deleted[0].kind, None);
+        assert_eq!(deleted[1].path, "/etc/motd");
+    }
+}
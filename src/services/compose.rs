@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use bollard::container::{
+    Config, CreateContainerOptions, InspectContainerOptions, NetworkingConfig,
+    RemoveContainerOptions, StartContainerOptions, StopContainerOptions,
+};
+use bollard::models::{EndpointSettings, HostConfig, PortBinding, PortMap};
+use bollard::network::{CreateNetworkOptions, InspectNetworkOptions};
+use bollard::volume::CreateVolumeOptions;
+
+use super::compose_file::{ComposePort, ComposeProject, ServiceDefinition};
+use super::docker::{ContainerInfo, ContainerState, DockerService};
+
+/// Label Compose sets on every container it creates with the project name.
+pub const PROJECT_LABEL: &str = "com.docker.compose.project";
+/// Label Compose sets with the service a container belongs to.
+pub const SERVICE_LABEL: &str = "com.docker.compose.service";
+
+/// Label Compose sets on networks and volumes it creates for a project.
+pub(super) const NETWORK_LABEL: &str = "com.docker.compose.network";
+pub(super) const VOLUME_LABEL: &str = "com.docker.compose.volume";
+
+/// Containers sharing a Compose project; `project` is `None` for standalone containers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContainerGroup {
+    pub project: Option<String>,
+    pub containers: Vec<ContainerInfo>,
+}
+
+impl ContainerGroup {
+    pub fn running(&self) -> usize {
+        self.containers
+            .iter()
+            .filter(|container| container.state == ContainerState::Running)
+            .count()
+    }
+}
+
+/// Groups containers by Compose project, projects sorted by name and standalone
+/// containers last. Containers inside a project are ordered by service name.
+pub fn group_by_project(containers: &[ContainerInfo]) -> Vec<ContainerGroup> {
+    let mut projects: HashMap<String, Vec<ContainerInfo>> = HashMap::new();
+    let mut standalone = Vec::new();
+
+    for container in containers {
+        match &container.compose_project {
+            Some(project) => projects
+                .entry(project.clone())
+                .or_default()
+                .push(container.clone()),
+            None => standalone.push(container.clone()),
+        }
+    }
+
+    let mut groups: Vec<ContainerGroup> = projects
+        .into_iter()
+        .map(|(project, mut containers)| {
+            containers.sort_by(|a, b| {
+                a.compose_service
+                    .cmp(&b.compose_service)
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+            ContainerGroup {
+                project: Some(project),
+                containers,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| a.project.cmp(&b.project));
+
+    if !standalone.is_empty() {
+        groups.push(ContainerGroup {
+            project: None,
+            containers: standalone,
+        });
+    }
+
+    groups
+}
+
+impl DockerService {
+    /// Creates the project's networks and named volumes that do not exist yet.
+    pub async fn compose_prepare(&self, project: &ComposeProject) -> Result<()> {
+        for network in project.networks.iter().filter(|network| !network.external) {
+            let exists = self
+                .docker
+                .inspect_network(&network.name, None::<InspectNetworkOptions<String>>)
+                .await
+                .is_ok();
+            if exists {
+                continue;
+            }
+            let options = CreateNetworkOptions {
+                name: network.name.clone(),
+                driver: network
+                    .driver
+                    .clone()
+                    .unwrap_or_else(|| "bridge".to_string()),
+                check_duplicate: true,
+                labels: HashMap::from([
+                    (PROJECT_LABEL.to_string(), project.name.clone()),
+                    (NETWORK_LABEL.to_string(), network.key.clone()),
+                ]),
+                ..Default::default()
+            };
+            self.docker.create_network(options).await?;
+        }
+
+        for volume in project.volumes.iter().filter(|volume| !volume.external) {
+            if self.docker.inspect_volume(&volume.name).await.is_ok() {
+                continue;
+            }
+            let options = CreateVolumeOptions {
+                name: volume.name.clone(),
+                driver: volume.driver.clone().unwrap_or_else(|| "local".to_string()),
+                labels: HashMap::from([
+                    (PROJECT_LABEL.to_string(), project.name.clone()),
+                    (VOLUME_LABEL.to_string(), volume.key.clone()),
+                ]),
+                ..Default::default()
+            };
+            self.docker.create_volume(options).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Brings one service up and returns a short description of what happened.
+    /// Existing containers are started as they are, not recreated.
+    pub async fn compose_up_service(
+        &self,
+        project: &ComposeProject,
+        service: &ServiceDefinition,
+    ) -> Result<&'static str> {
+        if let Ok(existing) = self
+            .docker
+            .inspect_container(&service.container_name, None::<InspectContainerOptions>)
+            .await
+        {
+            let running = existing
+                .state
+                .and_then(|state| state.running)
+                .unwrap_or(false);
+            if running {
+                return Ok("Already running");
+            }
+            self.start_container(&service.container_name).await?;
+            return Ok("Started");
+        }
+
+        self.ensure_image(&service.image).await?;
+
+        let options = Some(CreateContainerOptions {
+            name: service.container_name.clone(),
+            platform: None,
+        });
+        self.docker
+            .create_container(options, service_config(project, service))
+            .await?;
+
+        // Only one network can be attached at creation; join the rest before starting
+        for (network, aliases) in service.networks.iter().skip(1) {
+            self.connect_network(network, &service.container_name, aliases.clone())
+                .await?;
+        }
+
+        self.docker
+            .start_container(
+                &service.container_name,
+                None::<StartContainerOptions<String>>,
+            )
+            .await?;
+        Ok("Created and started")
+    }
+
+    /// Stops and removes a service's container; named volumes are kept.
+    pub async fn compose_down_service(&self, service: &ServiceDefinition) -> Result<&'static str> {
+        if self
+            .docker
+            .inspect_container(&service.container_name, None::<InspectContainerOptions>)
+            .await
+            .is_err()
+        {
+            return Ok("Not created");
+        }
+
+        self.docker
+            .stop_container(&service.container_name, None::<StopContainerOptions>)
+            .await?;
+        self.docker
+            .remove_container(
+                &service.container_name,
+                Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        Ok("Removed")
+    }
+
+    pub async fn compose_restart_service(
+        &self,
+        service: &ServiceDefinition,
+    ) -> Result<&'static str> {
+        self.restart_container(&service.container_name).await?;
+        Ok("Restarted")
+    }
+
+    /// Removes the networks `compose_prepare` created once every service is down.
+    pub async fn compose_teardown(&self, project: &ComposeProject) -> Result<()> {
+        for network in project.networks.iter().filter(|network| !network.external) {
+            let exists = self
+                .docker
+                .inspect_network(&network.name, None::<InspectNetworkOptions<String>>)
+                .await
+                .is_ok();
+            if exists {
+                self.remove_network(&network.name).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Exposed ports and host bindings for `ports`, in the shape `Config` expects.
+pub(super) fn port_config(ports: &[ComposePort]) -> (HashMap<String, HashMap<(), ()>>, PortMap) {
+    let exposed_ports = ports
+        .iter()
+        .map(|port| (port.key(), HashMap::new()))
+        .collect();
+
+    let mut port_bindings: PortMap = HashMap::new();
+    for port in ports {
+        port_bindings
+            .entry(port.key())
+            .or_insert_with(|| Some(Vec::new()))
+            .get_or_insert_with(Vec::new)
+            .push(PortBinding {
+                host_ip: port.host_ip.clone(),
+                host_port: port.published.clone(),
+            });
+    }
+    (exposed_ports, port_bindings)
+}
+
+pub(super) fn service_config(
+    project: &ComposeProject,
+    service: &ServiceDefinition,
+) -> Config<String> {
+    let mut labels = HashMap::from([
+        (PROJECT_LABEL.to_string(), project.name.clone()),
+        (SERVICE_LABEL.to_string(), service.name.clone()),
+        (
+            "com.docker.compose.container-number".to_string(),
+            "1".to_string(),
+        ),
+        ("com.docker.compose.oneoff".to_string(), "False".to_string()),
+    ]);
+    if let Some(dir) = project.file.parent() {
+        labels.insert(
+            "com.docker.compose.project.working_dir".to_string(),
+            dir.display().to_string(),
+        );
+    }
+
+    let (exposed_ports, port_bindings) = port_config(&service.ports);
+
+    let primary_network = service.networks.first();
+    let networking_config = primary_network.map(|(network, aliases)| NetworkingConfig {
+        endpoints_config: HashMap::from([(
+            network.clone(),
+            EndpointSettings {
+                aliases: Some(aliases.clone()),
+                ..Default::default()
+            },
+        )]),
+    });
+
+    Config {
+        image: Some(service.image.clone()),
+        cmd: service.command.clone(),
+        entrypoint: service.entrypoint.clone(),
+        env: (!service.environment.is_empty()).then(|| service.environment.clone()),
+        labels: Some(labels),
+        exposed_ports: (!exposed_ports.is_empty()).then_some(exposed_ports),
+        volumes: (!service.anonymous_volumes.is_empty()).then(|| {
+            service
+                .anonymous_volumes
+                .iter()
+                .map(|target| (target.clone(), HashMap::new()))
+                .collect()
+        }),
+        host_config: Some(HostConfig {
+            binds: (!service.binds.is_empty()).then(|| service.binds.clone()),
+            port_bindings: (!port_bindings.is_empty()).then_some(port_bindings),
+            network_mode: primary_network.map(|(network, _)| network.clone()),
+            restart_policy: service.restart.clone(),
+            ..Default::default()
+        }),
+        networking_config,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(name: &str, project: Option<&str>, service: Option<&str>) -> ContainerInfo {
+        ContainerInfo {
+            id: name.to_string(),
+            name: name.to_string(),
+            image: "nginx".to_string(),
+            status: "Up".to_string(),
+            ports: Vec::new(),
+            state: ContainerState::Running,
+            health: None,
+            exit_code: None,
+            compose_project: project.map(str::to_string),
+            compose_service: service.map(str::to_string),
+            labels: HashMap::new(),
+            volumes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn groups_projects_before_standalone_containers() {
+        let containers = vec![
+            container("loose", None, None),
+            container("shop-web-1", Some("shop"), Some("web")),
+            container("blog-db-1", Some("blog"), Some("db")),
+            container("shop-db-1", Some("shop"), Some("db")),
+        ];
+
+        let groups = group_by_project(&containers);
+
+        let projects: Vec<_> = groups.iter().map(|group| group.project.clone()).collect();
+        assert_eq!(
+            projects,
+            vec![Some("blog".to_string()), Some("shop".to_string()), None]
+        );
+        assert_eq!(groups[1].containers[0].name, "shop-db-1");
+        assert_eq!(groups[1].running(), 2);
+    }
+}
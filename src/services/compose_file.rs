@@ -0,0 +1,792 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow, bail};
+use bollard::models::{RestartPolicy, RestartPolicyNameEnum};
+use serde::Deserialize;
+use serde_yaml::Value;
+
+/// A Compose file resolved against its project: names are prefixed, relative
+/// bind mounts are absolute and services are in dependency order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComposeProject {
+    pub name: String,
+    pub file: PathBuf,
+    pub services: Vec<ServiceDefinition>,
+    pub networks: Vec<ProjectResource>,
+    pub volumes: Vec<ProjectResource>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServiceDefinition {
+    pub name: String,
+    pub container_name: String,
+    pub image: String,
+    pub command: Option<Vec<String>>,
+    pub entrypoint: Option<Vec<String>>,
+    pub environment: Vec<String>,
+    pub ports: Vec<ComposePort>,
+    /// Bind and named-volume mounts in `source:target[:mode]` form.
+    pub binds: Vec<String>,
+    /// Anonymous volume targets.
+    pub anonymous_volumes: Vec<String>,
+    /// Engine network names with the aliases the service uses on each.
+    pub networks: Vec<(String, Vec<String>)>,
+    pub depends_on: Vec<String>,
+    pub restart: Option<RestartPolicy>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComposePort {
+    pub host_ip: Option<String>,
+    pub published: Option<String>,
+    pub target: String,
+    pub protocol: String,
+}
+
+impl ComposePort {
+    /// The `port/protocol` key the engine uses for exposed ports and bindings.
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.target, self.protocol)
+    }
+}
+
+impl std::fmt::Display for ComposePort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(host_ip) = &self.host_ip {
+            write!(f, "{}:", host_ip)?;
+        }
+        if let Some(published) = &self.published {
+            write!(f, "{}:", published)?;
+        }
+        write!(f, "{}/{}", self.target, self.protocol)
+    }
+}
+
+/// A top-level network or volume, with the name the engine knows it by.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectResource {
+    pub key: String,
+    pub name: String,
+    pub driver: Option<String>,
+    pub external: bool,
+}
+
+impl ComposeProject {
+    pub fn load(path: &Path) -> Result<Self> {
+        let yaml = std::fs::read_to_string(path)?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let default_name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "default".to_string());
+        let mut project = Self::parse(&yaml, dir, &default_name)?;
+        project.file = path.to_path_buf();
+        Ok(project)
+    }
+
+    pub fn parse(yaml: &str, dir: &Path, default_name: &str) -> Result<Self> {
+        let file: RawComposeFile = serde_yaml::from_str(yaml)?;
+        let name = normalize_project_name(file.name.as_deref().unwrap_or(default_name));
+
+        let resource = |key: &String, raw: &Option<RawResource>| {
+            let raw = raw.clone().unwrap_or_default();
+            ProjectResource {
+                key: key.clone(),
+                name: raw.name.unwrap_or_else(|| {
+                    if raw.external {
+                        key.clone()
+                    } else {
+                        format!("{}_{}", name, key)
+                    }
+                }),
+                driver: raw.driver,
+                external: raw.external,
+            }
+        };
+
+        let mut networks: Vec<ProjectResource> = file
+            .networks
+            .iter()
+            .map(|(key, raw)| resource(key, raw))
+            .collect();
+        let volumes: Vec<ProjectResource> = file
+            .volumes
+            .iter()
+            .map(|(key, raw)| resource(key, raw))
+            .collect();
+
+        let uses_default_network = file
+            .services
+            .values()
+            .any(|service| service.networks.keys().is_empty());
+        if uses_default_network && !networks.iter().any(|network| network.key == "default") {
+            networks.push(resource(&"default".to_string(), &None));
+        }
+
+        let mut services = BTreeMap::new();
+        for (service_name, raw) in &file.services {
+            let service = resolve_service(&name, dir, service_name, raw, &networks, &volumes)?;
+            services.insert(service_name.clone(), service);
+        }
+
+        let order = dependency_order(&services)?;
+        let services = order
+            .into_iter()
+            .filter_map(|service_name| services.remove(&service_name))
+            .collect();
+
+        Ok(Self {
+            name,
+            file: dir.join("compose.yaml"),
+            services,
+            networks,
+            volumes,
+        })
+    }
+}
+
+fn resolve_service(
+    project: &str,
+    dir: &Path,
+    name: &str,
+    raw: &RawService,
+    networks: &[ProjectResource],
+    volumes: &[ProjectResource],
+) -> Result<ServiceDefinition> {
+    let image = raw.image.clone().ok_or_else(|| {
+        anyhow!(
+            "service {} has no image; building images is not supported",
+            name
+        )
+    })?;
+
+    let ports = raw
+        .ports
+        .iter()
+        .map(RawPort::resolve)
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut binds = Vec::new();
+    let mut anonymous_volumes = Vec::new();
+    for volume in &raw.volumes {
+        let (source, target, mode) = volume.parts()?;
+        match source {
+            None => anonymous_volumes.push(target),
+            Some(source) => {
+                let source = if source.starts_with(['.', '/', '~']) {
+                    resolve_host_path(dir, &source)
+                } else {
+                    volumes
+                        .iter()
+                        .find(|volume| volume.key == source)
+                        .map(|volume| volume.name.clone())
+                        .ok_or_else(|| {
+                            anyhow!("service {} uses undeclared volume {}", name, source)
+                        })?
+                };
+                binds.push(match mode {
+                    Some(mode) => format!("{}:{}:{}", source, target, mode),
+                    None => format!("{}:{}", source, target),
+                });
+            }
+        }
+    }
+
+    let service_networks = if raw.networks.keys().is_empty() {
+        vec![("default".to_string(), Vec::new())]
+    } else {
+        raw.networks.entries()
+    };
+    let networks = service_networks
+        .into_iter()
+        .map(|(key, aliases)| {
+            let network = networks
+                .iter()
+                .find(|network| network.key == key)
+                .ok_or_else(|| anyhow!("service {} uses undeclared network {}", name, key))?;
+            // Compose always lets services reach each other by service name
+            let mut all_aliases = vec![name.to_string()];
+            all_aliases.extend(aliases);
+            Ok((network.name.clone(), all_aliases))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(ServiceDefinition {
+        name: name.to_string(),
+        container_name: raw
+            .container_name
+            .clone()
+            .unwrap_or_else(|| format!("{}-{}-1", project, name)),
+        image,
+        command: raw
+            .command
+            .clone()
+            .map(|command| command.into_args(name))
+            .transpose()?,
+        entrypoint: raw
+            .entrypoint
+            .clone()
+            .map(|entrypoint| entrypoint.into_args(name))
+            .transpose()?,
+        environment: raw.environment.entries(),
+        ports,
+        binds,
+        anonymous_volumes,
+        networks,
+        depends_on: raw.depends_on.keys(),
+        restart: raw
+            .restart
+            .as_deref()
+            .map(|restart| parse_restart(name, restart))
+            .transpose()?,
+    })
+}
+
+/// Orders services so each one comes after everything it depends on.
+/// Ties are broken by name to keep the order stable between runs.
+fn dependency_order(services: &BTreeMap<String, ServiceDefinition>) -> Result<Vec<String>> {
+    for service in services.values() {
+        if let Some(missing) = service
+            .depends_on
+            .iter()
+            .find(|dependency| !services.contains_key(*dependency))
+        {
+            bail!(
+                "service {} depends on unknown service {}",
+                service.name,
+                missing
+            );
+        }
+    }
+
+    let mut order = Vec::new();
+    let mut placed = BTreeSet::new();
+    while order.len() < services.len() {
+        let ready: Vec<String> = services
+            .values()
+            .filter(|service| !placed.contains(&service.name))
+            .filter(|service| service.depends_on.iter().all(|dep| placed.contains(dep)))
+            .map(|service| service.name.clone())
+            .collect();
+
+        if ready.is_empty() {
+            let remaining: Vec<&str> = services
+                .keys()
+                .filter(|name| !placed.contains(*name))
+                .map(String::as_str)
+                .collect();
+            bail!(
+                "dependency cycle between services: {}",
+                remaining.join(", ")
+            );
+        }
+
+        for name in ready {
+            placed.insert(name.clone());
+            order.push(name);
+        }
+    }
+    Ok(order)
+}
+
+fn normalize_project_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
+
+fn resolve_host_path(dir: &Path, source: &str) -> String {
+    if let (Some(rest), Ok(home)) = (source.strip_prefix("~/"), std::env::var("HOME")) {
+        return Path::new(&home).join(rest).display().to_string();
+    }
+    if source.starts_with('/') {
+        source.to_string()
+    } else {
+        dir.join(source.trim_start_matches("./"))
+            .display()
+            .to_string()
+    }
+}
+
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawComposeFile {
+    name: Option<String>,
+    #[serde(default)]
+    services: BTreeMap<String, RawService>,
+    #[serde(default)]
+    networks: BTreeMap<String, Option<RawResource>>,
+    #[serde(default)]
+    volumes: BTreeMap<String, Option<RawResource>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+struct RawResource {
+    name: Option<String>,
+    driver: Option<String>,
+    #[serde(default)]
+    external: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawService {
+    image: Option<String>,
+    container_name: Option<String>,
+    command: Option<RawCommand>,
+    entrypoint: Option<RawCommand>,
+    #[serde(default)]
+    environment: RawEnvironment,
+    #[serde(default)]
+    ports: Vec<RawPort>,
+    #[serde(default)]
+    volumes: Vec<RawVolume>,
+    #[serde(default)]
+    networks: RawServiceNetworks,
+    #[serde(default)]
+    depends_on: RawDependsOn,
+    restart: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum RawCommand {
+    Shell(String),
+    Exec(Vec<String>),
+}
+
+impl RawCommand {
+    /// The string form is split the way a shell would, keeping quoted arguments whole.
+    fn into_args(self, service: &str) -> Result<Vec<String>> {
+        match self {
+            RawCommand::Shell(command) => shell_words::split(&command).map_err(|e| {
+                anyhow!(
+                    "service {} has an invalid command {}: {}",
+                    service,
+                    command,
+                    e
+                )
+            }),
+            RawCommand::Exec(args) => Ok(args),
+        }
+    }
+}
+
+/// Parses a `restart` value: `no`, `always`, `unless-stopped` or `on-failure[:N]`.
+fn parse_restart(service: &str, value: &str) -> Result<RestartPolicy> {
+    let (policy, retries) = match value.split_once(':') {
+        Some((policy, retries)) => (policy, Some(retries)),
+        None => (value, None),
+    };
+    let name = match policy {
+        "no" => RestartPolicyNameEnum::NO,
+        "always" => RestartPolicyNameEnum::ALWAYS,
+        "unless-stopped" => RestartPolicyNameEnum::UNLESS_STOPPED,
+        "on-failure" => RestartPolicyNameEnum::ON_FAILURE,
+        _ => bail!(
+            "service {} has an unknown restart policy {}",
+            service,
+            value
+        ),
+    };
+    let maximum_retry_count = match retries {
+        None => None,
+        Some(retries) if name == RestartPolicyNameEnum::ON_FAILURE => {
+            Some(retries.parse::<u32>().map_err(|_| {
+                anyhow!(
+                    "service {} has an invalid retry count in {}",
+                    service,
+                    value
+                )
+            })? as i64)
+        }
+        Some(_) => bail!(
+            "service {} sets retries on {}, which only on-failure takes",
+            service,
+            value
+        ),
+    };
+    Ok(RestartPolicy {
+        name: Some(name),
+        maximum_retry_count,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawEnvironment {
+    List(Vec<String>),
+    Map(BTreeMap<String, Option<Value>>),
+}
+
+impl Default for RawEnvironment {
+    fn default() -> Self {
+        RawEnvironment::List(Vec::new())
+    }
+}
+
+impl RawEnvironment {
+    /// Entries without a value are taken from the host environment, as Compose does.
+    fn entries(&self) -> Vec<String> {
+        let from_host = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .map(|value| format!("{}={}", key, value))
+        };
+        match self {
+            RawEnvironment::List(entries) => entries
+                .iter()
+                .filter_map(|entry| {
+                    if entry.contains('=') {
+                        Some(entry.clone())
+                    } else {
+                        from_host(entry)
+                    }
+                })
+                .collect(),
+            RawEnvironment::Map(entries) => entries
+                .iter()
+                .filter_map(
+                    |(key, value)| match value.as_ref().and_then(scalar_to_string) {
+                        Some(value) => Some(format!("{}={}", key, value)),
+                        None => from_host(key),
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawPort {
+    Short(Value),
+    Long {
+        target: u16,
+        published: Option<Value>,
+        host_ip: Option<String>,
+        protocol: Option<String>,
+    },
+}
+
+impl RawPort {
+    fn resolve(&self) -> Result<ComposePort> {
+        match self {
+            RawPort::Long {
+                target,
+                published,
+                host_ip,
+                protocol,
+            } => Ok(ComposePort {
+                host_ip: host_ip.clone(),
+                published: published.as_ref().and_then(scalar_to_string),
+                target: target.to_string(),
+                protocol: protocol.clone().unwrap_or_else(|| "tcp".to_string()),
+            }),
+            RawPort::Short(value) => {
+                let spec = scalar_to_string(value)
+                    .ok_or_else(|| anyhow!("invalid port specification {:?}", value))?;
+                parse_port(&spec)
+            }
+        }
+    }
+}
+
+/// Parses the short port syntax: `[[host_ip:]published:]target[/protocol]`.
+pub(super) fn parse_port(spec: &str) -> Result<ComposePort> {
+    let (ports, protocol) = match spec.split_once('/') {
+        Some((ports, protocol)) => (ports, protocol.to_string()),
+        None => (spec, "tcp".to_string()),
+    };
+    if ports.contains('-') {
+        bail!("port ranges are not supported: {}", spec);
+    }
+
+    let parts: Vec<&str> = ports.rsplitn(3, ':').collect();
+    let (host_ip, published, target) = match parts.as_slice() {
+        [target] => (None, None, *target),
+        [target, published] => (None, Some(*published), *target),
+        [target, published, host_ip] => (Some(*host_ip), Some(*published), *target),
+        _ => bail!("invalid port specification {}", spec),
+    };
+    if target.parse::<u16>().is_err() {
+        bail!("invalid container port in {}", spec);
+    }
+
+    Ok(ComposePort {
+        host_ip: host_ip
+            .map(|ip| ip.trim_matches(['[', ']']).to_string())
+            .filter(|ip| !ip.is_empty()),
+        published: published
+            .filter(|port| !port.is_empty())
+            .map(str::to_string),
+        target: target.to_string(),
+        protocol,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawVolume {
+    Short(String),
+    Long {
+        source: Option<String>,
+        target: String,
+        #[serde(default)]
+        read_only: bool,
+    },
+}
+
+impl RawVolume {
+    /// Splits a mount into `(source, target, mode)`; a missing source means anonymous.
+    fn parts(&self) -> Result<(Option<String>, String, Option<String>)> {
+        match self {
+            RawVolume::Long {
+                source,
+                target,
+                read_only,
+            } => Ok((
+                source.clone(),
+                target.clone(),
+                read_only.then(|| "ro".to_string()),
+            )),
+            RawVolume::Short(spec) => {
+                let parts: Vec<&str> = spec.split(':').collect();
+                match parts.as_slice() {
+                    [target] => Ok((None, target.to_string(), None)),
+                    [source, target] => Ok((Some(source.to_string()), target.to_string(), None)),
+                    [source, target, mode] => Ok((
+                        Some(source.to_string()),
+                        target.to_string(),
+                        Some(mode.to_string()),
+                    )),
+                    _ => bail!("invalid volume specification {}", spec),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawServiceNetworks {
+    List(Vec<String>),
+    Map(BTreeMap<String, Option<RawServiceNetwork>>),
+}
+
+impl Default for RawServiceNetworks {
+    fn default() -> Self {
+        RawServiceNetworks::List(Vec::new())
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+struct RawServiceNetwork {
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+impl RawServiceNetworks {
+    fn keys(&self) -> Vec<String> {
+        self.entries().into_iter().map(|(key, _)| key).collect()
+    }
+
+    fn entries(&self) -> Vec<(String, Vec<String>)> {
+        match self {
+            RawServiceNetworks::List(keys) => {
+                keys.iter().map(|key| (key.clone(), Vec::new())).collect()
+            }
+            RawServiceNetworks::Map(entries) => entries
+                .iter()
+                .map(|(key, network)| {
+                    let aliases = network.clone().unwrap_or_default().aliases;
+                    (key.clone(), aliases)
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawDependsOn {
+    List(Vec<String>),
+    Map(BTreeMap<String, Value>),
+}
+
+impl Default for RawDependsOn {
+    fn default() -> Self {
+        RawDependsOn::List(Vec::new())
+    }
+}
+
+impl RawDependsOn {
+    fn keys(&self) -> Vec<String> {
+        match self {
+            RawDependsOn::List(keys) => keys.clone(),
+            RawDependsOn::Map(entries) => entries.keys().cloned().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+name: Shop
+services:
+  web:
+    image: nginx:1.27
+    ports:
+      - "8080:80"
+      - "127.0.0.1:8443:443/tcp"
+    depends_on:
+      api:
+        condition: service_started
+    networks: [front]
+  api:
+    image: shop/api
+    command: ["serve", "--port", "3000"]
+    environment:
+      DATABASE_URL: postgres://db/shop
+      WORKERS: 4
+    depends_on: [db]
+    networks:
+      front:
+      back:
+        aliases: [backend]
+  db:
+    image: postgres:16
+    environment:
+      - POSTGRES_PASSWORD=secret
+    volumes:
+      - pgdata:/var/lib/postgresql/data
+      - ./init:/docker-entrypoint-initdb.d:ro
+    networks: [back]
+networks:
+  front:
+  back:
+volumes:
+  pgdata:
+"#;
+
+    #[test]
+    fn parse_orders_services_by_dependencies() {
+        let project = ComposeProject::parse(SAMPLE, Path::new("/srv/shop"), "ignored").unwrap();
+
+        assert_eq!(project.name, "shop");
+        let order: Vec<&str> = project.services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(order, vec!["db", "api", "web"]);
+
+        let db = &project.services[0];
+        assert_eq!(db.container_name, "shop-db-1");
+        assert_eq!(
+            db.binds,
+            vec![
+                "shop_pgdata:/var/lib/postgresql/data".to_string(),
+                "/srv/shop/init:/docker-entrypoint-initdb.d:ro".to_string(),
+            ]
+        );
+
+        let api = &project.services[1];
+        assert_eq!(
+            api.environment,
+            vec!["DATABASE_URL=postgres://db/shop", "WORKERS=4"]
+        );
+        assert_eq!(
+            api.networks[0],
+            (
+                "shop_back".to_string(),
+                vec!["api".to_string(), "backend".to_string()]
+            )
+        );
+
+        let web = &project.services[2];
+        assert_eq!(web.ports[1].host_ip.as_deref(), Some("127.0.0.1"));
+        assert_eq!(web.ports[1].key(), "443/tcp");
+        assert!(
+            !project
+                .networks
+                .iter()
+                .any(|network| network.key == "default")
+        );
+    }
+
+    #[test]
+    fn parse_rejects_dependency_cycles() {
+        let yaml = r#"
+services:
+  a: { image: busybox, depends_on: [b] }
+  b: { image: busybox, depends_on: [a] }
+"#;
+        let error = ComposeProject::parse(yaml, Path::new("/tmp"), "demo").unwrap_err();
+        assert!(error.to_string().contains("dependency cycle"));
+    }
+
+    #[test]
+    fn parse_splits_commands_and_restart_policies() {
+        let yaml = r#"
+services:
+  worker:
+    image: busybox
+    command: sh -c "echo hi && sleep 1"
+    restart: on-failure:3
+  web: { image: nginx, restart: unless-stopped }
+"#;
+        let project = ComposeProject::parse(yaml, Path::new("/tmp"), "demo").unwrap();
+        let worker = &project.services[1];
+        assert_eq!(
+            worker.command.as_deref(),
+            Some(
+                &[
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    "echo hi && sleep 1".to_string()
+                ][..]
+            )
+        );
+        let restart = worker.restart.as_ref().unwrap();
+        assert_eq!(restart.name, Some(RestartPolicyNameEnum::ON_FAILURE));
+        assert_eq!(restart.maximum_retry_count, Some(3));
+        assert_eq!(
+            project.services[0]
+                .restart
+                .as_ref()
+                .unwrap()
+                .maximum_retry_count,
+            None
+        );
+
+        for restart in ["sometimes", "always:2", "on-failure:many"] {
+            let yaml = format!(
+                "services:\n  a: {{ image: busybox, restart: \"{}\" }}\n",
+                restart
+            );
+            assert!(ComposeProject::parse(&yaml, Path::new("/tmp"), "demo").is_err());
+        }
+        let yaml = "services:\n  a: { image: busybox, command: 'echo \"unterminated' }\n";
+        assert!(ComposeProject::parse(yaml, Path::new("/tmp"), "demo").is_err());
+    }
+
+    #[test]
+    fn parse_port_short_syntax() {
+        let port = parse_port("3000").unwrap();
+        assert_eq!((port.published, port.target.as_str()), (None, "3000"));
+
+        let port = parse_port("5353:53/udp").unwrap();
+        assert_eq!(port.published.as_deref(), Some("5353"));
+        assert_eq!(port.key(), "53/udp");
+
+        assert!(parse_port("9000-9001:9000-9001").is_err());
+    }
+}
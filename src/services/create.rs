@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use bollard::container::{
+    Config, CreateContainerOptions, InspectContainerOptions, RenameContainerOptions,
+};
+use bollard::models::{ContainerInspectResponse, HostConfig, ImageConfig, RestartPolicy};
+
+use super::compose::port_config;
+use super::compose_file::{ComposePort, parse_port};
+use super::docker::DockerService;
+use super::limits::RestartPolicyKind;
+use super::networks::parse_labels;
+
+/// Labels that tie a container to a Compose project; a copy should not claim them.
+const COMPOSE_LABEL_PREFIX: &str = "com.docker.compose.";
+
+/// What the create-container form submits. Duplicating a container fills it in
+/// from `docker inspect` so only the differences need typing.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContainerSpec {
+    pub name: String,
+    pub image: String,
+    pub command: Vec<String>,
+    pub entrypoint: Vec<String>,
+    /// `KEY=value` pairs.
+    pub env: Vec<String>,
+    pub ports: Vec<ComposePort>,
+    /// Bind and named-volume mounts in `source:target[:mode]` form.
+    pub binds: Vec<String>,
+    pub labels: Vec<(String, String)>,
+    /// Empty for the engine's default bridge network.
+    pub network: String,
+    pub working_dir: String,
+    pub user: String,
+    pub restart_policy: RestartPolicyKind,
+    /// Only used by `on-failure`; zero retries without limit.
+    pub max_retries: i64,
+}
+
+/// The form's text fields, one entry per line.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContainerSpecText {
+    pub command: String,
+    pub entrypoint: String,
+    pub env: String,
+    pub ports: String,
+    pub binds: String,
+    pub labels: String,
+}
+
+impl ContainerSpec {
+    pub fn to_text(&self) -> ContainerSpecText {
+        ContainerSpecText {
+            command: self.command.join("\n"),
+            entrypoint: self.entrypoint.join("\n"),
+            env: self.env.join("\n"),
+            ports: self
+                .ports
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            binds: self.binds.join("\n"),
+            labels: self
+                .labels
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    /// A starting point for a copy of the container this spec was read from.
+    /// Host ports are left for the engine to pick, since the original still
+    /// holds the ones it published.
+    pub fn duplicate(mut self) -> Self {
+        self.name = format!("{}-copy", self.name);
+        for port in &mut self.ports {
+            port.published = None;
+        }
+        self
+    }
+
+    /// Replaces the list fields with what the form holds, failing on the first
+    /// port that does not parse.
+    pub fn with_text(mut self, text: &ContainerSpecText) -> Result<Self> {
+        let lines = |input: &str| -> Vec<String> {
+            input
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        self.command = lines(&text.command);
+        self.entrypoint = lines(&text.entrypoint);
+        self.env = lines(&text.env);
+        self.ports = lines(&text.ports)
+            .iter()
+            .map(|line| parse_port(line))
+            .collect::<Result<_>>()?;
+        self.binds = lines(&text.binds);
+        self.labels = parse_labels(&text.labels);
+        Ok(self)
+    }
+}
+
+impl DockerService {
+    /// Reads back the settings `docker run` would need to recreate `id`,
+    /// leaving out whatever its image already provides.
+    pub async fn container_spec(&self, id: &str) -> Result<ContainerSpec> {
+        let details = self
+            .docker
+            .inspect_container(id, None::<InspectContainerOptions>)
+            .await?;
+
+        // Settings the image already supplies would only add noise to a copy
+        let image = match details
+            .config
+            .as_ref()
+            .and_then(|config| config.image.as_ref())
+        {
+            Some(image) => self.docker.inspect_image(image).await.ok(),
+            None => None,
+        };
+        let defaults = image.and_then(|image| image.config).unwrap_or_default();
+        Ok(container_spec(details, defaults))
+    }
+
+    /// Creates (but does not start) a container from `spec`, pulling its image
+    /// if needed, and returns the new container's id.
+    pub async fn create_container(&self, spec: &ContainerSpec) -> Result<String> {
+        self.ensure_image(&spec.image).await?;
+
+        let options = (!spec.name.is_empty()).then(|| CreateContainerOptions {
+            name: spec.name.clone(),
+            platform: None,
+        });
+        let created = self
+            .docker
+            .create_container(options, container_config(spec))
+            .await?;
+        Ok(created.id)
+    }
+
+    pub async fn rename_container(&self, id: &str, name: &str) -> Result<()> {
+        self.docker
+            .rename_container(id, RenameContainerOptions { name })
+            .await?;
+        Ok(())
+    }
+}
+
+/// The create request for `spec`; empty fields are left to the image.
+pub(super) fn container_config(spec: &ContainerSpec) -> Config<String> {
+    let (exposed_ports, port_bindings) = port_config(&spec.ports);
+    let non_empty = |value: &String| (!value.is_empty()).then(|| value.clone());
+    let non_empty_list = |value: &Vec<String>| (!value.is_empty()).then(|| value.clone());
+    Config {
+        image: Some(spec.image.clone()),
+        cmd: non_empty_list(&spec.command),
+        entrypoint: non_empty_list(&spec.entrypoint),
+        env: non_empty_list(&spec.env),
+        working_dir: non_empty(&spec.working_dir),
+        user: non_empty(&spec.user),
+        labels: Some(spec.labels.iter().cloned().collect::<HashMap<_, _>>()),
+        exposed_ports: (!exposed_ports.is_empty()).then_some(exposed_ports),
+        host_config: Some(HostConfig {
+            binds: non_empty_list(&spec.binds),
+            port_bindings: (!port_bindings.is_empty()).then_some(port_bindings),
+            network_mode: non_empty(&spec.network),
+            restart_policy: Some(RestartPolicy {
+                name: spec.restart_policy.value().parse().ok(),
+                maximum_retry_count: (spec.restart_policy == RestartPolicyKind::OnFailure)
+                    .then_some(spec.max_retries),
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Builds a spec from `docker inspect` output, dropping the settings equal to
+/// the image's `defaults`.
+pub(super) fn container_spec(
+    details: ContainerInspectResponse,
+    defaults: ImageConfig,
+) -> ContainerSpec {
+    let config = details.config.unwrap_or_default();
+    let host = details.host_config.unwrap_or_default();
+    let unless_default = |value: Option<String>, default: Option<String>| {
+        value.filter(|value| Some(value) != default.as_ref())
+    };
+    let image_env = defaults.env.unwrap_or_default();
+    let image_labels = defaults.labels.unwrap_or_default();
+
+    let mut ports: Vec<ComposePort> = Vec::new();
+    for (key, bindings) in host.port_bindings.unwrap_or_default() {
+        let (target, protocol) = key.split_once('/').unwrap_or((key.as_str(), "tcp"));
+        for binding in bindings.unwrap_or_default() {
+            ports.push(ComposePort {
+                host_ip: binding.host_ip.filter(|ip| !ip.is_empty()),
+                published: binding.host_port.filter(|port| !port.is_empty()),
+                target: target.to_string(),
+                protocol: protocol.to_string(),
+            });
+        }
+    }
+    ports.sort_by_key(ToString::to_string);
+
+    let mut labels: Vec<(String, String)> = config
+        .labels
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(key, _)| !key.starts_with(COMPOSE_LABEL_PREFIX))
+        .filter(|(key, value)| image_labels.get(key) != Some(value))
+        .collect();
+    labels.sort();
+
+    let network = host
+        .network_mode
+        .filter(|mode| mode != "default" && mode != "bridge")
+        .unwrap_or_default();
+    let restart = host.restart_policy.unwrap_or_default();
+    let restart_policy = restart
+        .name
+        .map(|name| RestartPolicyKind::from_value(name.as_ref()))
+        .unwrap_or_default();
+    let max_retries = match restart_policy {
+        RestartPolicyKind::OnFailure => restart.maximum_retry_count.unwrap_or_default(),
+        _ => 0,
+    };
+
+    ContainerSpec {
+        name: details
+            .name
+            .unwrap_or_default()
+            .trim_start_matches('/')
+            .to_string(),
+        image: config.image.unwrap_or_default(),
+        command: config
+            .cmd
+            .filter(|cmd| Some(cmd) != defaults.cmd.as_ref())
+            .unwrap_or_default(),
+        entrypoint: config
+            .entrypoint
+            .filter(|entrypoint| Some(entrypoint) != defaults.entrypoint.as_ref())
+            .unwrap_or_default(),
+        env: config
+            .env
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|pair| !image_env.contains(pair))
+            .collect(),
+        ports,
+        binds: host.binds.unwrap_or_default(),
+        labels,
+        network,
+        working_dir: unless_default(config.working_dir, defaults.working_dir).unwrap_or_default(),
+        user: unless_default(config.user, defaults.user).unwrap_or_default(),
+        restart_policy,
+        max_retries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bollard::models::{ContainerConfig, PortBinding, RestartPolicyNameEnum};
+
+    #[test]
+    fn spec_survives_the_form_text() {
+        let spec = ContainerSpec {
+            image: "nginx:1.27".to_string(),
+            command: vec![
+                "nginx".to_string(),
+                "-g".to_string(),
+                "daemon off;".to_string(),
+            ],
+            env: vec!["MODE=debug".to_string()],
+            ports: vec![parse_port("127.0.0.1:8080:80").unwrap()],
+            labels: vec![("team".to_string(), "web".to_string())],
+            ..Default::default()
+        };
+
+        let text = spec.to_text();
+        assert_eq!(text.ports, "127.0.0.1:8080:80/tcp");
+        let parsed = ContainerSpec {
+            image: spec.image.clone(),
+            ..Default::default()
+        }
+        .with_text(&text)
+        .unwrap();
+        assert_eq!(parsed, spec);
+
+        let bad = ContainerSpecText {
+            ports: "80-90:80".to_string(),
+            ..Default::default()
+        };
+        assert!(ContainerSpec::default().with_text(&bad).is_err());
+    }
+
+    #[test]
+    fn spec_leaves_out_image_defaults_and_copies_without_host_ports() {
+        let strings = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        let defaults = ImageConfig {
+            cmd: Some(strings(&["nginx", "-g", "daemon off;"])),
+            env: Some(strings(&["PATH=/usr/bin", "NGINX_VERSION=1.27"])),
+            working_dir: Some("/".to_string()),
+            labels: Some(HashMap::from([(
+                "maintainer".to_string(),
+                "NGINX".to_string(),
+            )])),
+            ..Default::default()
+        };
+        let details = ContainerInspectResponse {
+            name: Some("/web".to_string()),
+            config: Some(ContainerConfig {
+                image: Some("nginx:1.27".to_string()),
+                user: Some("nginx".to_string()),
+                labels: Some(HashMap::from([
+                    ("maintainer".to_string(), "NGINX".to_string()),
+                    ("team".to_string(), "web".to_string()),
+                    ("com.docker.compose.project".to_string(), "shop".to_string()),
+                ])),
+                env: Some(strings(&[
+                    "PATH=/usr/bin",
+                    "NGINX_VERSION=1.27",
+                    "MODE=debug",
+                ])),
+                cmd: defaults.cmd.clone(),
+                working_dir: defaults.working_dir.clone(),
+                ..Default::default()
+            }),
+            host_config: Some(HostConfig {
+                network_mode: Some("bridge".to_string()),
+                restart_policy: Some(RestartPolicy {
+                    name: Some(RestartPolicyNameEnum::ON_FAILURE),
+                    maximum_retry_count: Some(5),
+                }),
+                port_bindings: Some(HashMap::from([(
+                    "80/tcp".to_string(),
+                    Some(vec![PortBinding {
+                        host_ip: Some(String::new()),
+                        host_port: Some("8080".to_string()),
+                    }]),
+                )])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let spec = container_spec(details, defaults);
+        assert_eq!(spec.name, "web");
+        assert!(spec.command.is_empty());
+        assert_eq!(spec.env, vec!["MODE=debug"]);
+        assert_eq!(spec.working_dir, "");
+        assert_eq!(spec.user, "nginx");
+        assert_eq!(spec.labels, vec![("team".to_string(), "web".to_string())]);
+        assert_eq!(spec.network, "");
+        assert_eq!(spec.restart_policy, RestartPolicyKind::OnFailure);
+        assert_eq!(spec.max_retries, 5);
+        assert_eq!(spec.to_text().ports, "8080:80/tcp");
+
+        let copy = spec.duplicate();
+        assert_eq!(copy.name, "web-copy");
+        assert_eq!(copy.to_text().ports, "80/tcp");
+    }
+}
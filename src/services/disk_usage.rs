@@ -0,0 +1,246 @@
+use anyhow::Result;
+use bollard::models::SystemDataUsageResponse;
+
+use super::docker::{DockerService, format_size};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DiskUsageKind {
+    Images,
+    Containers,
+    Volumes,
+    BuildCache,
+}
+
+impl DiskUsageKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DiskUsageKind::Images => "Images",
+            DiskUsageKind::Containers => "Containers",
+            DiskUsageKind::Volumes => "Local volumes",
+            DiskUsageKind::BuildCache => "Build cache",
+        }
+    }
+}
+
+/// One object counted by the `/system/df` endpoint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiskUsageItem {
+    pub name: String,
+    pub detail: String,
+    pub size: i64,
+    pub in_use: bool,
+}
+
+impl DiskUsageItem {
+    pub fn size_label(&self) -> String {
+        format_size(self.size)
+    }
+}
+
+/// Space taken by one kind of object and how much of it a prune would free.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiskUsageCategory {
+    pub kind: DiskUsageKind,
+    pub size: i64,
+    pub reclaimable: i64,
+    pub items: Vec<DiskUsageItem>,
+}
+
+impl DiskUsageCategory {
+    fn new(kind: DiskUsageKind, mut items: Vec<DiskUsageItem>) -> Self {
+        items.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+        Self {
+            kind,
+            size: items.iter().map(|item| item.size).sum(),
+            reclaimable: items
+                .iter()
+                .filter(|item| !item.in_use)
+                .map(|item| item.size)
+                .sum(),
+            items,
+        }
+    }
+
+    pub fn active(&self) -> usize {
+        self.items.iter().filter(|item| item.in_use).count()
+    }
+
+    pub fn size_label(&self) -> String {
+        format_size(self.size)
+    }
+
+    pub fn reclaimable_label(&self) -> String {
+        if self.size == 0 {
+            return format_size(0);
+        }
+        let percent = self.reclaimable as f64 / self.size as f64 * 100.0;
+        format!("{} ({:.0}%)", format_size(self.reclaimable), percent)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiskUsage {
+    pub categories: Vec<DiskUsageCategory>,
+}
+
+impl DiskUsage {
+    pub fn total_label(&self) -> String {
+        format_size(self.categories.iter().map(|category| category.size).sum())
+    }
+
+    pub fn reclaimable_label(&self) -> String {
+        format_size(
+            self.categories
+                .iter()
+                .map(|category| category.reclaimable)
+                .sum(),
+        )
+    }
+}
+
+impl DockerService {
+    pub async fn disk_usage(&self) -> Result<DiskUsage> {
+        let usage = self.docker.df().await?;
+        Ok(disk_usage(usage))
+    }
+}
+
+pub(super) fn disk_usage(usage: SystemDataUsageResponse) -> DiskUsage {
+    let short_id = |id: &str| {
+        id.trim_start_matches("sha256:")
+            .chars()
+            .take(12)
+            .collect::<String>()
+    };
+
+    // Layers shared with other images are not freed by removing one image, so
+    // only the unique part of an image counts towards its size
+    let images = usage
+        .images
+        .unwrap_or_default()
+        .into_iter()
+        .map(|image| DiskUsageItem {
+            name: image
+                .repo_tags
+                .first()
+                .filter(|tag| tag.as_str() != "<none>:<none>")
+                .cloned()
+                .unwrap_or_else(|| "<none>".to_string()),
+            detail: short_id(&image.id),
+            size: (image.size - image.shared_size.max(0)).max(0),
+            in_use: image.containers > 0,
+        })
+        .collect();
+
+    let containers = usage
+        .containers
+        .unwrap_or_default()
+        .into_iter()
+        .map(|container| DiskUsageItem {
+            name: container
+                .names
+                .as_ref()
+                .and_then(|names| names.first())
+                .map(|name| name.trim_start_matches('/').to_string())
+                .unwrap_or_else(|| "unnamed".to_string()),
+            detail: container.image.unwrap_or_else(|| "unknown".to_string()),
+            size: container.size_rw.unwrap_or(0).max(0),
+            in_use: container.state.as_deref() == Some("running"),
+        })
+        .collect();
+
+    let volumes = usage
+        .volumes
+        .unwrap_or_default()
+        .into_iter()
+        .map(|volume| {
+            // The engine reports -1 when it could not measure a volume or its references
+            let usage_data = volume.usage_data.unwrap_or_default();
+            DiskUsageItem {
+                detail: volume.driver,
+                name: volume.name,
+                size: usage_data.size.max(0),
+                in_use: usage_data.ref_count > 0,
+            }
+        })
+        .collect();
+
+    let build_cache = usage
+        .build_cache
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|record| !record.shared.unwrap_or(false))
+        .map(|record| DiskUsageItem {
+            name: record
+                .description
+                .filter(|description| !description.is_empty())
+                .unwrap_or_else(|| short_id(record.id.as_deref().unwrap_or_default())),
+            detail: record
+                .typ
+                .map(|typ| typ.to_string())
+                .unwrap_or_else(|| "--".to_string()),
+            size: record.size.unwrap_or(0).max(0),
+            in_use: record.in_use.unwrap_or(false),
+        })
+        .collect();
+
+    DiskUsage {
+        categories: vec![
+            DiskUsageCategory::new(DiskUsageKind::Images, images),
+            DiskUsageCategory::new(DiskUsageKind::Containers, containers),
+            DiskUsageCategory::new(DiskUsageKind::Volumes, volumes),
+            DiskUsageCategory::new(DiskUsageKind::BuildCache, build_cache),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bollard::models::{ContainerSummary, ImageSummary, Volume, VolumeUsageData};
+
+    use super::*;
+
+    #[test]
+    fn disk_usage_counts_unused_objects_as_reclaimable() {
+        let image = |tag: &str, size: i64, shared_size: i64, containers: i64| ImageSummary {
+            id: format!("sha256:{}", tag),
+            repo_tags: vec![tag.to_string()],
+            size,
+            shared_size,
+            containers,
+            ..Default::default()
+        };
+        let volume = |name: &str, size: i64, ref_count: i64| Volume {
+            name: name.to_string(),
+            driver: "local".to_string(),
+            usage_data: Some(VolumeUsageData { size, ref_count }),
+            ..Default::default()
+        };
+        let usage = SystemDataUsageResponse {
+            images: Some(vec![
+                image("nginx:latest", 100, 40, 1),
+                image("redis:7", 50, -1, 0),
+            ]),
+            containers: Some(vec![ContainerSummary {
+                names: Some(vec!["/web".to_string()]),
+                size_rw: Some(10),
+                state: Some("exited".to_string()),
+                ..Default::default()
+            }]),
+            volumes: Some(vec![volume("data", 30, 1), volume("orphan", -1, -1)]),
+            ..Default::default()
+        };
+
+        let usage = disk_usage(usage);
+        let images = &usage.categories[0];
+        let volumes = &usage.categories[2];
+
+        assert_eq!(images.size, 110);
+        assert_eq!(images.reclaimable, 50);
+        assert_eq!(images.items[0].name, "nginx:latest");
+        assert_eq!(usage.categories[1].reclaimable, 10);
+        assert_eq!(volumes.size, 30);
+        assert_eq!(volumes.active(), 1);
+        assert!(usage.categories[3].items.is_empty());
+    }
+}
@@ -1,8 +1,28 @@
-use anyhow::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
 use bollard::Docker;
-use bollard::container::{ListContainersOptions, StartContainerOptions, StopContainerOptions};
-use bollard::image::ListImagesOptions;
-use bollard::volume::ListVolumesOptions;
+use bollard::container::{
+    Config, CreateContainerOptions, DownloadFromContainerOptions, ListContainersOptions,
+    RemoveContainerOptions, RestartContainerOptions, StartContainerOptions, StopContainerOptions,
+    UploadToContainerOptions,
+};
+use bollard::image::{CreateImageOptions, ListImagesOptions, RemoveImageOptions};
+use bollard::models::{ContainerSummary, HostConfig, ImageSummary, MountPointTypeEnum, Volume};
+use bollard::volume::{ListVolumesOptions, RemoveVolumeOptions};
+use futures_util::StreamExt;
+use tokio::io::AsyncWriteExt;
+
+use super::archive::{self, DirectoryLister, FileEntry};
+use super::compose::{PROJECT_LABEL, SERVICE_LABEL};
+use super::health::{self, HealthStatus};
+use super::ports::{PublishedPort, published_ports};
+
+/// Image used for short-lived helper containers that expose a volume to the archive API.
+const HELPER_IMAGE: &str = "busybox:latest";
+/// Where helper containers mount the volume being browsed.
+const HELPER_MOUNT: &str = "/volume";
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ContainerState {
@@ -39,8 +59,16 @@ pub struct ContainerInfo {
     pub name: String,
     pub image: String,
     pub status: String,
-    pub ports: String,
+    pub ports: Vec<PublishedPort>,
     pub state: ContainerState,
+    pub health: Option<HealthStatus>,
+    /// Set once the container has exited.
+    pub exit_code: Option<i64>,
+    pub compose_project: Option<String>,
+    pub compose_service: Option<String>,
+    pub labels: HashMap<String, String>,
+    /// Names of the named volumes the container mounts.
+    pub volumes: Vec<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -49,6 +77,25 @@ pub struct ImageInfo {
     pub repository: String,
     pub tag: String,
     pub size: String,
+    pub size_bytes: i64,
+    /// Unix timestamp of when the image was built.
+    pub created: i64,
+    pub labels: HashMap<String, String>,
+    /// Every `repository:tag` that points at the image.
+    pub tags: Vec<String>,
+}
+
+impl ImageInfo {
+    /// What to remove the image by. The engine refuses to remove an image that
+    /// several tags point at by its id, so such an image is untagged one tag at a
+    /// time; removing the last tag removes the image.
+    pub fn removal_references(&self) -> Vec<String> {
+        if self.tags.len() > 1 {
+            self.tags.clone()
+        } else {
+            vec![self.id.clone()]
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -57,11 +104,25 @@ pub struct VolumeInfo {
     pub driver: String,
     pub mountpoint: String,
     pub size: String,
+    pub labels: HashMap<String, String>,
+    /// Whether any container, running or not, mounts the volume. The volume list
+    /// does not say, so `mark_in_use` fills it in from the container list.
+    pub in_use: bool,
+}
+
+/// Marks the volumes one of `containers` mounts as in use.
+pub fn mark_in_use(mut volumes: Vec<VolumeInfo>, containers: &[ContainerInfo]) -> Vec<VolumeInfo> {
+    for volume in &mut volumes {
+        volume.in_use = containers
+            .iter()
+            .any(|container| container.volumes.contains(&volume.name));
+    }
+    volumes
 }
 
 #[derive(Clone)]
 pub struct DockerService {
-    docker: Docker,
+    pub(super) docker: Docker,
 }
 
 impl DockerService {
@@ -78,65 +139,7 @@ impl DockerService {
 
         let containers = self.docker.list_containers(options).await?;
 
-        let container_infos = containers
-            .into_iter()
-            .map(|container| {
-                let id = container
-                    .id
-                    .as_ref()
-                    .map(|s| s.chars().take(12).collect())
-                    .unwrap_or_else(|| "unknown".to_string());
-
-                let name = container
-                    .names
-                    .as_ref()
-                    .and_then(|names| names.first())
-                    .map(|n| n.trim_start_matches('/').to_string())
-                    .unwrap_or_else(|| "unnamed".to_string());
-
-                let image = container.image.unwrap_or_else(|| "unknown".to_string());
-
-                let status = container.status.unwrap_or_else(|| "unknown".to_string());
-
-                let ports = if let Some(ports) = container.ports {
-                    if ports.is_empty() {
-                        "--".to_string()
-                    } else {
-                        ports
-                            .iter()
-                            .filter_map(|p| match (p.public_port, p.private_port) {
-                                (Some(pub_port), priv_port) => {
-                                    Some(format!("{}:{}", pub_port, priv_port))
-                                }
-                                (None, priv_port) => Some(format!("{}", priv_port)),
-                            })
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    }
-                } else {
-                    "--".to_string()
-                };
-
-                let state = if let Some(st) = container.state {
-                    if st == "running" {
-                        ContainerState::Running
-                    } else {
-                        ContainerState::Stopped
-                    }
-                } else {
-                    ContainerState::Stopped
-                };
-
-                ContainerInfo {
-                    id,
-                    name,
-                    image,
-                    status,
-                    ports,
-                    state,
-                }
-            })
-            .collect();
+        let container_infos = containers.into_iter().map(container_info).collect();
 
         Ok(container_infos)
     }
@@ -149,61 +152,22 @@ impl DockerService {
 
         let images = self.docker.list_images(options).await?;
 
-        let image_infos = images
-            .into_iter()
-            .map(|image| {
-                let id = image.id;
-
-                // Parse repository and tag from repo_tags (Vec<String>)
-                let (repository, tag) = if let Some(first) = image.repo_tags.first() {
-                    let parts: Vec<&str> = first.split(':').collect();
-                    let repo = parts.first().unwrap_or(&"<none>").to_string();
-                    let tag_part = parts.get(1).unwrap_or(&"<none>").to_string();
-                    (repo, tag_part)
-                } else {
-                    ("<none>".to_string(), "<none>".to_string())
-                };
-
-                // Format size directly (it's i64, not Option<i64>)
-                let size = format_size(image.size);
-
-                ImageInfo {
-                    id,
-                    repository,
-                    tag,
-                    size,
-                }
-            })
-            .collect();
+        let image_infos = images.into_iter().map(image_info).collect();
 
         Ok(image_infos)
     }
 
     pub async fn list_volumes(&self) -> Result<Vec<VolumeInfo>> {
-        let options = ListVolumesOptions::<String> {
-            ..Default::default()
-        };
-
-        let volumes_response = self.docker.list_volumes(Some(options)).await?;
+        let volumes_response = self
+            .docker
+            .list_volumes(None::<ListVolumesOptions<String>>)
+            .await?;
 
         let volume_infos = volumes_response
             .volumes
             .unwrap_or_default()
             .into_iter()
-            .map(|volume| {
-                let name = volume.name;
-                let driver = volume.driver;
-                let mountpoint = volume.mountpoint;
-                // Note: Size is not directly available from Docker API without additional inspection
-                let size = "--".to_string();
-
-                VolumeInfo {
-                    name,
-                    driver,
-                    mountpoint,
-                    size,
-                }
-            })
+            .map(volume_info)
             .collect();
 
         Ok(volume_infos)
@@ -222,9 +186,336 @@ impl DockerService {
             .await?;
         Ok(())
     }
+
+    pub async fn restart_container(&self, id: &str) -> Result<()> {
+        self.docker
+            .restart_container(id, None::<RestartContainerOptions>)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_container(&self, id: &str) -> Result<()> {
+        self.docker
+            .remove_container(id, None::<RemoveContainerOptions>)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_image(&self, id: &str) -> Result<()> {
+        self.docker
+            .remove_image(id, None::<RemoveImageOptions>, None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_volume(&self, name: &str) -> Result<()> {
+        self.docker
+            .remove_volume(name, None::<RemoveVolumeOptions>)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_volume_files(&self, volume: &str, path: &str) -> Result<Vec<FileEntry>> {
+        let helper = self.create_volume_helper(volume).await?;
+        let result = self.list_path(&helper, &helper_path(path), path).await;
+        let removed = self.remove_helper(&helper).await;
+        result.and_then(|value| removed.map(|()| value))
+    }
+
+    /// Streams `path` from the volume as a tar archive into `destination` and
+    /// returns how many bytes were written.
+    pub async fn download_volume_path(
+        &self,
+        volume: &str,
+        path: &str,
+        destination: &Path,
+    ) -> Result<u64> {
+        let helper = self.create_volume_helper(volume).await?;
+        let result = self
+            .download_to_file(&helper, &helper_path(path), destination)
+            .await;
+        let removed = self.remove_helper(&helper).await;
+        result.and_then(|value| removed.map(|()| value))
+    }
+
+    pub async fn upload_to_volume(
+        &self,
+        volume: &str,
+        dir: &str,
+        sources: Vec<PathBuf>,
+    ) -> Result<()> {
+        let helper = self.create_volume_helper(volume).await?;
+        let result = self.upload_files(&helper, &helper_path(dir), sources).await;
+        let removed = self.remove_helper(&helper).await;
+        result.and_then(|value| removed.map(|()| value))
+    }
+
+    pub async fn list_container_files(&self, id: &str, path: &str) -> Result<Vec<FileEntry>> {
+        self.list_path(id, container_path(path), path).await
+    }
+
+    /// Streams the archive of `path` to `destination`, returning its size.
+    pub async fn download_container_path(
+        &self,
+        id: &str,
+        path: &str,
+        destination: &Path,
+    ) -> Result<u64> {
+        self.download_to_file(id, container_path(path), destination)
+            .await
+    }
+
+    pub async fn upload_to_container(
+        &self,
+        id: &str,
+        dir: &str,
+        sources: Vec<PathBuf>,
+    ) -> Result<()> {
+        self.upload_files(id, container_path(dir), sources).await
+    }
+
+    async fn list_path(&self, container: &str, path: &str, dir: &str) -> Result<Vec<FileEntry>> {
+        let options = Some(DownloadFromContainerOptions { path });
+        let mut stream = self.docker.download_from_container(container, options);
+        let mut lister = DirectoryLister::new(path, dir);
+        while let Some(chunk) = stream.next().await {
+            lister.feed(&chunk?)?;
+            if lister.is_finished() {
+                break;
+            }
+        }
+        Ok(lister.finish())
+    }
+
+    async fn download_to_file(
+        &self,
+        container: &str,
+        path: &str,
+        destination: &Path,
+    ) -> Result<u64> {
+        let options = Some(DownloadFromContainerOptions { path });
+        let mut stream = self.docker.download_from_container(container, options);
+        let mut file = tokio::fs::File::create(destination).await?;
+        let mut written = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await?;
+        Ok(written)
+    }
+
+    /// Streams `sources` into `dir` as an archive built while it uploads.
+    async fn upload_files(&self, container: &str, dir: &str, sources: Vec<PathBuf>) -> Result<()> {
+        let options = Some(UploadToContainerOptions {
+            path: dir,
+            ..Default::default()
+        });
+        let (tar, building) = archive::archive_files(sources);
+        let uploaded = self
+            .docker
+            .upload_to_container_streaming(container, options, tar)
+            .await;
+        // A file that failed to read explains a failed upload better than the engine can
+        building.await??;
+        uploaded?;
+        Ok(())
+    }
+
+    /// Creates (but never starts) a container with `volume` mounted, pulling the
+    /// helper image first if it is not cached locally.
+    async fn create_volume_helper(&self, volume: &str) -> Result<String> {
+        self.ensure_image(HELPER_IMAGE).await?;
+
+        let config = Config {
+            image: Some(HELPER_IMAGE.to_string()),
+            cmd: Some(vec!["true".to_string()]),
+            labels: Some(
+                [(
+                    "com.doctainr.helper".to_string(),
+                    "volume-browser".to_string(),
+                )]
+                .into(),
+            ),
+            host_config: Some(HostConfig {
+                binds: Some(vec![format!("{}:{}", volume, HELPER_MOUNT)]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let created = self
+            .docker
+            .create_container(None::<CreateContainerOptions<String>>, config)
+            .await?;
+        Ok(created.id)
+    }
+
+    /// Pulls `image` unless it is already in the local cache.
+    pub(super) async fn ensure_image(&self, image: &str) -> Result<()> {
+        if self.docker.inspect_image(image).await.is_ok() {
+            return Ok(());
+        }
+
+        // An empty tag makes the engine pull every tag of the repository
+        let reference = with_default_tag(image);
+        let options = Some(CreateImageOptions {
+            from_image: reference.as_str(),
+            ..Default::default()
+        });
+        let mut pull = self.docker.create_image(options, None, None);
+        while let Some(progress) = pull.next().await {
+            progress?;
+        }
+        Ok(())
+    }
+
+    /// Removes a helper container. Callers still report their own error first,
+    /// since a leftover helper matters less than the operation that failed.
+    async fn remove_helper(&self, id: &str) -> Result<()> {
+        let options = Some(RemoveContainerOptions {
+            force: true,
+            ..Default::default()
+        });
+        self.docker
+            .remove_container(id, options)
+            .await
+            .with_context(|| format!("Failed to remove helper container {}", id))
+    }
 }
 
-fn format_size(size: i64) -> String {
+fn with_default_tag(image: &str) -> String {
+    let name = image.rsplit('/').next().unwrap_or(image);
+    if image.contains('@') || name.contains(':') {
+        image.to_string()
+    } else {
+        format!("{}:latest", image)
+    }
+}
+
+/// The archive API wants `/` itself rather than an empty path for the root.
+fn container_path(path: &str) -> &str {
+    match path.trim_end_matches('/') {
+        "" => "/",
+        trimmed => trimmed,
+    }
+}
+
+fn helper_path(path: &str) -> String {
+    format!("{}{}", HELPER_MOUNT, path.trim_end_matches('/'))
+}
+
+pub(super) fn container_info(container: ContainerSummary) -> ContainerInfo {
+    let id = container
+        .id
+        .as_ref()
+        .map(|s| s.chars().take(12).collect())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let name = container
+        .names
+        .as_ref()
+        .and_then(|names| names.first())
+        .map(|n| n.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| "unnamed".to_string());
+
+    let image = container.image.unwrap_or_else(|| "unknown".to_string());
+
+    let status = container.status.unwrap_or_else(|| "unknown".to_string());
+    let health = HealthStatus::from_status(&status);
+    let exit_code = health::exit_code(&status);
+
+    let ports = published_ports(container.ports.unwrap_or_default());
+
+    let state = if let Some(st) = container.state {
+        if st == "running" {
+            ContainerState::Running
+        } else {
+            ContainerState::Stopped
+        }
+    } else {
+        ContainerState::Stopped
+    };
+
+    let labels = container.labels.unwrap_or_default();
+    let compose_project = labels.get(PROJECT_LABEL).cloned();
+    let compose_service = labels.get(SERVICE_LABEL).cloned();
+
+    let volumes = container
+        .mounts
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|mount| mount.typ == Some(MountPointTypeEnum::VOLUME))
+        .filter_map(|mount| mount.name)
+        .collect();
+
+    ContainerInfo {
+        id,
+        name,
+        image,
+        status,
+        ports,
+        state,
+        health,
+        exit_code,
+        compose_project,
+        compose_service,
+        labels,
+        volumes,
+    }
+}
+
+pub(super) fn image_info(image: ImageSummary) -> ImageInfo {
+    let id = image.id;
+
+    // Parse repository and tag from repo_tags (Vec<String>)
+    let (repository, tag) = if let Some(first) = image.repo_tags.first() {
+        let parts: Vec<&str> = first.split(':').collect();
+        let repo = parts.first().unwrap_or(&"<none>").to_string();
+        let tag_part = parts.get(1).unwrap_or(&"<none>").to_string();
+        (repo, tag_part)
+    } else {
+        ("<none>".to_string(), "<none>".to_string())
+    };
+
+    // Format size directly (it's i64, not Option<i64>)
+    let size = format_size(image.size);
+
+    ImageInfo {
+        id,
+        repository,
+        tag,
+        size,
+        size_bytes: image.size,
+        created: image.created,
+        labels: image.labels,
+        tags: image
+            .repo_tags
+            .into_iter()
+            .filter(|tag| tag != "<none>:<none>")
+            .collect(),
+    }
+}
+
+pub(super) fn volume_info(volume: Volume) -> VolumeInfo {
+    let name = volume.name;
+    let driver = volume.driver;
+    let mountpoint = volume.mountpoint;
+    // Note: Size is not directly available from Docker API without additional inspection
+    let size = "--".to_string();
+
+    VolumeInfo {
+        name,
+        driver,
+        mountpoint,
+        size,
+        labels: volume.labels,
+        in_use: false,
+    }
+}
+
+pub(crate) fn format_size(size: i64) -> String {
     const KB: i64 = 1024;
     const MB: i64 = KB * 1024;
     const GB: i64 = MB * 1024;
@@ -250,6 +541,17 @@ mod tests {
         assert_eq!(ContainerState::Stopped.label(), "Stopped");
     }
 
+    #[test]
+    fn default_tag_is_added_only_when_missing() {
+        assert_eq!(with_default_tag("nginx"), "nginx:latest");
+        assert_eq!(
+            with_default_tag("localhost:5000/app"),
+            "localhost:5000/app:latest"
+        );
+        assert_eq!(with_default_tag("redis:7"), "redis:7");
+        assert_eq!(with_default_tag("alpine@sha256:abc"), "alpine@sha256:abc");
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(100), "100B");
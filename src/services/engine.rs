@@ -0,0 +1,121 @@
+use anyhow::Result;
+use bollard::models::{SystemInfo, SystemInfoCgroupVersionEnum};
+use bollard::system::Version;
+
+use super::docker::{DockerService, format_size};
+
+/// What the app is connected to, merged from the `/info` and `/version` endpoints.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EngineInfo {
+    pub name: String,
+    pub engine_version: String,
+    pub api_version: String,
+    pub operating_system: String,
+    pub architecture: String,
+    pub kernel_version: String,
+    pub storage_driver: String,
+    pub cgroup_version: String,
+    pub cpus: String,
+    pub memory: String,
+    pub rootless: bool,
+    pub registry_mirrors: Vec<String>,
+}
+
+impl DockerService {
+    pub async fn engine_info(&self) -> Result<EngineInfo> {
+        let info = self.docker.info().await?;
+        let version = self.docker.version().await?;
+        Ok(engine_info(info, version))
+    }
+}
+
+pub(super) fn engine_info(info: SystemInfo, version: Version) -> EngineInfo {
+    let or_unknown = |value: Option<String>| {
+        value
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "--".to_string())
+    };
+
+    // Rootless daemons advertise themselves through a `name=rootless` security option
+    let rootless = info
+        .security_options
+        .unwrap_or_default()
+        .iter()
+        .any(|option| option.split(',').any(|part| part == "name=rootless"));
+
+    EngineInfo {
+        name: or_unknown(info.name),
+        engine_version: or_unknown(version.version.or(info.server_version)),
+        api_version: match (version.api_version, version.min_api_version) {
+            (Some(api), Some(min)) => format!("{} (min {})", api, min),
+            (api, _) => or_unknown(api),
+        },
+        operating_system: or_unknown(info.operating_system.or(version.os)),
+        architecture: or_unknown(info.architecture.or(version.arch)),
+        kernel_version: or_unknown(info.kernel_version.or(version.kernel_version)),
+        storage_driver: or_unknown(info.driver),
+        cgroup_version: match info.cgroup_version {
+            Some(SystemInfoCgroupVersionEnum::EMPTY) | None => "--".to_string(),
+            Some(cgroup) => format!("v{}", cgroup),
+        },
+        cpus: info
+            .ncpu
+            .map(|cpus| cpus.to_string())
+            .unwrap_or_else(|| "--".to_string()),
+        memory: info
+            .mem_total
+            .map(format_size)
+            .unwrap_or_else(|| "--".to_string()),
+        rootless,
+        registry_mirrors: info
+            .registry_config
+            .and_then(|config| config.mirrors)
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bollard::models::RegistryServiceConfig;
+
+    use super::*;
+
+    #[test]
+    fn engine_info_merges_info_and_version() {
+        let info = SystemInfo {
+            server_version: Some("27.0.0".to_string()),
+            operating_system: Some("Ubuntu 24.04".to_string()),
+            driver: Some("overlay2".to_string()),
+            cgroup_version: Some(SystemInfoCgroupVersionEnum::_2),
+            ncpu: Some(8),
+            mem_total: Some(16 * 1024 * 1024 * 1024),
+            security_options: Some(vec![
+                "name=seccomp,profile=builtin".to_string(),
+                "name=rootless".to_string(),
+            ]),
+            registry_config: Some(RegistryServiceConfig {
+                mirrors: Some(vec!["https://mirror.example.com/".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let version = Version {
+            version: Some("27.1.1".to_string()),
+            api_version: Some("1.46".to_string()),
+            min_api_version: Some("1.24".to_string()),
+            kernel_version: Some("6.8.0".to_string()),
+            ..Default::default()
+        };
+
+        let engine = engine_info(info, version);
+
+        assert_eq!(engine.engine_version, "27.1.1");
+        assert_eq!(engine.api_version, "1.46 (min 1.24)");
+        assert_eq!(engine.kernel_version, "6.8.0");
+        assert_eq!(engine.cgroup_version, "v2");
+        assert_eq!(engine.memory, "16.0GB");
+        assert_eq!(engine.architecture, "--");
+        assert!(engine.rootless);
+        assert_eq!(engine.registry_mirrors, vec!["https://mirror.example.com/"]);
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+use std::io;
+
+use bollard::errors::Error as BollardError;
+
+/// What went wrong talking to the engine, classified so the UI can explain it.
+///
+/// Service methods return `anyhow::Result`; `DockerError::from` walks the error chain
+/// to find the bollard or I/O error underneath and picks the matching variant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DockerError {
+    Unreachable(String),
+    PermissionDenied(String),
+    NotFound(String),
+    Conflict(String),
+    ApiVersionMismatch(String),
+    Timeout,
+    Other(String),
+}
+
+impl DockerError {
+    pub fn title(&self) -> &'static str {
+        match self {
+            DockerError::Unreachable(_) => "Docker daemon unreachable",
+            DockerError::PermissionDenied(_) => "Permission denied",
+            DockerError::NotFound(_) => "Not found",
+            DockerError::Conflict(_) => "Conflict",
+            DockerError::ApiVersionMismatch(_) => "API version mismatch",
+            DockerError::Timeout => "Timed out",
+            DockerError::Other(_) => "Docker error",
+        }
+    }
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            DockerError::Unreachable(_) => {
+                "Make sure Docker is running and DOCKER_HOST points at the right socket."
+            }
+            DockerError::PermissionDenied(_) => {
+                "Your user cannot open the Docker socket. Add it to the docker group or use a rootless engine."
+            }
+            DockerError::NotFound(_) => {
+                "The object may have been removed outside the app. Refresh to update the list."
+            }
+            DockerError::Conflict(_) => {
+                "The object is in use or already in the requested state. Stop or disconnect what uses it first."
+            }
+            DockerError::ApiVersionMismatch(_) => {
+                "The engine does not speak the API version this app needs. Upgrade Docker."
+            }
+            DockerError::Timeout => "The engine is busy or stuck. Wait a moment and try again.",
+            DockerError::Other(_) => "See the message from the engine for details.",
+        }
+    }
+
+    /// Whether trying the same operation again can succeed without the user changing anything.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            DockerError::Unreachable(_) | DockerError::Timeout | DockerError::Other(_)
+        )
+    }
+
+    fn from_bollard(error: &BollardError) -> Option<Self> {
+        match error {
+            BollardError::DockerResponseServerError {
+                status_code,
+                message,
+            } => Some(match status_code {
+                404 => DockerError::NotFound(message.clone()),
+                409 => DockerError::Conflict(message.clone()),
+                400 if message.contains("client version") => {
+                    DockerError::ApiVersionMismatch(message.clone())
+                }
+                _ => DockerError::Other(message.clone()),
+            }),
+            BollardError::APIVersionParseError {} => {
+                Some(DockerError::ApiVersionMismatch(error.to_string()))
+            }
+            BollardError::RequestTimeoutError => Some(DockerError::Timeout),
+            BollardError::IOError { err } => DockerError::from_io(err),
+            BollardError::SocketNotFoundError(path) => {
+                Some(DockerError::Unreachable(format!("no socket at {}", path)))
+            }
+            // Transport errors wrap an I/O error further down the chain
+            _ => None,
+        }
+    }
+
+    fn from_io(error: &io::Error) -> Option<Self> {
+        match error.kind() {
+            io::ErrorKind::PermissionDenied => {
+                Some(DockerError::PermissionDenied(error.to_string()))
+            }
+            io::ErrorKind::NotFound
+            | io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::BrokenPipe => Some(DockerError::Unreachable(error.to_string())),
+            io::ErrorKind::TimedOut => Some(DockerError::Timeout),
+            _ => None,
+        }
+    }
+}
+
+impl From<&anyhow::Error> for DockerError {
+    fn from(error: &anyhow::Error) -> Self {
+        error
+            .chain()
+            .find_map(|cause| {
+                if let Some(docker) = cause.downcast_ref::<DockerError>() {
+                    Some(docker.clone())
+                } else if let Some(bollard) = cause.downcast_ref::<BollardError>() {
+                    DockerError::from_bollard(bollard)
+                } else {
+                    cause
+                        .downcast_ref::<io::Error>()
+                        .and_then(DockerError::from_io)
+                }
+            })
+            .unwrap_or_else(|| DockerError::Other(error.to_string()))
+    }
+}
+
+impl fmt::Display for DockerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DockerError::Timeout => write!(f, "{}", self.title()),
+            DockerError::Unreachable(detail)
+            | DockerError::PermissionDenied(detail)
+            | DockerError::NotFound(detail)
+            | DockerError::Conflict(detail)
+            | DockerError::ApiVersionMismatch(detail)
+            | DockerError::Other(detail) => write!(f, "{}: {}", self.title(), detail),
+        }
+    }
+}
+
+impl std::error::Error for DockerError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_errors_through_the_chain() {
+        let not_found = anyhow::Error::from(BollardError::DockerResponseServerError {
+            status_code: 404,
+            message: "No such container: web".to_string(),
+        });
+        assert_eq!(
+            DockerError::from(&not_found),
+            DockerError::NotFound("No such container: web".to_string())
+        );
+
+        let denied = anyhow::Error::from(BollardError::from(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "permission denied",
+        )))
+        .context("Failed to list containers");
+        assert!(matches!(
+            DockerError::from(&denied),
+            DockerError::PermissionDenied(_)
+        ));
+
+        let version = anyhow::Error::from(BollardError::DockerResponseServerError {
+            status_code: 400,
+            message: "client version 1.47 is too new. Maximum supported API version is 1.43"
+                .to_string(),
+        });
+        assert!(matches!(
+            DockerError::from(&version),
+            DockerError::ApiVersionMismatch(_)
+        ));
+
+        let other = anyhow::anyhow!("something odd");
+        assert_eq!(
+            DockerError::from(&other),
+            DockerError::Other("something odd".to_string())
+        );
+        assert!(!DockerError::Conflict(String::new()).is_retryable());
+        assert!(!DockerError::PermissionDenied(String::new()).is_retryable());
+    }
+}
@@ -0,0 +1,1689 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use bollard::container::{Config, NetworkingConfig, UpdateContainerOptions};
+use bollard::models::{
+    ChangeType, ContainerConfig, ContainerInspectResponse, ContainerStateStatusEnum,
+    ContainerSummary, EndpointSettings, FilesystemChange, Health, HealthConfig, HealthStatusEnum,
+    HealthcheckResult, HostConfig, ImageConfig, ImageSummary, Ipam, IpamConfig, MountPoint,
+    MountPointTypeEnum, Network, NetworkContainer, NetworkSettings, Port, PortTypeEnum,
+    SystemDataUsageResponse, SystemInfo, SystemInfoCgroupVersionEnum, Volume, VolumeUsageData,
+};
+use bollard::system::Version;
+use bytes::Bytes;
+use futures_util::StreamExt;
+
+use super::archive::{self, DirectoryLister, FileEntry, join_path, parent_path};
+use super::backend::DockerBackend;
+use super::changes::{PathChange, path_change};
+use super::compose::{
+    NETWORK_LABEL, PROJECT_LABEL, SERVICE_LABEL, VOLUME_LABEL, port_config, service_config,
+};
+use super::compose_file::{ComposePort, ComposeProject, ServiceDefinition};
+use super::create::{self, ContainerSpec};
+use super::disk_usage::{DiskUsage, disk_usage};
+use super::docker::{
+    ContainerInfo, ImageInfo, VolumeInfo, container_info, image_info, volume_info,
+};
+use super::engine::{EngineInfo, engine_info};
+use super::error::DockerError;
+use super::health::{self, ContainerHealth};
+use super::limits::{self, ContainerLimits};
+use super::networks::{NetworkDetails, NetworkInfo, NetworkSpec, network_details, network_info};
+use super::ports::{PublishedPort, bound_ports};
+use super::processes::ProcessList;
+use super::snapshot::CommitSpec;
+use super::topology::{NetworkTopology, TopologyNetwork, container_links};
+
+const MB: i64 = 1024 * 1024;
+
+/// An in-memory engine for demos and tests. It holds the same models the Engine
+/// API returns and maps them through the same functions as `DockerService`.
+#[derive(Clone, Default)]
+pub struct FakeEngine {
+    state: Arc<Mutex<FakeState>>,
+}
+
+#[derive(Default)]
+struct FakeState {
+    containers: Vec<FakeContainer>,
+    images: Vec<ImageSummary>,
+    volumes: Vec<FakeVolume>,
+    networks: Vec<Network>,
+    /// Counts created objects so their ids stay unique after removals.
+    serial: u64,
+}
+
+/// A container as both the list and the inspect endpoints describe it.
+struct FakeContainer {
+    summary: ContainerSummary,
+    details: ContainerInspectResponse,
+    files: FakeFiles,
+    changes: Vec<FilesystemChange>,
+}
+
+struct FakeVolume {
+    volume: Volume,
+    files: FakeFiles,
+}
+
+/// A filesystem kept as absolute paths; directories have no contents.
+#[derive(Default)]
+struct FakeFiles(BTreeMap<String, Option<Vec<u8>>>);
+
+impl FakeEngine {
+    /// A small Compose project plus a few loose objects, enough to exercise every view.
+    pub fn demo() -> Result<Self> {
+        let mut state = FakeState {
+            images: vec![
+                fake_image("nginx:1.27", 192 * MB, 2),
+                fake_image("postgres:16", 438 * MB, 1),
+                fake_image("redis:7", 117 * MB, 1),
+                fake_image("busybox:latest", 4 * MB, 0),
+            ],
+            volumes: vec![
+                fake_volume(
+                    "shop_db-data",
+                    96 * MB,
+                    1,
+                    &[("/PG_VERSION", "16\n"), ("/base/1/PG_VERSION", "16\n")],
+                ),
+                fake_volume("scratch", 12 * MB, 0, &[("/notes.txt", "Scratch space\n")]),
+            ],
+            networks: vec![
+                fake_network("bridge", Some("172.17.0.0/16")),
+                fake_network("host", None),
+                fake_network("none", None),
+                fake_network("shop_default", Some("172.18.0.0/16")),
+            ],
+            ..Default::default()
+        };
+        // Tagged twice, like an image pulled both as `latest` and by version.
+        state.images[3].repo_tags.push("busybox:1.36".to_string());
+
+        state.create(
+            "shop-web-1",
+            demo_config("nginx:1.27", Some(("shop", "web")), &[(80, Some(8080))]),
+        )?;
+        let mut db = demo_config("postgres:16", Some(("shop", "db")), &[(5432, None)]);
+        db.host_config.get_or_insert_default().binds =
+            Some(vec!["shop_db-data:/var/lib/postgresql/data".to_string()]);
+        state.create("shop-db-1", db)?;
+        state.create("cache", demo_config("redis:7", None, &[(6379, None)]))?;
+        // Stopped, and bound to the same host port as shop-web-1
+        state.create("docs", demo_config("nginx:1.27", None, &[(80, Some(8080))]))?;
+
+        let web = state.container_mut("shop-web-1")?;
+        web.write_file("/run/nginx.pid", b"1\n".to_vec());
+        web.set_running(true);
+
+        let db = state.container_mut("shop-db-1")?;
+        let host = db.details.host_config.get_or_insert_default();
+        host.memory = Some(512 * MB);
+        host.pids_limit = Some(200);
+        db.details.config.get_or_insert_default().healthcheck = Some(HealthConfig {
+            test: Some(vec![
+                "CMD-SHELL".to_string(),
+                "pg_isready -U postgres".to_string(),
+            ]),
+            ..Default::default()
+        });
+        let check = |start: &str| HealthcheckResult {
+            start: Some(start.to_string()),
+            exit_code: Some(0),
+            output: Some("/var/run/postgresql:5432 - accepting connections\n".to_string()),
+            ..Default::default()
+        };
+        db.details.state.get_or_insert_default().health = Some(Health {
+            status: Some(HealthStatusEnum::HEALTHY),
+            failing_streak: Some(0),
+            log: Some(vec![
+                check("2024-05-01T09:00:00Z"),
+                check("2024-05-01T09:00:30Z"),
+            ]),
+        });
+        db.write_file("/var/lib/postgresql/data/postmaster.pid", b"1\n".to_vec());
+        db.set_running(true);
+
+        Ok(Self {
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, FakeState>> {
+        // A panic while the state was locked may have left it half updated
+        self.state
+            .lock()
+            .map_err(|_| anyhow!("The fake engine's state was poisoned by an earlier panic"))
+    }
+}
+
+impl FakeState {
+    fn container(&self, reference: &str) -> Result<&FakeContainer> {
+        self.containers
+            .iter()
+            .find(|container| container.matches(reference))
+            .ok_or_else(|| no_such_container(reference))
+    }
+
+    fn container_mut(&mut self, reference: &str) -> Result<&mut FakeContainer> {
+        self.containers
+            .iter_mut()
+            .find(|container| container.matches(reference))
+            .ok_or_else(|| no_such_container(reference))
+    }
+
+    fn volume_mut(&mut self, name: &str) -> Result<&mut FakeVolume> {
+        self.volumes
+            .iter_mut()
+            .find(|volume| volume.volume.name == name)
+            .ok_or_else(|| DockerError::NotFound(format!("get {}: no such volume", name)).into())
+    }
+
+    /// Finds a network by name or id prefix, as the engine resolves references.
+    fn network(&self, reference: &str) -> Result<&Network> {
+        self.networks
+            .iter()
+            .find(|network| {
+                network.name.as_deref() == Some(reference)
+                    || network
+                        .id
+                        .as_deref()
+                        .is_some_and(|id| !reference.is_empty() && id.starts_with(reference))
+            })
+            .ok_or_else(|| DockerError::NotFound(format!("network {} not found", reference)).into())
+    }
+
+    fn next_id(&mut self, seed: &str) -> String {
+        self.serial += 1;
+        fake_id(&format!("{}#{}", seed, self.serial))
+    }
+
+    /// Adds `image` to the cache unless it is there, like a pull.
+    fn ensure_image(&mut self, image: &str) {
+        let reference = if image.rsplit('/').next().unwrap_or(image).contains(':') {
+            image.to_string()
+        } else {
+            format!("{}:latest", image)
+        };
+        let cached = self
+            .images
+            .iter()
+            .any(|summary| summary.repo_tags.contains(&reference));
+        if !cached {
+            self.images.push(fake_image(&reference, 8 * MB, 0));
+        }
+    }
+
+    /// Creates a stopped container from a create request and returns its id.
+    fn create(&mut self, name: &str, config: Config<String>) -> Result<String> {
+        let name = if name.is_empty() {
+            format!("container_{}", self.serial + 1)
+        } else {
+            name.to_string()
+        };
+        if self.container(&name).is_ok() {
+            return Err(DockerError::Conflict(format!(
+                "Conflict. The container name \"/{}\" is already in use",
+                name
+            ))
+            .into());
+        }
+
+        let host_config = config.host_config.clone().unwrap_or_default();
+        let mut endpoints = config
+            .networking_config
+            .clone()
+            .map(|networking| networking.endpoints_config)
+            .unwrap_or_default();
+        let network = match host_config.network_mode.as_deref() {
+            None | Some("default") => "bridge",
+            Some(mode) if mode.starts_with("container:") => "",
+            Some(mode) => mode,
+        }
+        .to_string();
+        if !network.is_empty() {
+            self.network(&network)?;
+        }
+
+        let id = self.next_id(&name);
+        let image = config.image.clone().unwrap_or_default();
+        let mut container = FakeContainer {
+            summary: ContainerSummary {
+                id: Some(id.clone()),
+                names: Some(vec![format!("/{}", name)]),
+                image: Some(image.clone()),
+                labels: config.labels.clone(),
+                ports: Some(Vec::new()),
+                mounts: Some(mounts(&host_config)),
+                size_rw: Some(2 * MB),
+                state: Some("created".to_string()),
+                status: Some("Created".to_string()),
+                ..Default::default()
+            },
+            details: ContainerInspectResponse {
+                id: Some(id.clone()),
+                name: Some(format!("/{}", name)),
+                image: Some(image.clone()),
+                config: Some(inspect_config(config)),
+                host_config: Some(host_config),
+                state: Some(bollard::models::ContainerState {
+                    status: Some(ContainerStateStatusEnum::CREATED),
+                    running: Some(false),
+                    ..Default::default()
+                }),
+                network_settings: Some(NetworkSettings {
+                    networks: Some(HashMap::new()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            files: image_files(&name, &image),
+            changes: Vec::new(),
+        };
+        container.set_running(false);
+        self.containers.push(container);
+
+        if !network.is_empty() {
+            let aliases = endpoints
+                .remove(&network)
+                .and_then(|endpoint| endpoint.aliases)
+                .unwrap_or_default();
+            self.connect(&network, &id, aliases)?;
+        }
+        Ok(id)
+    }
+
+    /// Starts the container unless another running one holds a host port it binds.
+    fn start(&mut self, reference: &str) -> Result<()> {
+        let container = self.container(reference)?;
+        for port in container.bound_ports() {
+            let taken = self
+                .containers
+                .iter()
+                .filter(|other| other.is_running() && !other.matches(reference))
+                .any(|other| {
+                    other
+                        .bound_ports()
+                        .iter()
+                        .any(|theirs| port.overlaps(theirs))
+                });
+            if taken {
+                let address = port.host_address().unwrap_or_default();
+                return Err(DockerError::Other(format!(
+                    "driver failed programming external connectivity on endpoint {}: Bind for {} failed: port is already allocated",
+                    container.name(),
+                    address
+                ))
+                .into());
+            }
+        }
+        self.container_mut(reference)?.set_running(true);
+        Ok(())
+    }
+
+    fn remove_container(&mut self, reference: &str, force: bool) -> Result<()> {
+        if !force && self.container(reference)?.is_running() {
+            return Err(DockerError::Conflict(format!(
+                "cannot remove container {}: container is running",
+                reference
+            ))
+            .into());
+        }
+        self.containers
+            .retain(|container| !container.matches(reference));
+        Ok(())
+    }
+
+    fn create_network(&mut self, spec: &NetworkSpec) -> Result<String> {
+        if self.network(&spec.name).is_ok() {
+            return Err(DockerError::Conflict(format!(
+                "network with name {} already exists",
+                spec.name
+            ))
+            .into());
+        }
+        // Like the engine, pick the next free /16 when no subnet is given
+        let subnet = spec
+            .subnet
+            .clone()
+            .unwrap_or_else(|| format!("172.{}.0.0/16", 17 + self.networks.len()));
+        let id = self.next_id(&spec.name);
+        let mut network = fake_network(&spec.name, Some(&subnet));
+        network.id = Some(id.clone());
+        network.driver = Some(spec.driver.clone());
+        network.labels = Some(spec.labels.iter().cloned().collect());
+        if let Some(gateway) = &spec.gateway
+            && let Some(config) = network
+                .ipam
+                .as_mut()
+                .and_then(|ipam| ipam.config.as_mut())
+                .and_then(|configs| configs.first_mut())
+        {
+            config.gateway = Some(gateway.clone());
+        }
+        self.networks.push(network);
+        Ok(id)
+    }
+
+    fn remove_network(&mut self, reference: &str) -> Result<()> {
+        let network = self.network(reference)?;
+        let name = network.name.clone().unwrap_or_default();
+        if matches!(name.as_str(), "bridge" | "host" | "none") {
+            return Err(DockerError::Conflict(format!(
+                "{} is a pre-defined network and cannot be removed",
+                name
+            ))
+            .into());
+        }
+        if self
+            .containers
+            .iter()
+            .any(|container| container.endpoint(&name).is_some())
+        {
+            return Err(DockerError::Conflict(format!(
+                "error while removing network: network {} has active endpoints",
+                name
+            ))
+            .into());
+        }
+        self.networks
+            .retain(|network| network.name.as_deref() != Some(name.as_str()));
+        Ok(())
+    }
+
+    fn connect(&mut self, network: &str, container: &str, aliases: Vec<String>) -> Result<()> {
+        let network = self.network(network)?.clone();
+        let name = network.name.clone().unwrap_or_default();
+        let address = self.next_address(&network);
+        let container = self.container_mut(container)?;
+        if container.endpoint(&name).is_some() {
+            return Err(DockerError::Conflict(format!(
+                "endpoint with name {} already exists in network {}",
+                container.name(),
+                name
+            ))
+            .into());
+        }
+        let gateway = ipam_config(&network).and_then(|config| config.gateway.clone());
+        let endpoint = EndpointSettings {
+            network_id: network.id.clone(),
+            aliases: (!aliases.is_empty()).then_some(aliases),
+            ip_prefix_len: address.as_ref().map(|_| 16),
+            ip_address: address,
+            gateway,
+            ..Default::default()
+        };
+        container
+            .details
+            .network_settings
+            .get_or_insert_default()
+            .networks
+            .get_or_insert_default()
+            .insert(name, endpoint);
+        Ok(())
+    }
+
+    fn disconnect(&mut self, network: &str, container: &str) -> Result<()> {
+        let name = self.network(network)?.name.clone().unwrap_or_default();
+        let container = self.container_mut(container)?;
+        let removed = container
+            .details
+            .network_settings
+            .as_mut()
+            .and_then(|settings| settings.networks.as_mut())
+            .and_then(|networks| networks.remove(&name));
+        if removed.is_none() {
+            return Err(DockerError::Conflict(format!(
+                "container {} is not connected to network {}",
+                container.name(),
+                name
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// The lowest free address in the network's subnet, for networks that have one.
+    fn next_address(&self, network: &Network) -> Option<String> {
+        let subnet = ipam_config(network)?.subnet.as_ref()?;
+        let prefix = subnet.split('/').next()?.rsplit_once('.')?.0;
+        let name = network.name.as_deref().unwrap_or_default();
+        let taken: Vec<&str> = self
+            .containers
+            .iter()
+            .filter_map(|container| container.endpoint(name)?.ip_address.as_deref())
+            .collect();
+        (2..255)
+            .map(|host| format!("{}.{}", prefix, host))
+            .find(|address| !taken.contains(&address.as_str()))
+    }
+}
+
+impl FakeContainer {
+    /// Matches the way the engine resolves references: full or short id, or name.
+    fn matches(&self, reference: &str) -> bool {
+        let id_matches = self
+            .summary
+            .id
+            .as_deref()
+            .is_some_and(|id| !reference.is_empty() && id.starts_with(reference));
+        id_matches || self.name() == reference.trim_start_matches('/')
+    }
+
+    fn name(&self) -> &str {
+        self.details
+            .name
+            .as_deref()
+            .unwrap_or_default()
+            .trim_start_matches('/')
+    }
+
+    fn is_running(&self) -> bool {
+        self.summary.state.as_deref() == Some("running")
+    }
+
+    fn bound_ports(&self) -> Vec<PublishedPort> {
+        let bindings = self
+            .details
+            .host_config
+            .as_ref()
+            .and_then(|host| host.port_bindings.clone());
+        bound_ports(bindings.unwrap_or_default())
+    }
+
+    fn endpoint(&self, network: &str) -> Option<&EndpointSettings> {
+        self.details
+            .network_settings
+            .as_ref()?
+            .networks
+            .as_ref()?
+            .get(network)
+    }
+
+    /// Updates both views of the container. Like the engine, only a running
+    /// container lists its ports.
+    fn set_running(&mut self, running: bool) {
+        let health = self
+            .details
+            .state
+            .as_ref()
+            .and_then(|state| state.health.as_ref())
+            .and_then(|health| health.status);
+        let (state, status) = match (running, self.summary.state.as_deref()) {
+            (true, _) => ("running", "Up Less than a second"),
+            (false, Some("created")) => ("created", "Created"),
+            (false, _) => ("exited", "Exited (0) Less than a second ago"),
+        };
+        let suffix = match health {
+            Some(HealthStatusEnum::HEALTHY) if running => " (healthy)",
+            Some(HealthStatusEnum::UNHEALTHY) if running => " (unhealthy)",
+            Some(HealthStatusEnum::STARTING) if running => " (health: starting)",
+            _ => "",
+        };
+        self.summary.state = Some(state.to_string());
+        self.summary.status = Some(format!("{}{}", status, suffix));
+        self.summary.ports = Some(if running {
+            summary_ports(&self.details)
+        } else {
+            Vec::new()
+        });
+
+        let details_state = self.details.state.get_or_insert_default();
+        details_state.running = Some(running);
+        details_state.status = Some(match state {
+            "running" => ContainerStateStatusEnum::RUNNING,
+            "created" => ContainerStateStatusEnum::CREATED,
+            _ => ContainerStateStatusEnum::EXITED,
+        });
+        details_state.exit_code = Some(0);
+        let bindings = self
+            .details
+            .host_config
+            .as_ref()
+            .and_then(|host| host.port_bindings.clone());
+        self.details.network_settings.get_or_insert_default().ports =
+            if running { bindings } else { None };
+    }
+
+    /// Writes a file into the container's layer and records the change.
+    fn write_file(&mut self, path: &str, contents: Vec<u8>) {
+        let added = self.files.insert(path, Some(contents));
+        self.record_change(path, added);
+    }
+
+    fn record_change(&mut self, path: &str, added: bool) {
+        if self.changes.iter().any(|change| change.path == path) {
+            return;
+        }
+        self.changes.push(FilesystemChange {
+            path: path.to_string(),
+            kind: if added {
+                ChangeType::_1
+            } else {
+                ChangeType::_0
+            },
+        });
+    }
+}
+
+impl FakeFiles {
+    fn new(files: &[(&str, &str)]) -> Self {
+        let mut tree = Self::default();
+        for (path, contents) in files {
+            tree.insert(path, Some(contents.as_bytes().to_vec()));
+        }
+        tree
+    }
+
+    fn is_directory(&self, path: &str) -> bool {
+        path == "/" || self.0.get(path).is_some_and(Option::is_none)
+    }
+
+    /// Stores a file, or a directory when `contents` is `None`, creating its
+    /// parents. Returns whether the path is new.
+    fn insert(&mut self, path: &str, contents: Option<Vec<u8>>) -> bool {
+        let path = normalize(path);
+        let mut parent = parent_path(&path);
+        while parent != "/" {
+            self.0.entry(parent.clone()).or_insert(None);
+            parent = parent_path(&parent);
+        }
+        self.0.insert(path, contents).is_none()
+    }
+
+    /// Archives `path` the way the archive endpoint does: the path itself comes
+    /// first, named by its last component, followed by everything below it.
+    fn archive(&self, path: &str) -> Result<Vec<u8>> {
+        let path = normalize(path);
+        if !self.is_directory(&path) && !self.0.contains_key(&path) {
+            return Err(DockerError::NotFound(format!("Could not find the file {}", path)).into());
+        }
+        let base = parent_path(&path);
+        let below = format!("{}/", path.trim_end_matches('/'));
+
+        let mut builder = tar::Builder::new(Vec::new());
+        for (entry, contents) in self
+            .0
+            .iter()
+            .filter(|(entry, _)| **entry == path || entry.starts_with(&below))
+        {
+            let name = entry[base.len()..].trim_start_matches('/');
+            let mut header = tar::Header::new_gnu();
+            match contents {
+                Some(data) => {
+                    header.set_size(data.len() as u64);
+                    header.set_mode(0o644);
+                    builder.append_data(&mut header, name, data.as_slice())?;
+                }
+                None => {
+                    header.set_entry_type(tar::EntryType::Directory);
+                    header.set_size(0);
+                    header.set_mode(0o755);
+                    builder.append_data(&mut header, name, std::io::empty())?;
+                }
+            }
+        }
+        Ok(builder.into_inner()?)
+    }
+
+    /// Extracts an uploaded archive into `dir`, returning each path written and
+    /// whether it is new.
+    fn unpack(&mut self, dir: &str, archive: &[u8]) -> Result<Vec<(String, bool)>> {
+        let dir = normalize(dir);
+        if !self.is_directory(&dir) {
+            return Err(DockerError::NotFound(format!("Could not find the file {}", dir)).into());
+        }
+        let mut written = Vec::new();
+        for entry in tar::Archive::new(archive).entries()? {
+            let mut entry = entry?;
+            let name = entry.path()?.to_string_lossy().into_owned();
+            let path = join_path(&dir, name.trim_end_matches('/'));
+            let contents = if entry.header().entry_type().is_dir() {
+                None
+            } else {
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data)?;
+                Some(data)
+            };
+            let added = self.insert(&path, contents);
+            written.push((path, added));
+        }
+        Ok(written)
+    }
+}
+
+#[async_trait]
+impl DockerBackend for FakeEngine {
+    fn host(&self) -> String {
+        "fake://in-memory".to_string()
+    }
+
+    async fn engine_info(&self) -> Result<EngineInfo> {
+        let info = SystemInfo {
+            name: Some("doctainr-fake".to_string()),
+            operating_system: Some("In-memory fake engine".to_string()),
+            architecture: Some(std::env::consts::ARCH.to_string()),
+            kernel_version: Some("--".to_string()),
+            driver: Some("memory".to_string()),
+            cgroup_version: Some(SystemInfoCgroupVersionEnum::_2),
+            ncpu: Some(4),
+            mem_total: Some(8 * 1024 * MB),
+            ..Default::default()
+        };
+        let version = Version {
+            version: Some("27.0.0-fake".to_string()),
+            api_version: Some("1.47".to_string()),
+            min_api_version: Some("1.24".to_string()),
+            ..Default::default()
+        };
+        Ok(engine_info(info, version))
+    }
+
+    async fn disk_usage(&self) -> Result<DiskUsage> {
+        let state = self.state()?;
+        Ok(disk_usage(SystemDataUsageResponse {
+            images: Some(state.images.clone()),
+            containers: Some(
+                state
+                    .containers
+                    .iter()
+                    .map(|container| container.summary.clone())
+                    .collect(),
+            ),
+            volumes: Some(
+                state
+                    .volumes
+                    .iter()
+                    .map(|volume| volume.volume.clone())
+                    .collect(),
+            ),
+            ..Default::default()
+        }))
+    }
+
+    async fn list_containers(&self) -> Result<Vec<ContainerInfo>> {
+        let state = self.state()?;
+        Ok(state
+            .containers
+            .iter()
+            .map(|container| container_info(container.summary.clone()))
+            .collect())
+    }
+
+    async fn list_images(&self) -> Result<Vec<ImageInfo>> {
+        let state = self.state()?;
+        Ok(state.images.iter().cloned().map(image_info).collect())
+    }
+
+    async fn list_volumes(&self) -> Result<Vec<VolumeInfo>> {
+        let state = self.state()?;
+        Ok(state
+            .volumes
+            .iter()
+            .map(|volume| volume_info(volume.volume.clone()))
+            .collect())
+    }
+
+    async fn list_networks(&self) -> Result<Vec<NetworkInfo>> {
+        let state = self.state()?;
+        let mut networks: Vec<NetworkInfo> = state.networks.iter().map(network_info).collect();
+        networks.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(networks)
+    }
+
+    // Like the real engine, starting a running container or stopping a stopped one
+    // succeeds without changing anything
+    async fn port_bindings(&self, ids: &[String]) -> Result<HashMap<String, Vec<PublishedPort>>> {
+        let state = self.state()?;
+        Ok(ids
+            .iter()
+            .filter_map(|id| Some((id.clone(), state.container(id).ok()?.bound_ports())))
+            .collect())
+    }
+
+    async fn start_container(&self, id: &str) -> Result<()> {
+        let mut state = self.state()?;
+        if !state.container(id)?.is_running() {
+            state.start(id)?;
+        }
+        Ok(())
+    }
+
+    async fn stop_container(&self, id: &str) -> Result<()> {
+        let mut state = self.state()?;
+        let container = state.container_mut(id)?;
+        if container.is_running() {
+            container.set_running(false);
+        }
+        Ok(())
+    }
+
+    async fn restart_container(&self, id: &str) -> Result<()> {
+        self.state()?.start(id)
+    }
+
+    async fn remove_container(&self, id: &str) -> Result<()> {
+        self.state()?.remove_container(id, false)
+    }
+
+    async fn remove_image(&self, id: &str) -> Result<()> {
+        let mut state = self.state()?;
+        let index = state
+            .images
+            .iter()
+            .position(|image| image.id == id || image.repo_tags.iter().any(|tag| tag == id))
+            .ok_or_else(|| DockerError::NotFound(format!("No such image: {}", id)))?;
+        let tags = state.images[index].repo_tags.clone();
+        if tags.len() > 1 {
+            if !tags.iter().any(|tag| tag == id) {
+                return Err(DockerError::Conflict(format!(
+                    "unable to delete {} (must be forced) - image is referenced in multiple repositories",
+                    id
+                ))
+                .into());
+            }
+            state.images[index].repo_tags.retain(|tag| tag != id);
+            return Ok(());
+        }
+        let in_use = state.containers.iter().any(|container| {
+            container
+                .summary
+                .image
+                .as_ref()
+                .is_some_and(|image| tags.contains(image))
+        });
+        if in_use {
+            return Err(DockerError::Conflict(format!(
+                "unable to delete {}: image is being used by a container",
+                id
+            ))
+            .into());
+        }
+        state.images.remove(index);
+        Ok(())
+    }
+
+    async fn remove_volume(&self, name: &str) -> Result<()> {
+        let mut state = self.state()?;
+        state.volume_mut(name)?;
+        let in_use = state.containers.iter().any(|container| {
+            container
+                .summary
+                .mounts
+                .iter()
+                .flatten()
+                .any(|mount| mount.name.as_deref() == Some(name))
+        });
+        if in_use {
+            return Err(DockerError::Conflict(format!("remove {}: volume is in use", name)).into());
+        }
+        state.volumes.retain(|volume| volume.volume.name != name);
+        Ok(())
+    }
+
+    async fn inspect_network(&self, id: &str) -> Result<NetworkDetails> {
+        let state = self.state()?;
+        let mut network = state.network(id)?.clone();
+        let name = network.name.clone().unwrap_or_default();
+        let containers = state
+            .containers
+            .iter()
+            .filter_map(|container| {
+                let endpoint = container.endpoint(&name)?;
+                let attached = NetworkContainer {
+                    name: Some(container.name().to_string()),
+                    ipv4_address: endpoint.ip_address.as_ref().map(|address| {
+                        format!("{}/{}", address, endpoint.ip_prefix_len.unwrap_or(16))
+                    }),
+                    ..Default::default()
+                };
+                Some((container.summary.id.clone().unwrap_or_default(), attached))
+            })
+            .collect();
+        network.containers = Some(containers);
+        Ok(network_details(network))
+    }
+
+    async fn network_topology(&self) -> Result<NetworkTopology> {
+        let networks = self.list_networks().await?;
+        let state = self.state()?;
+        let mut topology = NetworkTopology {
+            networks: networks
+                .into_iter()
+                .map(|network| TopologyNetwork {
+                    id: network.id,
+                    name: network.name,
+                    driver: network.driver,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        for container in &state.containers {
+            let listed = container_info(container.summary.clone());
+            let (container, links) =
+                container_links(&topology.networks, container.details.clone(), listed.state);
+            topology.containers.push(container);
+            topology.links.extend(links);
+        }
+        Ok(topology)
+    }
+
+    async fn create_network(&self, spec: &NetworkSpec) -> Result<String> {
+        self.state()?.create_network(spec)
+    }
+
+    async fn remove_network(&self, id: &str) -> Result<()> {
+        self.state()?.remove_network(id)
+    }
+
+    async fn connect_network(
+        &self,
+        network: &str,
+        container: &str,
+        aliases: Vec<String>,
+    ) -> Result<()> {
+        self.state()?.connect(network, container, aliases)
+    }
+
+    async fn disconnect_network(&self, network: &str, container: &str) -> Result<()> {
+        self.state()?.disconnect(network, container)
+    }
+
+    async fn list_volume_files(&self, volume: &str, path: &str) -> Result<Vec<FileEntry>> {
+        let archive = self.state()?.volume_mut(volume)?.files.archive(path)?;
+        list_archive(&archive, path)
+    }
+
+    async fn download_volume_path(
+        &self,
+        volume: &str,
+        path: &str,
+        destination: &Path,
+    ) -> Result<u64> {
+        let archive = self.state()?.volume_mut(volume)?.files.archive(path)?;
+        tokio::fs::write(destination, &archive).await?;
+        Ok(archive.len() as u64)
+    }
+
+    async fn upload_to_volume(&self, volume: &str, dir: &str, sources: Vec<PathBuf>) -> Result<()> {
+        let archive = collect_upload(sources).await?;
+        self.state()?
+            .volume_mut(volume)?
+            .files
+            .unpack(dir, &archive)?;
+        Ok(())
+    }
+
+    async fn list_container_files(&self, id: &str, path: &str) -> Result<Vec<FileEntry>> {
+        let archive = self.state()?.container(id)?.files.archive(path)?;
+        list_archive(&archive, path)
+    }
+
+    async fn download_container_path(
+        &self,
+        id: &str,
+        path: &str,
+        destination: &Path,
+    ) -> Result<u64> {
+        let archive = self.state()?.container(id)?.files.archive(path)?;
+        tokio::fs::write(destination, &archive).await?;
+        Ok(archive.len() as u64)
+    }
+
+    async fn upload_to_container(&self, id: &str, dir: &str, sources: Vec<PathBuf>) -> Result<()> {
+        let archive = collect_upload(sources).await?;
+        let mut state = self.state()?;
+        let container = state.container_mut(id)?;
+        for (path, added) in container.files.unpack(dir, &archive)? {
+            container.record_change(&path, added);
+        }
+        Ok(())
+    }
+
+    async fn container_changes(&self, id: &str) -> Result<Vec<PathChange>> {
+        let state = self.state()?;
+        Ok(state
+            .container(id)?
+            .changes
+            .iter()
+            .cloned()
+            .map(path_change)
+            .collect())
+    }
+
+    async fn commit_container(&self, id: &str, spec: &CommitSpec) -> Result<String> {
+        let mut state = self.state()?;
+        let container = state.container(id)?;
+        let base = container.summary.image.clone().unwrap_or_default();
+        let size = state
+            .images
+            .iter()
+            .find(|image| image.repo_tags.contains(&base))
+            .map_or(0, |image| image.size)
+            + container.summary.size_rw.unwrap_or_default();
+
+        let mut image = fake_image("", size, 0);
+        image.id = format!("sha256:{}", state.next_id(id));
+        image.repo_tags = if spec.repo.is_empty() {
+            Vec::new()
+        } else if spec.tag.is_empty() {
+            vec![format!("{}:latest", spec.repo)]
+        } else {
+            vec![format!("{}:{}", spec.repo, spec.tag)]
+        };
+        let committed = image.id.clone();
+        state.images.push(image);
+        Ok(committed)
+    }
+
+    async fn export_container(&self, id: &str, destination: &Path) -> Result<u64> {
+        let archive = self.state()?.container(id)?.files.archive("/")?;
+        tokio::fs::write(destination, &archive).await?;
+        Ok(archive.len() as u64)
+    }
+
+    // Answers like `ps -ef` whatever the arguments, with the container's command as PID 1
+    async fn top_processes(&self, id: &str, _ps_args: &str) -> Result<ProcessList> {
+        let state = self.state()?;
+        let container = state.container(id)?;
+        if !container.is_running() {
+            return Err(DockerError::Conflict(format!("Container {} is not running", id)).into());
+        }
+        let config = container.details.config.clone().unwrap_or_default();
+        let command: Vec<String> = config
+            .entrypoint
+            .unwrap_or_default()
+            .into_iter()
+            .chain(config.cmd.unwrap_or_default())
+            .collect();
+        let command = if command.is_empty() {
+            container.summary.image.clone().unwrap_or_default()
+        } else {
+            command.join(" ")
+        };
+        let row = |pid: &str, ppid: &str, command: String| {
+            ["root", pid, ppid, "0", "09:00", "?", "00:00:00"]
+                .into_iter()
+                .map(str::to_string)
+                .chain([command])
+                .collect()
+        };
+        Ok(ProcessList {
+            titles: ["UID", "PID", "PPID", "C", "STIME", "TTY", "TIME", "CMD"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            processes: vec![row("4211", "4190", command)],
+        })
+    }
+
+    async fn container_limits(&self, id: &str) -> Result<ContainerLimits> {
+        let state = self.state()?;
+        let host = state
+            .container(id)?
+            .details
+            .host_config
+            .clone()
+            .unwrap_or_default();
+        Ok(limits::container_limits(host))
+    }
+
+    async fn update_limits(
+        &self,
+        id: &str,
+        current: &ContainerLimits,
+        limits: &ContainerLimits,
+    ) -> Result<()> {
+        let mut state = self.state()?;
+        let host = state
+            .container_mut(id)?
+            .details
+            .host_config
+            .get_or_insert_default();
+        apply_update(host, limits::update_options(current, limits));
+        Ok(())
+    }
+
+    async fn container_spec(&self, id: &str) -> Result<ContainerSpec> {
+        let state = self.state()?;
+        let details = state.container(id)?.details.clone();
+        Ok(create::container_spec(details, ImageConfig::default()))
+    }
+
+    async fn create_container(&self, spec: &ContainerSpec) -> Result<String> {
+        let mut state = self.state()?;
+        state.ensure_image(&spec.image);
+        state.create(&spec.name, create::container_config(spec))
+    }
+
+    async fn rename_container(&self, id: &str, name: &str) -> Result<()> {
+        let mut state = self.state()?;
+        if state.container(name).is_ok() {
+            return Err(DockerError::Conflict(format!(
+                "Conflict. The container name \"/{}\" is already in use",
+                name
+            ))
+            .into());
+        }
+        let container = state.container_mut(id)?;
+        container.summary.names = Some(vec![format!("/{}", name)]);
+        container.details.name = Some(format!("/{}", name));
+        Ok(())
+    }
+
+    async fn container_health(&self, id: &str) -> Result<ContainerHealth> {
+        let state = self.state()?;
+        Ok(health::container_health(
+            state.container(id)?.details.clone(),
+        ))
+    }
+
+    async fn compose_prepare(&self, project: &ComposeProject) -> Result<()> {
+        let mut state = self.state()?;
+        for network in project.networks.iter().filter(|network| !network.external) {
+            if state.network(&network.name).is_err() {
+                state.create_network(&NetworkSpec {
+                    name: network.name.clone(),
+                    driver: network
+                        .driver
+                        .clone()
+                        .unwrap_or_else(|| "bridge".to_string()),
+                    labels: vec![
+                        (PROJECT_LABEL.to_string(), project.name.clone()),
+                        (NETWORK_LABEL.to_string(), network.key.clone()),
+                    ],
+                    ..Default::default()
+                })?;
+            }
+        }
+        for volume in project.volumes.iter().filter(|volume| !volume.external) {
+            if state.volume_mut(&volume.name).is_err() {
+                let mut created = fake_volume(&volume.name, 0, 0, &[]);
+                created.volume.driver =
+                    volume.driver.clone().unwrap_or_else(|| "local".to_string());
+                created.volume.labels = HashMap::from([
+                    (PROJECT_LABEL.to_string(), project.name.clone()),
+                    (VOLUME_LABEL.to_string(), volume.key.clone()),
+                ]);
+                state.volumes.push(created);
+            }
+        }
+        Ok(())
+    }
+
+    async fn compose_up_service(
+        &self,
+        project: &ComposeProject,
+        service: &ServiceDefinition,
+    ) -> Result<&'static str> {
+        let mut state = self.state()?;
+        if let Ok(existing) = state.container(&service.container_name) {
+            if existing.is_running() {
+                return Ok("Already running");
+            }
+            state.start(&service.container_name)?;
+            return Ok("Started");
+        }
+
+        state.ensure_image(&service.image);
+        let id = state.create(&service.container_name, service_config(project, service))?;
+        for (network, aliases) in service.networks.iter().skip(1) {
+            state.connect(network, &id, aliases.clone())?;
+        }
+        state.start(&id)?;
+        Ok("Created and started")
+    }
+
+    async fn compose_down_service(&self, service: &ServiceDefinition) -> Result<&'static str> {
+        let mut state = self.state()?;
+        if state.container(&service.container_name).is_err() {
+            return Ok("Not created");
+        }
+        state.remove_container(&service.container_name, true)?;
+        Ok("Removed")
+    }
+
+    async fn compose_restart_service(&self, service: &ServiceDefinition) -> Result<&'static str> {
+        self.state()?.start(&service.container_name)?;
+        Ok("Restarted")
+    }
+
+    async fn compose_teardown(&self, project: &ComposeProject) -> Result<()> {
+        let mut state = self.state()?;
+        for network in project.networks.iter().filter(|network| !network.external) {
+            if state.network(&network.name).is_ok() {
+                state.remove_network(&network.name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn no_such_container(reference: &str) -> anyhow::Error {
+    DockerError::NotFound(format!("No such container: {}", reference)).into()
+}
+
+/// Lists `path` from its archive through the same reader `DockerService` uses.
+fn list_archive(archive: &[u8], path: &str) -> Result<Vec<FileEntry>> {
+    let mut lister = DirectoryLister::new(path, path);
+    lister.feed(archive)?;
+    Ok(lister.finish())
+}
+
+/// Builds the upload archive the way `DockerService` streams it, then keeps it whole.
+async fn collect_upload(sources: Vec<PathBuf>) -> Result<Vec<u8>> {
+    let (tar, building) = archive::archive_files(sources);
+    let chunks: Vec<Bytes> = tar.collect().await;
+    building.await??;
+    Ok(chunks.concat())
+}
+
+/// Applies an update request the way the engine does: zero CPU and memory
+/// values leave the current setting alone.
+fn apply_update(host: &mut HostConfig, update: UpdateContainerOptions<String>) {
+    let set = |target: &mut Option<i64>, value: Option<i64>| {
+        if let Some(value) = value.filter(|value| *value != 0) {
+            *target = Some(value);
+        }
+    };
+    set(
+        &mut host.cpu_shares,
+        update.cpu_shares.map(|shares| shares as i64),
+    );
+    set(&mut host.cpu_quota, update.cpu_quota);
+    set(&mut host.cpu_period, update.cpu_period);
+    set(&mut host.memory, update.memory);
+    set(&mut host.memory_swap, update.memory_swap);
+    if let Some(pids_limit) = update.pids_limit {
+        host.pids_limit = Some(pids_limit);
+    }
+    if let Some(restart_policy) = update.restart_policy {
+        host.restart_policy = Some(restart_policy);
+    }
+}
+
+/// What inspect reports as the config of a container created from `config`.
+fn inspect_config(config: Config<String>) -> ContainerConfig {
+    ContainerConfig {
+        image: config.image,
+        cmd: config.cmd,
+        entrypoint: config.entrypoint,
+        env: config.env,
+        labels: config.labels,
+        exposed_ports: config.exposed_ports,
+        volumes: config.volumes,
+        working_dir: config.working_dir,
+        user: config.user,
+        healthcheck: config.healthcheck,
+        ..Default::default()
+    }
+}
+
+/// The list entry's mounts for `host`'s binds. A bind whose source is not a path
+/// mounts a named volume.
+fn mounts(host: &HostConfig) -> Vec<MountPoint> {
+    host.binds
+        .iter()
+        .flatten()
+        .filter_map(|bind| {
+            let mut parts = bind.split(':');
+            let source = parts.next()?;
+            let destination = parts.next()?;
+            let typ = if source.starts_with('/') {
+                MountPointTypeEnum::BIND
+            } else {
+                MountPointTypeEnum::VOLUME
+            };
+            Some(MountPoint {
+                name: (typ == MountPointTypeEnum::VOLUME).then(|| source.to_string()),
+                source: Some(source.to_string()),
+                destination: Some(destination.to_string()),
+                typ: Some(typ),
+                rw: Some(parts.next() != Some("ro")),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// The list entry's ports for a running container: one per host binding, or
+/// one without a public port for an exposed port that is not published.
+fn summary_ports(details: &ContainerInspectResponse) -> Vec<Port> {
+    let exposed = details
+        .config
+        .as_ref()
+        .and_then(|config| config.exposed_ports.clone())
+        .unwrap_or_default();
+    let bindings = details
+        .host_config
+        .as_ref()
+        .and_then(|host| host.port_bindings.clone())
+        .unwrap_or_default();
+    let mut keys: Vec<&String> = exposed.keys().chain(bindings.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut ports = Vec::new();
+    for key in keys {
+        let (port, protocol) = key.split_once('/').unwrap_or((key.as_str(), "tcp"));
+        let Ok(private_port) = port.parse() else {
+            continue;
+        };
+        let typ = Some(match protocol {
+            "udp" => PortTypeEnum::UDP,
+            "sctp" => PortTypeEnum::SCTP,
+            _ => PortTypeEnum::TCP,
+        });
+        let published: Vec<_> = bindings.get(key).cloned().flatten().unwrap_or_default();
+        if published.is_empty() {
+            ports.push(Port {
+                private_port,
+                typ,
+                ..Default::default()
+            });
+        }
+        for binding in published {
+            ports.push(Port {
+                ip: Some(
+                    binding
+                        .host_ip
+                        .filter(|ip| !ip.is_empty())
+                        .unwrap_or_else(|| "0.0.0.0".to_string()),
+                ),
+                private_port,
+                public_port: binding.host_port.and_then(|port| port.parse().ok()),
+                typ,
+            });
+        }
+    }
+    ports
+}
+
+fn ipam_config(network: &Network) -> Option<&IpamConfig> {
+    network.ipam.as_ref()?.config.as_ref()?.first()
+}
+
+fn normalize(path: &str) -> String {
+    let path = path.trim_end_matches('/');
+    if path.is_empty() {
+        "/".to_string()
+    } else if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{}", path)
+    }
+}
+
+fn fake_id(seed: &str) -> String {
+    (0..4)
+        .map(|round| {
+            let mut hasher = DefaultHasher::new();
+            (seed, round).hash(&mut hasher);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+fn fake_image(tag: &str, size: i64, containers: i64) -> ImageSummary {
+    ImageSummary {
+        id: format!("sha256:{}", fake_id(tag)),
+        repo_tags: vec![tag.to_string()],
+        size,
+        shared_size: -1,
+        containers,
+        ..Default::default()
+    }
+}
+
+/// A create request like the ones the demo project's containers came from.
+fn demo_config(
+    image: &str,
+    compose: Option<(&str, &str)>,
+    ports: &[(u16, Option<u16>)],
+) -> Config<String> {
+    let ports: Vec<ComposePort> = ports
+        .iter()
+        .map(|&(target, published)| ComposePort {
+            host_ip: None,
+            published: published.map(|port| port.to_string()),
+            target: target.to_string(),
+            protocol: "tcp".to_string(),
+        })
+        .collect();
+    let (exposed_ports, port_bindings) = port_config(&ports);
+    let labels: HashMap<String, String> = compose
+        .map(|(project, service)| {
+            HashMap::from([
+                (PROJECT_LABEL.to_string(), project.to_string()),
+                (SERVICE_LABEL.to_string(), service.to_string()),
+            ])
+        })
+        .unwrap_or_default();
+    let network = compose.map(|(project, _)| format!("{}_default", project));
+
+    Config {
+        image: Some(image.to_string()),
+        labels: Some(labels),
+        exposed_ports: Some(exposed_ports),
+        host_config: Some(HostConfig {
+            port_bindings: Some(port_bindings),
+            network_mode: network.clone(),
+            ..Default::default()
+        }),
+        networking_config: network
+            .zip(compose)
+            .map(|(network, (_, service))| NetworkingConfig {
+                endpoints_config: HashMap::from([(
+                    network,
+                    EndpointSettings {
+                        aliases: Some(vec![service.to_string()]),
+                        ..Default::default()
+                    },
+                )]),
+            }),
+        ..Default::default()
+    }
+}
+
+/// The files a container of `image` starts with.
+fn image_files(name: &str, image: &str) -> FakeFiles {
+    let hostname = format!("{}\n", name);
+    let mut files = vec![
+        ("/etc/hostname", hostname.as_str()),
+        ("/etc/hosts", "127.0.0.1\tlocalhost\n"),
+    ];
+    match image.split(':').next().unwrap_or(image) {
+        "nginx" => files.extend([
+            (
+                "/etc/nginx/nginx.conf",
+                "events {}\nhttp { include conf.d/*.conf; }\n",
+            ),
+            (
+                "/usr/share/nginx/html/index.html",
+                "<h1>Welcome to nginx!</h1>\n",
+            ),
+        ]),
+        "postgres" => files.push(("/var/lib/postgresql/data/PG_VERSION", "16\n")),
+        "redis" => files.push(("/usr/local/etc/redis/redis.conf", "appendonly yes\n")),
+        _ => {}
+    }
+    let mut tree = FakeFiles::new(&files);
+    tree.insert("/tmp", None);
+    tree
+}
+
+fn fake_volume(name: &str, size: i64, ref_count: i64, files: &[(&str, &str)]) -> FakeVolume {
+    FakeVolume {
+        volume: Volume {
+            name: name.to_string(),
+            driver: "local".to_string(),
+            mountpoint: format!("/var/lib/docker/volumes/{}/_data", name),
+            usage_data: Some(VolumeUsageData { size, ref_count }),
+            ..Default::default()
+        },
+        files: FakeFiles::new(files),
+    }
+}
+
+fn fake_network(name: &str, subnet: Option<&str>) -> Network {
+    let driver = match name {
+        "host" | "none" => name,
+        _ => "bridge",
+    };
+    let ipam = subnet.map(|subnet| Ipam {
+        config: Some(vec![IpamConfig {
+            gateway: subnet
+                .split('/')
+                .next()
+                .and_then(|address| address.rsplit_once('.'))
+                .map(|(prefix, _)| format!("{}.1", prefix)),
+            subnet: Some(subnet.to_string()),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    });
+    Network {
+        id: Some(fake_id(name)),
+        name: Some(name.to_string()),
+        driver: Some(driver.to_string()),
+        scope: Some("local".to_string()),
+        internal: Some(false),
+        ipam,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{ContainerState, FileKind, mark_in_use, port_conflicts};
+
+    #[tokio::test]
+    async fn fake_engine_transitions_container_state() {
+        let engine = FakeEngine::demo().unwrap();
+        let cache = |containers: Vec<ContainerInfo>| {
+            containers
+                .into_iter()
+                .find(|container| container.name == "cache")
+                .unwrap()
+        };
+
+        let before = cache(engine.list_containers().await.unwrap());
+        assert_eq!(before.state, ContainerState::Stopped);
+        assert!(before.ports.is_empty());
+
+        engine.start_container(&before.id).await.unwrap();
+        let after = cache(engine.list_containers().await.unwrap());
+        assert_eq!(after.state, ContainerState::Running);
+        assert_eq!(after.status, "Up Less than a second");
+        assert_eq!(after.ports[0].to_string(), "6379/tcp");
+
+        engine.stop_container("cache").await.unwrap();
+        let stopped = cache(engine.list_containers().await.unwrap());
+        assert_eq!(stopped.state, ContainerState::Stopped);
+
+        assert!(engine.start_container("missing").await.is_err());
+
+        engine.rename_container("cache", "redis").await.unwrap();
+        assert!(engine.rename_container("redis", "docs").await.is_err());
+        let names: Vec<String> = engine
+            .list_containers()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|container| container.name)
+            .collect();
+        assert!(names.contains(&"redis".to_string()));
+        assert!(!names.contains(&"cache".to_string()));
+    }
+
+    #[tokio::test]
+    async fn stopped_containers_keep_port_bindings() {
+        let engine = FakeEngine::demo().unwrap();
+        let containers = engine.list_containers().await.unwrap();
+        let ids: Vec<String> = containers
+            .iter()
+            .map(|container| container.id.clone())
+            .collect();
+        let bindings = engine.port_bindings(&ids).await.unwrap();
+
+        let conflicts = port_conflicts(&containers, &bindings);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].containers, vec!["docs", "shop-web-1"]);
+        assert_eq!(conflicts[0].running, vec!["shop-web-1"]);
+
+        assert!(engine.start_container("docs").await.is_err());
+        engine.stop_container("shop-web-1").await.unwrap();
+        engine.start_container("docs").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn removes_multi_tagged_images_by_tag() {
+        let engine = FakeEngine::demo().unwrap();
+        let busybox = |images: Vec<ImageInfo>| {
+            images
+                .into_iter()
+                .find(|image| image.repository == "busybox")
+        };
+
+        let image = busybox(engine.list_images().await.unwrap()).unwrap();
+        assert_eq!(image.tags, vec!["busybox:latest", "busybox:1.36"]);
+        assert!(engine.remove_image(&image.id).await.is_err());
+
+        for reference in image.removal_references() {
+            engine.remove_image(&reference).await.unwrap();
+        }
+        assert!(busybox(engine.list_images().await.unwrap()).is_none());
+    }
+
+    #[tokio::test]
+    async fn volumes_in_use_follow_container_mounts() {
+        let engine = FakeEngine::demo().unwrap();
+        let in_use = |volumes: Vec<VolumeInfo>, containers: Vec<ContainerInfo>| {
+            mark_in_use(volumes, &containers)
+                .into_iter()
+                .filter(|volume| volume.in_use)
+                .map(|volume| volume.name)
+                .collect::<Vec<_>>()
+        };
+
+        let volumes = engine.list_volumes().await.unwrap();
+        let containers = engine.list_containers().await.unwrap();
+        assert_eq!(in_use(volumes, containers), ["shop_db-data"]);
+        assert!(engine.remove_volume("shop_db-data").await.is_err());
+
+        // A stopped container still holds on to its volumes
+        engine.stop_container("shop-db-1").await.unwrap();
+        assert!(engine.remove_volume("shop_db-data").await.is_err());
+
+        engine.remove_container("shop-db-1").await.unwrap();
+        let volumes = engine.list_volumes().await.unwrap();
+        let containers = engine.list_containers().await.unwrap();
+        assert!(in_use(volumes, containers).is_empty());
+        engine.remove_volume("shop_db-data").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn networks_and_topology_follow_connections() {
+        let engine = FakeEngine::demo().unwrap();
+
+        let topology = engine.network_topology().await.unwrap();
+        let shop = topology
+            .networks
+            .iter()
+            .find(|network| network.name == "shop_default")
+            .unwrap();
+        assert_eq!(topology.links_for_network(&shop.id).count(), 2);
+
+        let spec = NetworkSpec {
+            name: "backend".to_string(),
+            driver: "bridge".to_string(),
+            ..Default::default()
+        };
+        engine.create_network(&spec).await.unwrap();
+        assert!(engine.create_network(&spec).await.is_err());
+        engine
+            .connect_network("backend", "cache", vec!["redis".to_string()])
+            .await
+            .unwrap();
+
+        let details = engine.inspect_network("backend").await.unwrap();
+        assert_eq!(details.endpoints.len(), 1);
+        assert_eq!(details.endpoints[0].container_name, "cache");
+        assert!(engine.remove_network("backend").await.is_err());
+
+        engine.disconnect_network("backend", "cache").await.unwrap();
+        engine.remove_network("backend").await.unwrap();
+        assert!(engine.remove_network("bridge").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn browses_and_uploads_files() {
+        let engine = FakeEngine::demo().unwrap();
+
+        let root = engine.list_container_files("docs", "/").await.unwrap();
+        let etc = root.iter().find(|entry| entry.name == "etc").unwrap();
+        assert_eq!(etc.kind, FileKind::Directory);
+        let nginx = engine
+            .list_container_files("docs", "/etc/nginx")
+            .await
+            .unwrap();
+        assert_eq!(nginx[0].path, "/etc/nginx/nginx.conf");
+
+        let source = std::env::temp_dir().join(format!("doctainr-fake-{}.txt", std::process::id()));
+        std::fs::write(&source, "hello").unwrap();
+        engine
+            .upload_to_container("docs", "/tmp", vec![source.clone()])
+            .await
+            .unwrap();
+        engine
+            .upload_to_volume("scratch", "/", vec![source.clone()])
+            .await
+            .unwrap();
+        std::fs::remove_file(&source).unwrap();
+
+        let name = source.file_name().unwrap().to_string_lossy().into_owned();
+        let tmp = engine.list_container_files("docs", "/tmp").await.unwrap();
+        assert_eq!(tmp[0].name, name);
+        let changes = engine.container_changes("docs").await.unwrap();
+        assert_eq!(changes[0].path, format!("/tmp/{}", name));
+        let volume = engine.list_volume_files("scratch", "/").await.unwrap();
+        assert!(volume.iter().any(|entry| entry.name == name));
+        assert!(
+            engine
+                .list_volume_files("scratch", "/missing")
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn creates_containers_and_updates_limits() {
+        let engine = FakeEngine::demo().unwrap();
+
+        let mut spec = engine
+            .container_spec("shop-web-1")
+            .await
+            .unwrap()
+            .duplicate();
+        spec.name = "web-copy".to_string();
+        spec.ports.clear();
+        let id = engine.create_container(&spec).await.unwrap();
+        assert!(engine.create_container(&spec).await.is_err());
+        engine.start_container(&id).await.unwrap();
+        assert_eq!(
+            engine
+                .top_processes(&id, "-ef")
+                .await
+                .unwrap()
+                .processes
+                .len(),
+            1
+        );
+
+        let current = engine.container_limits("shop-db-1").await.unwrap();
+        assert_eq!(current.memory, 512 * MB);
+        let limits = ContainerLimits {
+            pids_limit: 100,
+            memory: 0,
+            ..current.clone()
+        };
+        engine
+            .update_limits("shop-db-1", &current, &limits)
+            .await
+            .unwrap();
+        let updated = engine.container_limits("shop-db-1").await.unwrap();
+        assert_eq!(updated.pids_limit, 100);
+        assert_eq!(updated.memory, 512 * MB);
+
+        let health = engine.container_health("shop-db-1").await.unwrap();
+        assert_eq!(health.log.len(), 2);
+    }
+}
@@ -0,0 +1,9 @@
+use std::collections::HashMap;
+
+/// Whether `labels` satisfies a `key` or `key=value` filter.
+pub fn label_matches(filter: &str, labels: &HashMap<String, String>) -> bool {
+    match filter.split_once('=') {
+        Some((key, value)) => labels.get(key.trim()).is_some_and(|v| v == value.trim()),
+        None => labels.contains_key(filter.trim()),
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use bollard::container::InspectContainerOptions;
+use bollard::models::{ContainerInspectResponse, HealthStatusEnum, HealthcheckResult};
+use chrono::{DateTime, Local};
+
+use super::docker::{ContainerInfo, ContainerState, DockerService};
+
+/// The exit code a process reports when `docker stop` ends it with SIGTERM.
+const SIGTERM_EXIT: i64 = 143;
+
+/// The result of a container's health check, for containers that define one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HealthStatus {
+    Starting,
+    Healthy,
+    Unhealthy,
+}
+
+impl HealthStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HealthStatus::Starting => "Starting",
+            HealthStatus::Healthy => "Healthy",
+            HealthStatus::Unhealthy => "Unhealthy",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            HealthStatus::Starting => "working",
+            HealthStatus::Healthy => "running",
+            HealthStatus::Unhealthy => "stopped",
+        }
+    }
+
+    /// Reads the health the engine appends to a list entry's status, like
+    /// `Up 2 hours (unhealthy)` or `Up 3 seconds (health: starting)`.
+    pub(super) fn from_status(status: &str) -> Option<Self> {
+        if status.ends_with("(health: starting)") {
+            Some(HealthStatus::Starting)
+        } else if status.ends_with("(unhealthy)") {
+            Some(HealthStatus::Unhealthy)
+        } else if status.ends_with("(healthy)") {
+            Some(HealthStatus::Healthy)
+        } else {
+            None
+        }
+    }
+
+    fn from_engine(status: HealthStatusEnum) -> Option<Self> {
+        match status {
+            HealthStatusEnum::STARTING => Some(HealthStatus::Starting),
+            HealthStatusEnum::HEALTHY => Some(HealthStatus::Healthy),
+            HealthStatusEnum::UNHEALTHY => Some(HealthStatus::Unhealthy),
+            HealthStatusEnum::EMPTY | HealthStatusEnum::NONE => None,
+        }
+    }
+}
+
+/// The exit code in a list entry's status, like `Exited (137) 3 minutes ago`.
+pub(super) fn exit_code(status: &str) -> Option<i64> {
+    status
+        .strip_prefix("Exited (")?
+        .split_once(')')?
+        .0
+        .parse()
+        .ok()
+}
+
+/// One run of a container's health check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HealthCheck {
+    pub started: String,
+    pub exit_code: i64,
+    pub output: String,
+}
+
+impl HealthCheck {
+    pub fn passed(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// The health check a container runs and its most recent results, newest first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContainerHealth {
+    pub status: Option<HealthStatus>,
+    /// The check command, empty when the container has no health check.
+    pub test: Vec<String>,
+    pub failing_streak: i64,
+    pub log: Vec<HealthCheck>,
+}
+
+fn health_check(result: HealthcheckResult) -> HealthCheck {
+    let started = result.start.unwrap_or_default();
+    HealthCheck {
+        started: DateTime::parse_from_rfc3339(&started)
+            .map(|at| at.with_timezone(&Local).format("%H:%M:%S").to_string())
+            .unwrap_or(started),
+        exit_code: result.exit_code.unwrap_or(-1),
+        output: result.output.unwrap_or_default().trim_end().to_string(),
+    }
+}
+
+/// Something worth interrupting the user for, found by comparing two refreshes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HealthAlert {
+    Unhealthy { name: String },
+    Exited { name: String, code: i64 },
+}
+
+impl HealthAlert {
+    pub fn title(&self) -> String {
+        match self {
+            HealthAlert::Unhealthy { name } => format!("{} is unhealthy", name),
+            HealthAlert::Exited { name, .. } => format!("{} exited", name),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        match self {
+            HealthAlert::Unhealthy { .. } => "Its health check started failing.".to_string(),
+            HealthAlert::Exited { code, .. } => format!("It stopped with exit code {}.", code),
+        }
+    }
+}
+
+/// Containers that turned unhealthy, or stopped with a non-zero exit code,
+/// between two refreshes. Exits from SIGTERM are left out since that is how
+/// `docker stop` ends a container, and containers missing from `previous`
+/// never alert.
+pub fn health_alerts(previous: &[ContainerInfo], current: &[ContainerInfo]) -> Vec<HealthAlert> {
+    let previous: HashMap<&str, &ContainerInfo> = previous
+        .iter()
+        .map(|container| (container.id.as_str(), container))
+        .collect();
+
+    current
+        .iter()
+        .filter_map(|container| {
+            let before = previous.get(container.id.as_str())?;
+            if container.health == Some(HealthStatus::Unhealthy)
+                && before.health != Some(HealthStatus::Unhealthy)
+            {
+                return Some(HealthAlert::Unhealthy {
+                    name: container.name.clone(),
+                });
+            }
+            match container.exit_code {
+                Some(code)
+                    if code != 0
+                        && code != SIGTERM_EXIT
+                        && before.state == ContainerState::Running
+                        && container.state == ContainerState::Stopped =>
+                {
+                    Some(HealthAlert::Exited {
+                        name: container.name.clone(),
+                        code,
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+impl DockerService {
+    pub async fn container_health(&self, id: &str) -> Result<ContainerHealth> {
+        let details = self
+            .docker
+            .inspect_container(id, None::<InspectContainerOptions>)
+            .await?;
+        Ok(container_health(details))
+    }
+}
+
+pub(super) fn container_health(details: ContainerInspectResponse) -> ContainerHealth {
+    let test = details
+        .config
+        .and_then(|config| config.healthcheck)
+        .and_then(|healthcheck| healthcheck.test)
+        .unwrap_or_default();
+    let Some(health) = details.state.and_then(|state| state.health) else {
+        return ContainerHealth {
+            test,
+            ..Default::default()
+        };
+    };
+
+    let mut log: Vec<HealthCheck> = health
+        .log
+        .unwrap_or_default()
+        .into_iter()
+        .map(health_check)
+        .collect();
+    log.reverse();
+
+    ContainerHealth {
+        status: health.status.and_then(HealthStatus::from_engine),
+        test,
+        failing_streak: health.failing_streak.unwrap_or_default(),
+        log,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(id: &str, status: &str) -> ContainerInfo {
+        ContainerInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            image: "postgres:16".to_string(),
+            status: status.to_string(),
+            ports: Vec::new(),
+            state: if status.starts_with("Up") {
+                ContainerState::Running
+            } else {
+                ContainerState::Stopped
+            },
+            health: HealthStatus::from_status(status),
+            exit_code: exit_code(status),
+            compose_project: None,
+            compose_service: None,
+            labels: HashMap::new(),
+            volumes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn alerts_on_new_failures_only() {
+        assert_eq!(
+            HealthStatus::from_status("Up 3 seconds (health: starting)"),
+            Some(HealthStatus::Starting)
+        );
+        assert_eq!(HealthStatus::from_status("Up 2 hours"), None);
+        assert_eq!(exit_code("Exited (137) 3 minutes ago"), Some(137));
+        assert_eq!(exit_code("Up 2 hours"), None);
+
+        let previous = vec![
+            container("db", "Up 2 hours (healthy)"),
+            container("cache", "Up 2 hours (unhealthy)"),
+            container("worker", "Up 2 hours"),
+            container("web", "Up 2 hours"),
+            container("job", "Exited (1) 3 days ago"),
+        ];
+        let current = vec![
+            container("db", "Up 2 hours (unhealthy)"),
+            container("cache", "Up 2 hours (unhealthy)"),
+            container("worker", "Exited (137) Less than a second ago"),
+            container("web", "Exited (143) Less than a second ago"),
+            container("job", "Exited (1) 3 days ago"),
+            container("new", "Up 1 second (unhealthy)"),
+        ];
+
+        let alerts = health_alerts(&previous, &current);
+        assert_eq!(
+            alerts,
+            vec![
+                HealthAlert::Unhealthy {
+                    name: "db".to_string()
+                },
+                HealthAlert::Exited {
+                    name: "worker".to_string(),
+                    code: 137
+                },
+            ]
+        );
+        assert_eq!(alerts[1].body(), "It stopped with exit code 137.");
+    }
+}
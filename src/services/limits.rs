@@ -0,0 +1,297 @@
+use anyhow::{Result, anyhow};
+use bollard::container::UpdateContainerOptions;
+use bollard::models::{HostConfig, RestartPolicy, RestartPolicyNameEnum};
+
+use super::docker::DockerService;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RestartPolicyKind {
+    #[default]
+    No,
+    Always,
+    UnlessStopped,
+    OnFailure,
+}
+
+impl RestartPolicyKind {
+    pub const ALL: [RestartPolicyKind; 4] = [
+        RestartPolicyKind::No,
+        RestartPolicyKind::Always,
+        RestartPolicyKind::UnlessStopped,
+        RestartPolicyKind::OnFailure,
+    ];
+
+    /// The name the engine and `docker run --restart` use.
+    pub fn value(&self) -> &'static str {
+        match self {
+            RestartPolicyKind::No => "no",
+            RestartPolicyKind::Always => "always",
+            RestartPolicyKind::UnlessStopped => "unless-stopped",
+            RestartPolicyKind::OnFailure => "on-failure",
+        }
+    }
+
+    /// The `--restart` value, like `on-failure:3`; retries only count for
+    /// `on-failure`, and zero means no limit.
+    pub fn flag(&self, max_retries: i64) -> String {
+        match self {
+            RestartPolicyKind::OnFailure if max_retries > 0 => {
+                format!("{}:{}", self.value(), max_retries)
+            }
+            _ => self.value().to_string(),
+        }
+    }
+
+    pub fn from_value(value: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.value() == value)
+            .unwrap_or_default()
+    }
+}
+
+/// Resource limits and restart policy that `docker update` can change on a
+/// running container. Zero means the engine default (no limit).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContainerLimits {
+    pub cpu_shares: i64,
+    /// Microseconds of CPU time per `cpu_period`.
+    pub cpu_quota: i64,
+    pub cpu_period: i64,
+    /// Bytes.
+    pub memory: i64,
+    /// Memory plus swap in bytes; -1 allows unlimited swap.
+    pub memory_swap: i64,
+    pub pids_limit: i64,
+    pub restart_policy: RestartPolicyKind,
+    /// Only used by `on-failure`.
+    pub max_retries: i64,
+}
+
+/// Parses a size like `512m`, `1g` or `1048576` into bytes; empty means zero.
+/// `-1` (no limit) is only accepted when `allow_unlimited` is set, as it is for
+/// memory plus swap but not for memory itself.
+pub fn parse_bytes(input: &str, allow_unlimited: bool) -> Result<i64> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Ok(0);
+    }
+    if input == "-1" {
+        if allow_unlimited {
+            return Ok(-1);
+        }
+        anyhow::bail!("-1 is only allowed for memory plus swap");
+    }
+    let digits = input.trim_end_matches('b');
+    let (number, unit) = match digits.char_indices().last() {
+        Some((index, 'k')) => (&digits[..index], 1 << 10),
+        Some((index, 'm')) => (&digits[..index], 1 << 20),
+        Some((index, 'g')) => (&digits[..index], 1 << 30),
+        _ => (digits, 1),
+    };
+    let value: i64 = number
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("{} is not a size such as 512m or 1g", input))?;
+    if value < 0 {
+        anyhow::bail!("{} is negative", input);
+    }
+    value
+        .checked_mul(unit)
+        .ok_or_else(|| anyhow!("{} is too large", input))
+}
+
+/// Parses a whole number such as CPU shares or a PIDs limit; empty means zero.
+/// Negative numbers are refused, except `-1` (no limit) when `allow_unlimited` is
+/// set, as it is for the PIDs limit.
+pub fn parse_count(input: &str, allow_unlimited: bool) -> Result<i64> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(0);
+    }
+    let value: i64 = input
+        .parse()
+        .map_err(|_| anyhow!("{} is not a whole number", input))?;
+    match value {
+        -1 if allow_unlimited => Ok(-1),
+        -1 => anyhow::bail!("-1 is only allowed for the PIDs limit"),
+        value if value < 0 => anyhow::bail!("{} is negative", input),
+        value => Ok(value),
+    }
+}
+
+/// The inverse of `parse_bytes`, using the largest unit that divides evenly.
+pub fn format_bytes(bytes: i64) -> String {
+    match bytes {
+        0 => String::new(),
+        bytes if bytes > 0 && bytes % (1 << 30) == 0 => format!("{}g", bytes >> 30),
+        bytes if bytes > 0 && bytes % (1 << 20) == 0 => format!("{}m", bytes >> 20),
+        bytes if bytes > 0 && bytes % (1 << 10) == 0 => format!("{}k", bytes >> 10),
+        bytes => bytes.to_string(),
+    }
+}
+
+impl DockerService {
+    pub async fn container_limits(&self, id: &str) -> Result<ContainerLimits> {
+        let details = self.docker.inspect_container(id, None).await?;
+        Ok(container_limits(details.host_config.unwrap_or_default()))
+    }
+
+    /// Changes the container from `current` to `limits` in place. The engine
+    /// ignores zero CPU and memory values, so those keep their current setting.
+    pub async fn update_limits(
+        &self,
+        id: &str,
+        current: &ContainerLimits,
+        limits: &ContainerLimits,
+    ) -> Result<()> {
+        self.docker
+            .update_container(id, update_options(current, limits))
+            .await?;
+        Ok(())
+    }
+}
+
+pub(super) fn container_limits(host: HostConfig) -> ContainerLimits {
+    let restart = host.restart_policy.unwrap_or_default();
+    let restart_policy = match restart.name {
+        Some(RestartPolicyNameEnum::ALWAYS) => RestartPolicyKind::Always,
+        Some(RestartPolicyNameEnum::UNLESS_STOPPED) => RestartPolicyKind::UnlessStopped,
+        Some(RestartPolicyNameEnum::ON_FAILURE) => RestartPolicyKind::OnFailure,
+        _ => RestartPolicyKind::No,
+    };
+
+    ContainerLimits {
+        cpu_shares: host.cpu_shares.unwrap_or_default(),
+        cpu_quota: host.cpu_quota.unwrap_or_default(),
+        cpu_period: host.cpu_period.unwrap_or_default(),
+        memory: host.memory.unwrap_or_default(),
+        memory_swap: host.memory_swap.unwrap_or_default(),
+        pids_limit: host.pids_limit.unwrap_or_default(),
+        restart_policy,
+        max_retries: restart.maximum_retry_count.unwrap_or_default(),
+    }
+}
+
+/// The update that turns `current` into `limits`. Only the fields that differ
+/// are sent, so a limit the user left alone is never reset; a PIDs limit cleared
+/// to zero is removed.
+pub(super) fn update_options(
+    current: &ContainerLimits,
+    limits: &ContainerLimits,
+) -> UpdateContainerOptions<String> {
+    let changed = |current: i64, new: i64| (current != new).then_some(new);
+    let restart_changed = current.restart_policy != limits.restart_policy
+        || (limits.restart_policy == RestartPolicyKind::OnFailure
+            && current.max_retries != limits.max_retries);
+    let name = match limits.restart_policy {
+        RestartPolicyKind::No => RestartPolicyNameEnum::NO,
+        RestartPolicyKind::Always => RestartPolicyNameEnum::ALWAYS,
+        RestartPolicyKind::UnlessStopped => RestartPolicyNameEnum::UNLESS_STOPPED,
+        RestartPolicyKind::OnFailure => RestartPolicyNameEnum::ON_FAILURE,
+    };
+    UpdateContainerOptions {
+        cpu_shares: changed(current.cpu_shares, limits.cpu_shares).map(|shares| shares as isize),
+        cpu_quota: changed(current.cpu_quota, limits.cpu_quota),
+        cpu_period: changed(current.cpu_period, limits.cpu_period),
+        memory: changed(current.memory, limits.memory),
+        memory_swap: changed(current.memory_swap, limits.memory_swap),
+        pids_limit: changed(current.pids_limit, limits.pids_limit),
+        restart_policy: restart_changed.then(|| RestartPolicy {
+            name: Some(name),
+            maximum_retry_count: (limits.restart_policy == RestartPolicyKind::OnFailure)
+                .then_some(limits.max_retries),
+        }),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_round_trip_through_units() {
+        assert_eq!(parse_bytes("", false).unwrap(), 0);
+        assert_eq!(parse_bytes("512m", false).unwrap(), 512 << 20);
+        assert_eq!(parse_bytes(" 1G ", false).unwrap(), 1 << 30);
+        assert_eq!(parse_bytes("64kb", false).unwrap(), 64 << 10);
+        assert_eq!(parse_bytes("-1", true).unwrap(), -1);
+        assert!(parse_bytes("-1", false).is_err());
+        assert!(parse_bytes("-2", true).is_err());
+        assert!(parse_bytes("-5m", true).is_err());
+        assert!(parse_bytes("9000000000g", false).is_err());
+        assert!(parse_bytes("lots", false).is_err());
+
+        assert_eq!(format_bytes(512 << 20), "512m");
+        assert_eq!(format_bytes(3 << 30), "3g");
+        assert_eq!(format_bytes(1000), "1000");
+        assert_eq!(format_bytes(0), "");
+    }
+
+    #[test]
+    fn restart_policy_maps_both_ways() {
+        let limits = container_limits(HostConfig {
+            memory: Some(512 << 20),
+            restart_policy: Some(RestartPolicy {
+                name: Some(RestartPolicyNameEnum::ON_FAILURE),
+                maximum_retry_count: Some(3),
+            }),
+            ..Default::default()
+        });
+        assert_eq!(limits.memory, 512 << 20);
+        assert_eq!(limits.restart_policy, RestartPolicyKind::OnFailure);
+        assert_eq!(limits.max_retries, 3);
+
+        let restart = update_options(&ContainerLimits::default(), &limits)
+            .restart_policy
+            .unwrap();
+        assert_eq!(restart.name, Some(RestartPolicyNameEnum::ON_FAILURE));
+        assert_eq!(restart.maximum_retry_count, Some(3));
+
+        let always = ContainerLimits {
+            restart_policy: RestartPolicyKind::Always,
+            ..limits.clone()
+        };
+        let restart = update_options(&limits, &always).restart_policy.unwrap();
+        assert_eq!(restart.name, Some(RestartPolicyNameEnum::ALWAYS));
+        assert_eq!(restart.maximum_retry_count, None);
+
+        let unset = container_limits(HostConfig::default());
+        assert_eq!(unset.restart_policy, RestartPolicyKind::No);
+        assert_eq!(unset.max_retries, 0);
+    }
+
+    #[test]
+    fn updates_only_send_changed_fields() {
+        let current = ContainerLimits {
+            memory: 512 << 20,
+            pids_limit: 200,
+            ..Default::default()
+        };
+        let update = update_options(&current, &current);
+        assert_eq!(update.memory, None);
+        assert_eq!(update.pids_limit, None);
+        assert!(update.restart_policy.is_none());
+
+        let cleared = ContainerLimits {
+            cpu_shares: 512,
+            pids_limit: 0,
+            ..current.clone()
+        };
+        let update = update_options(&current, &cleared);
+        assert_eq!(update.cpu_shares, Some(512));
+        assert_eq!(update.pids_limit, Some(0));
+        assert_eq!(update.memory, None);
+    }
+
+    #[test]
+    fn counts_refuse_negative_numbers() {
+        assert_eq!(parse_count("", false).unwrap(), 0);
+        assert_eq!(parse_count(" 1024 ", false).unwrap(), 1024);
+        assert_eq!(parse_count("-1", true).unwrap(), -1);
+        assert!(parse_count("-1", false).is_err());
+        assert!(parse_count("-5", true).is_err());
+        assert!(parse_count("1.5", false).is_err());
+    }
+}
@@ -1,3 +1,44 @@
+mod archive;
+mod backend;
+mod changes;
+mod compose;
+mod compose_file;
+mod create;
+mod disk_usage;
 mod docker;
+mod engine;
+mod error;
+mod fake;
+mod filters;
+mod health;
+mod limits;
+mod networks;
+mod ports;
+mod processes;
+mod snapshot;
+mod snippets;
+#[cfg(test)]
+mod stub_engine;
+mod topology;
 
-pub use docker::{ContainerInfo, ContainerState, DockerService, ImageInfo, VolumeInfo};
+pub use archive::{FileEntry, FileKind, parent_path};
+pub use backend::{DockerBackend, connect};
+pub use changes::{ChangeKind, PathChange, change_rows};
+pub use compose::group_by_project;
+pub use compose_file::ComposeProject;
+pub use create::{ContainerSpec, ContainerSpecText};
+pub use disk_usage::{DiskUsage, DiskUsageKind};
+pub use docker::{ContainerInfo, ContainerState, ImageInfo, VolumeInfo, mark_in_use};
+pub use engine::EngineInfo;
+pub use error::DockerError;
+#[cfg(test)]
+pub use fake::FakeEngine;
+pub use filters::label_matches;
+pub use health::{ContainerHealth, HealthAlert, HealthStatus, health_alerts};
+pub use limits::{ContainerLimits, RestartPolicyKind, format_bytes, parse_bytes, parse_count};
+pub use networks::{NETWORK_DRIVERS, NetworkDetails, NetworkInfo, NetworkSpec, parse_labels};
+pub use ports::{PortConflict, PublishedPort, port_conflicts, taken_ports};
+pub use processes::{DEFAULT_PS_ARGS, ProcessList};
+pub use snapshot::{CommitSpec, parse_changes};
+pub use snippets::{compose_service, docker_run_command};
+pub use topology::{NetworkTopology, NodeKind, layout};
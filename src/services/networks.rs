@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use bollard::models::{EndpointSettings, Ipam, IpamConfig, Network};
+use bollard::network::{
+    ConnectNetworkOptions, CreateNetworkOptions, DisconnectNetworkOptions, InspectNetworkOptions,
+    ListNetworksOptions,
+};
+
+use super::docker::DockerService;
+
+/// Drivers offered when creating a network from the UI.
+pub const NETWORK_DRIVERS: [&str; 4] = ["bridge", "overlay", "macvlan", "ipvlan"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetworkInfo {
+    pub id: String,
+    pub name: String,
+    pub driver: String,
+    pub scope: String,
+    pub subnet: String,
+    pub gateway: String,
+    pub internal: bool,
+}
+
+impl NetworkInfo {
+    /// The networks every engine creates on its own; they cannot be removed.
+    pub fn is_builtin(&self) -> bool {
+        matches!(self.name.as_str(), "bridge" | "host" | "none")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetworkEndpoint {
+    pub container_id: String,
+    pub container_name: String,
+    pub ipv4_address: String,
+    pub mac_address: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetworkDetails {
+    pub info: NetworkInfo,
+    pub labels: Vec<(String, String)>,
+    pub endpoints: Vec<NetworkEndpoint>,
+}
+
+/// User input for creating a network. Empty optional fields are left to the engine.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NetworkSpec {
+    pub name: String,
+    pub driver: String,
+    pub subnet: Option<String>,
+    pub gateway: Option<String>,
+    pub labels: Vec<(String, String)>,
+}
+
+impl DockerService {
+    pub async fn list_networks(&self) -> Result<Vec<NetworkInfo>> {
+        let networks = self
+            .docker
+            .list_networks(None::<ListNetworksOptions<String>>)
+            .await?;
+
+        let mut network_infos: Vec<NetworkInfo> = networks.iter().map(network_info).collect();
+        network_infos.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(network_infos)
+    }
+
+    pub async fn inspect_network(&self, id: &str) -> Result<NetworkDetails> {
+        let network = self
+            .docker
+            .inspect_network(id, None::<InspectNetworkOptions<String>>)
+            .await?;
+        Ok(network_details(network))
+    }
+
+    pub async fn create_network(&self, spec: &NetworkSpec) -> Result<String> {
+        let ipam = if spec.subnet.is_some() || spec.gateway.is_some() {
+            Ipam {
+                config: Some(vec![IpamConfig {
+                    subnet: spec.subnet.clone(),
+                    gateway: spec.gateway.clone(),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }
+        } else {
+            Ipam::default()
+        };
+
+        let options = CreateNetworkOptions {
+            name: spec.name.clone(),
+            driver: spec.driver.clone(),
+            check_duplicate: true,
+            ipam,
+            labels: spec.labels.iter().cloned().collect::<HashMap<_, _>>(),
+            ..Default::default()
+        };
+
+        let created = self.docker.create_network(options).await?;
+        Ok(created.id)
+    }
+
+    pub async fn remove_network(&self, id: &str) -> Result<()> {
+        self.docker.remove_network(id).await?;
+        Ok(())
+    }
+
+    pub async fn connect_network(
+        &self,
+        network: &str,
+        container: &str,
+        aliases: Vec<String>,
+    ) -> Result<()> {
+        let options = ConnectNetworkOptions {
+            container: container.to_string(),
+            endpoint_config: EndpointSettings {
+                aliases: (!aliases.is_empty()).then_some(aliases),
+                ..Default::default()
+            },
+        };
+        self.docker.connect_network(network, options).await?;
+        Ok(())
+    }
+
+    pub async fn disconnect_network(&self, network: &str, container: &str) -> Result<()> {
+        let options = DisconnectNetworkOptions {
+            container: container.to_string(),
+            force: false,
+        };
+        self.docker.disconnect_network(network, options).await?;
+        Ok(())
+    }
+}
+
+/// Maps inspect output, whose `containers` holds one entry per attached endpoint.
+pub(super) fn network_details(network: Network) -> NetworkDetails {
+    let mut labels: Vec<(String, String)> = network
+        .labels
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    labels.sort();
+
+    let mut endpoints: Vec<NetworkEndpoint> = network
+        .containers
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(container_id, container)| NetworkEndpoint {
+            container_name: container
+                .name
+                .unwrap_or_else(|| container_id.chars().take(12).collect()),
+            container_id,
+            ipv4_address: container
+                .ipv4_address
+                .filter(|address| !address.is_empty())
+                .unwrap_or_else(|| "--".to_string()),
+            mac_address: container
+                .mac_address
+                .filter(|address| !address.is_empty())
+                .unwrap_or_else(|| "--".to_string()),
+        })
+        .collect();
+    endpoints.sort_by(|a, b| a.container_name.cmp(&b.container_name));
+
+    NetworkDetails {
+        info: network_info(&network),
+        labels,
+        endpoints,
+    }
+}
+
+pub(super) fn network_info(network: &Network) -> NetworkInfo {
+    let ipam_config = network
+        .ipam
+        .as_ref()
+        .and_then(|ipam| ipam.config.as_ref())
+        .and_then(|configs| configs.first());
+
+    NetworkInfo {
+        id: network
+            .id
+            .as_ref()
+            .map(|s| s.chars().take(12).collect())
+            .unwrap_or_else(|| "unknown".to_string()),
+        name: network
+            .name
+            .clone()
+            .unwrap_or_else(|| "unnamed".to_string()),
+        driver: network.driver.clone().unwrap_or_else(|| "--".to_string()),
+        scope: network.scope.clone().unwrap_or_else(|| "--".to_string()),
+        subnet: ipam_config
+            .and_then(|config| config.subnet.clone())
+            .unwrap_or_else(|| "--".to_string()),
+        gateway: ipam_config
+            .and_then(|config| config.gateway.clone())
+            .unwrap_or_else(|| "--".to_string()),
+        internal: network.internal.unwrap_or(false),
+    }
+}
+
+/// Parses `key=value` pairs separated by commas or newlines, skipping blanks.
+pub fn parse_labels(input: &str) -> Vec<(String, String)> {
+    input
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (key.trim().to_string(), value.trim().to_string()),
+            None => (pair.to_string(), String::new()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_labels_accepts_commas_and_newlines() {
+        let labels = parse_labels("team=platform, env = dev\nflag\n\n");
+        assert_eq!(
+            labels,
+            vec![
+                ("team".to_string(), "platform".to_string()),
+                ("env".to_string(), "dev".to_string()),
+                ("flag".to_string(), String::new()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,428 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use bollard::container::InspectContainerOptions;
+use bollard::models::{Port, PortMap, PortTypeEnum};
+use futures_util::stream::{self, StreamExt};
+
+use super::compose_file::ComposePort;
+use super::docker::{ContainerInfo, ContainerState, DockerService};
+use super::error::DockerError;
+use super::topology::INSPECT_CONCURRENCY;
+
+/// One port a container exposes, published on the host or not.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PublishedPort {
+    pub private_port: u16,
+    pub public_port: Option<u16>,
+    /// `None` when the port is bound on every interface.
+    pub host_ip: Option<String>,
+    pub protocol: String,
+}
+
+impl PublishedPort {
+    /// The address other programs reach the port on, like `127.0.0.1:8080`.
+    pub fn host_address(&self) -> Option<String> {
+        let port = self.public_port?;
+        Some(match self.host_ip.as_deref() {
+            None => port.to_string(),
+            Some(ip) if ip.contains(':') => format!("[{}]:{}", ip, port),
+            Some(ip) => format!("{}:{}", ip, port),
+        })
+    }
+
+    /// A link that opens the port in a browser, for published TCP ports only.
+    pub fn url(&self) -> Option<String> {
+        if self.protocol != "tcp" {
+            return None;
+        }
+        let port = self.public_port?;
+        let host = match self.host_ip.as_deref() {
+            None => "localhost".to_string(),
+            Some(ip) if ip.contains(':') => format!("[{}]", ip),
+            Some(ip) => ip.to_string(),
+        };
+        let scheme = if self.private_port == 443 {
+            "https"
+        } else {
+            "http"
+        };
+        Some(format!("{}://{}:{}", scheme, host, port))
+    }
+
+    /// Whether this binding and `other` would claim the same host socket.
+    pub(super) fn overlaps(&self, other: &PublishedPort) -> bool {
+        self.public_port.is_some()
+            && self.public_port == other.public_port
+            && self.protocol == other.protocol
+            && (self.host_ip.is_none() || other.host_ip.is_none() || self.host_ip == other.host_ip)
+    }
+}
+
+impl std::fmt::Display for PublishedPort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(address) = self.host_address() {
+            write!(f, "{} → ", address)?;
+        }
+        write!(f, "{}/{}", self.private_port, self.protocol)
+    }
+}
+
+/// Maps the engine's port list, folding the separate IPv4 and IPv6 entries it
+/// reports for a binding on every interface into one.
+pub(super) fn published_ports(ports: Vec<Port>) -> Vec<PublishedPort> {
+    let mut published: Vec<PublishedPort> = ports
+        .into_iter()
+        .map(|port| PublishedPort {
+            private_port: port.private_port,
+            public_port: port.public_port,
+            host_ip: port.ip.filter(|ip| {
+                port.public_port.is_some() && !matches!(ip.as_str(), "" | "0.0.0.0" | "::")
+            }),
+            protocol: match port.typ {
+                Some(PortTypeEnum::UDP) => "udp",
+                Some(PortTypeEnum::SCTP) => "sctp",
+                _ => "tcp",
+            }
+            .to_string(),
+        })
+        .collect();
+    published.sort();
+    published.dedup();
+    published
+}
+
+/// Maps the host bindings a container was created with. Unlike the list entry's
+/// ports, these are known while the container is stopped.
+pub(super) fn bound_ports(bindings: PortMap) -> Vec<PublishedPort> {
+    let mut ports = Vec::new();
+    for (key, bindings) in bindings {
+        let (port, protocol) = key.split_once('/').unwrap_or((key.as_str(), "tcp"));
+        let Ok(private_port) = port.parse() else {
+            continue;
+        };
+        let typ = Some(match protocol {
+            "udp" => PortTypeEnum::UDP,
+            "sctp" => PortTypeEnum::SCTP,
+            _ => PortTypeEnum::TCP,
+        });
+        for binding in bindings.unwrap_or_default() {
+            ports.push(Port {
+                ip: binding.host_ip,
+                private_port,
+                public_port: binding.host_port.and_then(|port| port.parse().ok()),
+                typ,
+            });
+        }
+    }
+    published_ports(ports)
+}
+
+impl DockerService {
+    /// The host ports each of `ids` was created with, by id. Containers removed
+    /// since they were listed are left out.
+    pub async fn port_bindings(
+        &self,
+        ids: &[String],
+    ) -> Result<HashMap<String, Vec<PublishedPort>>> {
+        let inspected: Vec<_> = stream::iter(ids.iter().cloned())
+            .map(|id| async move {
+                let details = self
+                    .docker
+                    .inspect_container(&id, None::<InspectContainerOptions>)
+                    .await;
+                (id, details)
+            })
+            .buffer_unordered(INSPECT_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut bindings = HashMap::new();
+        for (id, details) in inspected {
+            match details {
+                Ok(details) => {
+                    let ports = details
+                        .host_config
+                        .and_then(|host| host.port_bindings)
+                        .unwrap_or_default();
+                    bindings.insert(id, bound_ports(ports));
+                }
+                Err(e) => {
+                    let e = anyhow::Error::from(e);
+                    if !matches!(DockerError::from(&e), DockerError::NotFound(_)) {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(bindings)
+    }
+}
+
+/// The host ports `container` binds: what it was created with once that has
+/// been read, and until then the list entry's ports, which only cover a running
+/// container.
+fn container_ports<'a>(
+    container: &'a ContainerInfo,
+    bindings: &'a HashMap<String, Vec<PublishedPort>>,
+) -> &'a [PublishedPort] {
+    bindings
+        .get(&container.id)
+        .map_or(&container.ports, |ports| ports)
+}
+
+/// A host port that more than one container binds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PortConflict {
+    pub host_port: u16,
+    pub protocol: String,
+    /// Names of the containers that bind it, sorted.
+    pub containers: Vec<String>,
+    /// The ones among `containers` that are running.
+    pub running: Vec<String>,
+}
+
+impl PortConflict {
+    pub fn message(&self) -> String {
+        format!(
+            "Host port {}/{} is bound by {}",
+            self.host_port,
+            self.protocol,
+            self.containers.join(", ")
+        )
+    }
+
+    /// What the conflict means given which of the containers are running now.
+    pub fn consequence(&self) -> String {
+        match self.running.as_slice() {
+            [] => "None of them is running; only one can be started at a time".to_string(),
+            [running] => format!("{} is running, so starting the others fails", running),
+            running => format!("{} are running at the same time", running.join(", ")),
+        }
+    }
+}
+
+/// Host ports that two or more containers, running or stopped, bind on
+/// overlapping addresses. `bindings` holds what each container was created with;
+/// see `container_ports`.
+pub fn port_conflicts(
+    containers: &[ContainerInfo],
+    bindings: &HashMap<String, Vec<PublishedPort>>,
+) -> Vec<PortConflict> {
+    let mut claims: BTreeMap<(u16, &str), Vec<(&str, &PublishedPort)>> = BTreeMap::new();
+    for container in containers {
+        for port in container_ports(container, bindings) {
+            if let Some(public_port) = port.public_port {
+                claims
+                    .entry((public_port, port.protocol.as_str()))
+                    .or_default()
+                    .push((container.name.as_str(), port));
+            }
+        }
+    }
+
+    claims
+        .into_iter()
+        .filter_map(|((host_port, protocol), bindings)| {
+            let mut names: Vec<String> = bindings
+                .iter()
+                .filter(|(name, port)| {
+                    bindings
+                        .iter()
+                        .any(|(other, theirs)| other != name && port.overlaps(theirs))
+                })
+                .map(|(name, _)| name.to_string())
+                .collect();
+            names.sort();
+            names.dedup();
+            let running = containers
+                .iter()
+                .filter(|container| container.state == ContainerState::Running)
+                .filter(|container| names.contains(&container.name))
+                .map(|container| container.name.clone())
+                .collect();
+            (names.len() > 1).then(|| PortConflict {
+                host_port,
+                protocol: protocol.to_string(),
+                containers: names,
+                running,
+            })
+        })
+        .collect()
+}
+
+/// Existing containers, running or stopped, that already bind a host port the new
+/// container asks for, as `(port, container name)` pairs. Port ranges are not checked.
+pub fn taken_ports(
+    ports: &[ComposePort],
+    containers: &[ContainerInfo],
+    bindings: &HashMap<String, Vec<PublishedPort>>,
+) -> Vec<(String, String)> {
+    let mut taken = Vec::new();
+    for wanted in ports {
+        let Some(public_port) = wanted
+            .published
+            .as_deref()
+            .and_then(|published| published.parse().ok())
+        else {
+            continue;
+        };
+        let wanted_binding = PublishedPort {
+            private_port: 0,
+            public_port: Some(public_port),
+            host_ip: wanted.host_ip.clone(),
+            protocol: wanted.protocol.clone(),
+        };
+        for container in containers {
+            if container_ports(container, bindings)
+                .iter()
+                .any(|port| port.overlaps(&wanted_binding))
+            {
+                taken.push((wanted.to_string(), container.name.clone()));
+            }
+        }
+    }
+    taken
+}
+
+#[cfg(test)]
+mod tests {
+    use bollard::models::PortBinding;
+
+    use super::*;
+
+    fn binding(ip: &str, public: u16, private: u16) -> Port {
+        Port {
+            ip: Some(ip.to_string()),
+            private_port: private,
+            public_port: Some(public),
+            typ: Some(PortTypeEnum::TCP),
+        }
+    }
+
+    #[test]
+    fn folds_dual_stack_bindings_and_finds_conflicts() {
+        let web = published_ports(vec![
+            binding("0.0.0.0", 8080, 80),
+            binding("::", 8080, 80),
+            Port {
+                ip: None,
+                private_port: 53,
+                public_port: None,
+                typ: Some(PortTypeEnum::UDP),
+            },
+        ]);
+        assert_eq!(web.len(), 2);
+        assert_eq!(web[0].to_string(), "53/udp");
+        assert_eq!(web[0].url(), None);
+        assert_eq!(web[1].to_string(), "8080 → 80/tcp");
+        assert_eq!(web[1].url().as_deref(), Some("http://localhost:8080"));
+
+        let local = published_ports(vec![binding("127.0.0.1", 8443, 443)]);
+        assert_eq!(local[0].url().as_deref(), Some("https://127.0.0.1:8443"));
+
+        let container = |name: &str, ports: Vec<PublishedPort>| ContainerInfo {
+            name: name.to_string(),
+            id: name.to_string(),
+            image: "nginx:1.27".to_string(),
+            status: "Created".to_string(),
+            ports,
+            state: ContainerState::Stopped,
+            health: None,
+            exit_code: None,
+            compose_project: None,
+            compose_service: None,
+            labels: Default::default(),
+            volumes: Vec::new(),
+        };
+        let containers = vec![
+            container("web", web),
+            container(
+                "proxy",
+                published_ports(vec![binding("127.0.0.1", 8080, 80)]),
+            ),
+            container(
+                "admin",
+                published_ports(vec![binding("127.0.0.1", 8443, 443)]),
+            ),
+            container("tls", local),
+        ];
+        let conflicts = port_conflicts(&containers, &HashMap::new());
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(
+            conflicts[0].message(),
+            "Host port 8080/tcp is bound by proxy, web"
+        );
+        assert_eq!(conflicts[1].containers, vec!["admin", "tls"]);
+
+        let wanted = vec![ComposePort {
+            host_ip: Some("192.168.1.5".to_string()),
+            published: Some("8080".to_string()),
+            target: "3000".to_string(),
+            protocol: "tcp".to_string(),
+        }];
+        assert_eq!(
+            taken_ports(&wanted, &containers, &HashMap::new()),
+            vec![("192.168.1.5:8080:3000/tcp".to_string(), "web".to_string())]
+        );
+    }
+
+    #[test]
+    fn stopped_containers_keep_their_bindings() {
+        let container =
+            |name: &str, state: ContainerState, ports: Vec<PublishedPort>| ContainerInfo {
+                name: name.to_string(),
+                id: name.to_string(),
+                image: "nginx:1.27".to_string(),
+                status: String::new(),
+                ports,
+                state,
+                health: None,
+                exit_code: None,
+                compose_project: None,
+                compose_service: None,
+                labels: Default::default(),
+                volumes: Vec::new(),
+            };
+        let bound = |host_port: &str| {
+            bound_ports(HashMap::from([(
+                "80/tcp".to_string(),
+                Some(vec![PortBinding {
+                    host_ip: Some(String::new()),
+                    host_port: Some(host_port.to_string()),
+                }]),
+            )]))
+        };
+        // The list only reports the ports of the running container
+        let containers = vec![
+            container("web", ContainerState::Running, bound("8080")),
+            container("docs", ContainerState::Stopped, Vec::new()),
+        ];
+        assert!(port_conflicts(&containers, &HashMap::new()).is_empty());
+
+        let bindings = HashMap::from([
+            ("web".to_string(), bound("8080")),
+            ("docs".to_string(), bound("8080")),
+        ]);
+        let conflicts = port_conflicts(&containers, &bindings);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].containers, vec!["docs", "web"]);
+        assert_eq!(conflicts[0].running, vec!["web"]);
+        assert_eq!(
+            conflicts[0].consequence(),
+            "web is running, so starting the others fails"
+        );
+
+        let wanted = vec![ComposePort {
+            host_ip: None,
+            published: Some("8080".to_string()),
+            target: "80".to_string(),
+            protocol: "tcp".to_string(),
+        }];
+        let owners: Vec<String> = taken_ports(&wanted, &containers, &bindings)
+            .into_iter()
+            .map(|(_, owner)| owner)
+            .collect();
+        assert_eq!(owners, ["web", "docs"]);
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::Result;
+use bollard::container::TopOptions;
+
+use super::docker::DockerService;
+
+/// What `docker top` passes to `ps` when no arguments are given.
+pub const DEFAULT_PS_ARGS: &str = "-ef";
+
+/// The output of `ps` inside a container, split into columns.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProcessList {
+    pub titles: Vec<String>,
+    pub processes: Vec<Vec<String>>,
+}
+
+impl ProcessList {
+    fn column(&self, names: &[&str]) -> Option<usize> {
+        self.titles
+            .iter()
+            .position(|title| names.contains(&title.as_str()))
+    }
+
+    /// Zombies show a `Z` state when `ps` reports one, and `<defunct>` in the
+    /// command otherwise.
+    pub fn is_zombie(&self, process: &[String]) -> bool {
+        let state = self
+            .column(&["STAT", "S"])
+            .and_then(|index| process.get(index))
+            .is_some_and(|state| state.starts_with('Z'));
+        let command = self
+            .column(&["CMD", "COMMAND"])
+            .and_then(|index| process.get(index))
+            .is_some_and(|command| command.ends_with("<defunct>"));
+        state || command
+    }
+
+    pub fn zombie_count(&self) -> usize {
+        self.processes
+            .iter()
+            .filter(|process| self.is_zombie(process))
+            .count()
+    }
+}
+
+impl DockerService {
+    pub async fn top_processes(&self, id: &str, ps_args: &str) -> Result<ProcessList> {
+        let options = Some(TopOptions { ps_args });
+        let top = self.docker.top_processes(id, options).await?;
+        Ok(ProcessList {
+            titles: top.titles.unwrap_or_default(),
+            processes: top.processes.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn zombies_are_found_by_state_or_command() {
+        let aux = ProcessList {
+            titles: row(&["USER", "PID", "STAT", "COMMAND"]),
+            processes: vec![
+                row(&["root", "1", "Ss", "nginx: master"]),
+                row(&["root", "7", "Z", "[sh]"]),
+            ],
+        };
+        assert_eq!(aux.zombie_count(), 1);
+        assert!(aux.is_zombie(&aux.processes[1]));
+
+        let ef = ProcessList {
+            titles: row(&["UID", "PID", "PPID", "CMD"]),
+            processes: vec![row(&["root", "9", "1", "[worker] <defunct>"])],
+        };
+        assert_eq!(ef.zombie_count(), 1);
+    }
+}
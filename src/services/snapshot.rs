@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use bollard::container::Config;
+use bollard::image::CommitContainerOptions;
+use futures_util::StreamExt;
+use tokio::io::AsyncWriteExt;
+
+use super::docker::DockerService;
+
+/// Dockerfile instructions the engine accepts as commit changes.
+const CHANGE_INSTRUCTIONS: [&str; 9] = [
+    "CMD",
+    "ENTRYPOINT",
+    "ENV",
+    "EXPOSE",
+    "LABEL",
+    "ONBUILD",
+    "USER",
+    "VOLUME",
+    "WORKDIR",
+];
+
+/// User input for committing a container as a new image.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommitSpec {
+    pub repo: String,
+    pub tag: String,
+    pub comment: String,
+    pub author: String,
+    /// Pauses the container while the commit runs so the snapshot is consistent.
+    pub pause: bool,
+    pub changes: Vec<String>,
+}
+
+/// Splits one Dockerfile instruction per line, rejecting any the engine would
+/// refuse to apply while committing.
+pub fn parse_changes(input: &str) -> Result<Vec<String>> {
+    let changes: Vec<String> = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    for change in &changes {
+        let instruction = change.split_whitespace().next().unwrap_or_default();
+        if !CHANGE_INSTRUCTIONS.contains(&instruction.to_uppercase().as_str()) {
+            bail!(
+                "{} cannot be applied when committing; use one of {}",
+                instruction,
+                CHANGE_INSTRUCTIONS.join(", ")
+            );
+        }
+    }
+    Ok(changes)
+}
+
+impl DockerService {
+    /// Returns the id of the new image.
+    pub async fn commit_container(&self, id: &str, spec: &CommitSpec) -> Result<String> {
+        let changes = spec.changes.join("\n");
+        let options = CommitContainerOptions {
+            container: id,
+            repo: spec.repo.as_str(),
+            tag: spec.tag.as_str(),
+            comment: spec.comment.as_str(),
+            author: spec.author.as_str(),
+            pause: spec.pause,
+            changes: (!changes.is_empty()).then_some(changes.as_str()),
+        };
+        let commit = self
+            .docker
+            .commit_container(options, Config::<String>::default())
+            .await?;
+        Ok(commit.id.unwrap_or_default())
+    }
+
+    /// Streams the container's filesystem as a tar archive into `destination`
+    /// and returns how many bytes were written.
+    pub async fn export_container(&self, id: &str, destination: &Path) -> Result<u64> {
+        let mut file = tokio::fs::File::create(destination).await?;
+        let mut stream = self.docker.export_container(id);
+        let mut written = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await?;
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_changes_keeps_supported_instructions() {
+        let changes = parse_changes("ENV DEBUG=1\n\n  cmd [\"sh\"]  \n").unwrap();
+        assert_eq!(changes, vec!["ENV DEBUG=1", "cmd [\"sh\"]"]);
+
+        let error = parse_changes("RUN rm -rf /").unwrap_err();
+        assert!(error.to_string().starts_with("RUN cannot be applied"));
+    }
+}
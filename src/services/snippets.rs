@@ -0,0 +1,241 @@
+use anyhow::Result;
+use serde_yaml::{Mapping, Value};
+
+use super::compose_file::ComposePort;
+use super::create::ContainerSpec;
+use super::limits::RestartPolicyKind;
+
+/// Wraps `arg` in single quotes unless a POSIX shell would read it unchanged.
+fn shell_quote(arg: &str) -> String {
+    let is_plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c));
+    if is_plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// `[host_ip:][published:]target`, with the protocol only when it is not TCP.
+fn short_port(port: &ComposePort) -> String {
+    let mut short = String::new();
+    if let Some(host_ip) = &port.host_ip {
+        short.push_str(host_ip);
+        short.push(':');
+    }
+    if let Some(published) = &port.published {
+        short.push_str(published);
+        short.push(':');
+    }
+    short.push_str(&port.target);
+    if port.protocol != "tcp" {
+        short.push('/');
+        short.push_str(&port.protocol);
+    }
+    short
+}
+
+/// The `docker run` invocation that recreates `spec`, one option per line.
+pub fn docker_run_command(spec: &ContainerSpec) -> String {
+    let mut options: Vec<String> = vec!["docker run -d".to_string()];
+    let mut option = |flag: &str, value: &str| {
+        options.push(format!("{} {}", flag, shell_quote(value)));
+    };
+
+    if !spec.name.is_empty() {
+        option("--name", &spec.name);
+    }
+    if spec.restart_policy != RestartPolicyKind::No {
+        option("--restart", &spec.restart_policy.flag(spec.max_retries));
+    }
+    if !spec.network.is_empty() {
+        option("--network", &spec.network);
+    }
+    for port in &spec.ports {
+        option("-p", &short_port(port));
+    }
+    for bind in &spec.binds {
+        option("-v", bind);
+    }
+    for pair in &spec.env {
+        option("-e", pair);
+    }
+    for (key, value) in &spec.labels {
+        option("--label", &format!("{}={}", key, value));
+    }
+    if !spec.working_dir.is_empty() {
+        option("-w", &spec.working_dir);
+    }
+    if !spec.user.is_empty() {
+        option("-u", &spec.user);
+    }
+
+    // `--entrypoint` takes a single executable; its remaining arguments lead the command
+    let mut args = Vec::new();
+    if let Some((executable, rest)) = spec.entrypoint.split_first() {
+        option("--entrypoint", executable);
+        args.extend(rest.iter().cloned());
+    }
+    args.extend(spec.command.iter().cloned());
+
+    let mut image = shell_quote(&spec.image);
+    for arg in &args {
+        image.push(' ');
+        image.push_str(&shell_quote(arg));
+    }
+    options.push(image);
+    options.join(" \\\n  ")
+}
+
+/// A Compose file with a single service equivalent to `spec`.
+pub fn compose_service(spec: &ContainerSpec) -> Result<String> {
+    let strings = |values: &[String]| -> Value {
+        Value::Sequence(values.iter().cloned().map(Value::String).collect())
+    };
+
+    let mut service = Mapping::new();
+    service.insert("image".into(), spec.image.clone().into());
+    if !spec.name.is_empty() {
+        service.insert("container_name".into(), spec.name.clone().into());
+    }
+    if !spec.entrypoint.is_empty() {
+        service.insert("entrypoint".into(), strings(&spec.entrypoint));
+    }
+    if !spec.command.is_empty() {
+        service.insert("command".into(), strings(&spec.command));
+    }
+    if !spec.env.is_empty() {
+        service.insert("environment".into(), strings(&spec.env));
+    }
+    if !spec.ports.is_empty() {
+        let ports: Vec<String> = spec.ports.iter().map(short_port).collect();
+        service.insert("ports".into(), strings(&ports));
+    }
+    if !spec.binds.is_empty() {
+        service.insert("volumes".into(), strings(&spec.binds));
+    }
+    if !spec.labels.is_empty() {
+        let labels = spec
+            .labels
+            .iter()
+            .map(|(key, value)| (key.clone().into(), value.clone().into()))
+            .collect::<Mapping>();
+        service.insert("labels".into(), Value::Mapping(labels));
+    }
+    if !spec.working_dir.is_empty() {
+        service.insert("working_dir".into(), spec.working_dir.clone().into());
+    }
+    if !spec.user.is_empty() {
+        service.insert("user".into(), spec.user.clone().into());
+    }
+    if spec.restart_policy != RestartPolicyKind::No {
+        service.insert(
+            "restart".into(),
+            spec.restart_policy.flag(spec.max_retries).into(),
+        );
+    }
+
+    let mut file = Mapping::new();
+    if spec.network.is_empty() {
+        file.insert("services".into(), service_map(spec, service));
+    } else if is_network_mode(&spec.network) {
+        // These are not networks a service can join, and Compose refuses them there
+        service.insert("network_mode".into(), spec.network.clone().into());
+        file.insert("services".into(), service_map(spec, service));
+    } else {
+        service.insert(
+            "networks".into(),
+            strings(std::slice::from_ref(&spec.network)),
+        );
+        file.insert("services".into(), service_map(spec, service));
+
+        // The network already exists, so Compose should join it rather than create it
+        let mut external = Mapping::new();
+        external.insert("external".into(), true.into());
+        let mut networks = Mapping::new();
+        networks.insert(spec.network.clone().into(), Value::Mapping(external));
+        file.insert("networks".into(), Value::Mapping(networks));
+    }
+
+    Ok(serde_yaml::to_string(&file)?)
+}
+
+/// Whether `network` names a network mode rather than a network.
+fn is_network_mode(network: &str) -> bool {
+    matches!(network, "host" | "none") || network.starts_with("container:")
+}
+
+fn service_map(spec: &ContainerSpec, service: Mapping) -> Value {
+    let name = if spec.name.is_empty() {
+        "app"
+    } else {
+        &spec.name
+    };
+    let mut services = Mapping::new();
+    services.insert(name.into(), Value::Mapping(service));
+    Value::Mapping(services)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::compose_file::parse_port;
+
+    fn spec() -> ContainerSpec {
+        ContainerSpec {
+            name: "web".to_string(),
+            image: "nginx:1.27".to_string(),
+            command: vec!["nginx".into(), "-g".into(), "daemon off;".into()],
+            env: vec!["GREETING=it's me".to_string()],
+            ports: vec![
+                parse_port("8080:80").unwrap(),
+                parse_port("127.0.0.1:53:53/udp").unwrap(),
+            ],
+            network: "backend".to_string(),
+            restart_policy: RestartPolicyKind::UnlessStopped,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn run_command_quotes_what_the_shell_would_split() {
+        assert_eq!(
+            docker_run_command(&spec()),
+            "docker run -d \\\n  --name web \\\n  --restart unless-stopped \\\n  \
+             --network backend \\\n  -p 8080:80 \\\n  -p 127.0.0.1:53:53/udp \\\n  \
+             -e 'GREETING=it'\\''s me' \\\n  nginx:1.27 nginx -g 'daemon off;'"
+        );
+    }
+
+    #[test]
+    fn compose_service_joins_the_existing_network() {
+        let yaml = compose_service(&spec()).unwrap();
+        let parsed: Value = serde_yaml::from_str(&yaml).unwrap();
+        let service = &parsed["services"]["web"];
+        assert_eq!(service["image"], "nginx:1.27");
+        assert_eq!(service["ports"][1], "127.0.0.1:53:53/udp");
+        assert_eq!(service["command"][2], "daemon off;");
+        assert_eq!(service["restart"], "unless-stopped");
+        assert_eq!(parsed["networks"]["backend"]["external"], true);
+    }
+
+    #[test]
+    fn network_modes_and_retry_counts_survive() {
+        let spec = ContainerSpec {
+            network: "container:db".to_string(),
+            restart_policy: RestartPolicyKind::OnFailure,
+            max_retries: 3,
+            ..spec()
+        };
+        assert!(docker_run_command(&spec).contains("--restart on-failure:3"));
+
+        let parsed: Value = serde_yaml::from_str(&compose_service(&spec).unwrap()).unwrap();
+        let service = &parsed["services"]["web"];
+        assert_eq!(service["network_mode"], "container:db");
+        assert_eq!(service["networks"], Value::Null);
+        assert_eq!(service["restart"], "on-failure:3");
+        assert_eq!(parsed["networks"], Value::Null);
+    }
+}
@@ -0,0 +1,238 @@
+//! A stub Docker Engine API served over a unix socket, so `DockerService` can be
+//! exercised end to end without a daemon. It speaks just enough HTTP/1.1 for
+//! bollard and answers the endpoints the list and start/stop calls use with the
+//! fixtures in `tests/fixtures`.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bollard::{API_DEFAULT_VERSION, Docker};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::task::JoinHandle;
+
+use super::docker::{ContainerState, DockerService};
+
+const CONTAINERS: &str = include_str!("../../tests/fixtures/containers.json");
+const IMAGES: &str = include_str!("../../tests/fixtures/images.json");
+const VOLUMES: &str = include_str!("../../tests/fixtures/volumes.json");
+
+/// Containers the stub knows about, by their short id.
+const KNOWN_CONTAINERS: [&str; 2] = ["4f3c2b1a9e8d", "9a8b7c6d5e4f"];
+
+struct StubEngine {
+    dir: PathBuf,
+    requests: Arc<Mutex<Vec<String>>>,
+    server: JoinHandle<()>,
+}
+
+impl StubEngine {
+    fn start() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "doctainr-stub-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let listener = UnixListener::bind(dir.join("docker.sock")).unwrap();
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let log = requests.clone();
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, log.clone()));
+            }
+        });
+
+        Self {
+            dir,
+            requests,
+            server,
+        }
+    }
+
+    fn service(&self) -> DockerService {
+        let socket = self.dir.join("docker.sock");
+        let docker =
+            Docker::connect_with_unix(socket.to_str().unwrap(), 5, API_DEFAULT_VERSION).unwrap();
+        DockerService { docker }
+    }
+
+    /// `METHOD /path` of every request served so far, without the version prefix or query.
+    fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for StubEngine {
+    fn drop(&mut self) {
+        self.server.abort();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+async fn serve(stream: UnixStream, log: Arc<Mutex<Vec<String>>>) {
+    let mut reader = BufReader::new(stream);
+    // bollard keeps connections alive, so answer requests until the client hangs up
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).await.unwrap_or(0) == 0 {
+            return;
+        }
+
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).await.unwrap();
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':')
+                && name.eq_ignore_ascii_case("content-length")
+            {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).await.unwrap();
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let path = strip_version(parts.next().unwrap_or_default());
+        log.lock().unwrap().push(format!("{} {}", method, path));
+
+        let (status, body) = route(&method, &path);
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        reader
+            .get_mut()
+            .write_all(response.as_bytes())
+            .await
+            .unwrap();
+    }
+}
+
+/// Drops the query string and the `/v1.xx` prefix bollard puts on every path.
+fn strip_version(target: &str) -> String {
+    let path = target.split('?').next().unwrap_or_default();
+    match path.strip_prefix("/v") {
+        Some(rest) => match rest.split_once('/') {
+            Some((version, rest)) if version.chars().all(|c| c.is_ascii_digit() || c == '.') => {
+                format!("/{}", rest)
+            }
+            _ => path.to_string(),
+        },
+        None => path.to_string(),
+    }
+}
+
+fn route(method: &str, path: &str) -> (&'static str, String) {
+    let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        ("GET", ["containers", "json"]) => ("200 OK", CONTAINERS.to_string()),
+        ("GET", ["images", "json"]) => ("200 OK", IMAGES.to_string()),
+        ("GET", ["volumes"]) => ("200 OK", VOLUMES.to_string()),
+        ("POST", ["containers", id, "start" | "stop"]) => {
+            if KNOWN_CONTAINERS.iter().any(|known| known.starts_with(id)) {
+                ("204 No Content", String::new())
+            } else {
+                (
+                    "404 Not Found",
+                    format!(r#"{{"message":"No such container: {}"}}"#, id),
+                )
+            }
+        }
+        _ => (
+            "404 Not Found",
+            format!(r#"{{"message":"page not found: {} {}"}}"#, method, path),
+        ),
+    }
+}
+
+#[tokio::test]
+async fn list_containers_maps_fixture() {
+    let engine = StubEngine::start();
+    let containers = engine.service().list_containers().await.unwrap();
+
+    assert_eq!(containers.len(), 2);
+    let web = &containers[0];
+    assert_eq!(web.id, "4f3c2b1a9e8d");
+    assert_eq!(web.name, "shop-web-1");
+    assert_eq!(web.image, "nginx:1.27");
+    assert_eq!(web.status, "Up 2 hours");
+    let ports: Vec<String> = web.ports.iter().map(ToString::to_string).collect();
+    assert_eq!(ports, vec!["8080 → 80/tcp", "443/tcp"]);
+    assert_eq!(web.state, ContainerState::Running);
+    assert_eq!(web.compose_project.as_deref(), Some("shop"));
+    assert_eq!(web.compose_service.as_deref(), Some("web"));
+
+    let db = &containers[1];
+    assert_eq!(db.name, "db");
+    assert!(db.ports.is_empty());
+    assert_eq!(db.state, ContainerState::Stopped);
+    assert_eq!(db.compose_project, None);
+    assert_eq!(db.volumes, vec!["shop_db-data"]);
+    assert!(web.volumes.is_empty());
+
+    assert_eq!(engine.requests(), vec!["GET /containers/json"]);
+}
+
+#[tokio::test]
+async fn list_images_maps_fixture() {
+    let engine = StubEngine::start();
+    let images = engine.service().list_images().await.unwrap();
+
+    assert_eq!(images.len(), 2);
+    assert_eq!(
+        images[0].id,
+        "sha256:5ef79149e0ec84a7a9f9284c3f91aa3c20608f8391f5445eabe92ef07dbda03c"
+    );
+    assert_eq!(images[0].repository, "nginx");
+    assert_eq!(images[0].tag, "1.27");
+    assert_eq!(images[0].size, "187.9MB");
+    assert_eq!(images[1].repository, "<none>");
+    assert_eq!(images[1].tag, "<none>");
+    assert_eq!(images[1].size, "2.0KB");
+}
+
+#[tokio::test]
+async fn list_volumes_maps_fixture() {
+    let engine = StubEngine::start();
+    let volumes = engine.service().list_volumes().await.unwrap();
+
+    assert_eq!(volumes.len(), 1);
+    assert_eq!(volumes[0].name, "shop_db-data");
+    assert_eq!(volumes[0].driver, "local");
+    assert_eq!(
+        volumes[0].mountpoint,
+        "/var/lib/docker/volumes/shop_db-data/_data"
+    );
+    assert_eq!(volumes[0].size, "--");
+}
+
+#[tokio::test]
+async fn start_and_stop_hit_container_endpoints() {
+    let engine = StubEngine::start();
+    let service = engine.service();
+
+    service.start_container("9a8b7c6d5e4f").await.unwrap();
+    service.stop_container("4f3c2b1a9e8d").await.unwrap();
+    let missing = service.start_container("deadbeef").await.unwrap_err();
+
+    assert!(missing.to_string().contains("No such container: deadbeef"));
+    assert_eq!(
+        engine.requests(),
+        vec![
+            "POST /containers/9a8b7c6d5e4f/start",
+            "POST /containers/4f3c2b1a9e8d/stop",
+            "POST /containers/deadbeef/start",
+        ]
+    );
+}
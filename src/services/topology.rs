@@ -0,0 +1,362 @@
+use anyhow::Result;
+use bollard::container::InspectContainerOptions;
+use bollard::models::ContainerInspectResponse;
+use futures_util::stream::{self, StreamExt};
+
+use super::docker::{ContainerState, DockerService};
+use super::error::DockerError;
+
+/// How many containers are inspected at once while building the graph.
+pub(super) const INSPECT_CONCURRENCY: usize = 8;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TopologyNetwork {
+    pub id: String,
+    pub name: String,
+    pub driver: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TopologyContainer {
+    pub id: String,
+    pub name: String,
+    pub state: ContainerState,
+    pub ports: Vec<String>,
+}
+
+/// A container's endpoint on a network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TopologyLink {
+    pub network_id: String,
+    pub container_id: String,
+    pub ip_address: String,
+    pub aliases: Vec<String>,
+}
+
+/// A listed container whose inspect failed, so it is missing from the graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedContainer {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NetworkTopology {
+    pub networks: Vec<TopologyNetwork>,
+    pub containers: Vec<TopologyContainer>,
+    pub links: Vec<TopologyLink>,
+    pub skipped: Vec<SkippedContainer>,
+}
+
+impl NetworkTopology {
+    pub fn links_for_container<'a>(
+        &'a self,
+        container_id: &'a str,
+    ) -> impl Iterator<Item = &'a TopologyLink> {
+        self.links
+            .iter()
+            .filter(move |link| link.container_id == container_id)
+    }
+
+    pub fn links_for_network<'a>(
+        &'a self,
+        network_id: &'a str,
+    ) -> impl Iterator<Item = &'a TopologyLink> {
+        self.links
+            .iter()
+            .filter(move |link| link.network_id == network_id)
+    }
+
+    pub fn network_name(&self, network_id: &str) -> String {
+        self.networks
+            .iter()
+            .find(|network| network.id == network_id)
+            .map(|network| network.name.clone())
+            .unwrap_or_else(|| network_id.to_string())
+    }
+
+    pub fn container_name(&self, container_id: &str) -> String {
+        self.containers
+            .iter()
+            .find(|container| container.id == container_id)
+            .map(|container| container.name.clone())
+            .unwrap_or_else(|| container_id.to_string())
+    }
+}
+
+impl DockerService {
+    /// Builds the network/container graph from network listings and container inspect data.
+    pub async fn network_topology(&self) -> Result<NetworkTopology> {
+        let networks = self.list_networks().await?;
+        let containers = self.list_containers().await?;
+
+        let mut topology = NetworkTopology {
+            networks: networks
+                .into_iter()
+                .map(|network| TopologyNetwork {
+                    id: network.id,
+                    name: network.name,
+                    driver: network.driver,
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut inspected: Vec<_> = stream::iter(containers.into_iter().enumerate())
+            .map(|(index, container)| async move {
+                let details = self
+                    .docker
+                    .inspect_container(&container.id, None::<InspectContainerOptions>)
+                    .await;
+                (index, container, details)
+            })
+            .buffer_unordered(INSPECT_CONCURRENCY)
+            .collect()
+            .await;
+        // Keep the listing order so the graph does not reshuffle on every refresh
+        inspected.sort_by_key(|(index, _, _)| *index);
+
+        for (_, container, details) in inspected {
+            // A container removed since the listing should not take the whole graph down
+            match details {
+                Ok(details) => {
+                    let (container, links) =
+                        container_links(&topology.networks, details, container.state);
+                    topology.containers.push(container);
+                    topology.links.extend(links);
+                }
+                Err(e) => topology.skipped.push(SkippedContainer {
+                    name: container.name,
+                    reason: DockerError::from(&anyhow::Error::from(e)).to_string(),
+                }),
+            }
+        }
+
+        Ok(topology)
+    }
+}
+
+pub(super) fn container_links(
+    networks: &[TopologyNetwork],
+    details: ContainerInspectResponse,
+    state: ContainerState,
+) -> (TopologyContainer, Vec<TopologyLink>) {
+    let id: String = details.id.unwrap_or_default().chars().take(12).collect();
+    let name = details
+        .name
+        .map(|name| name.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| "unnamed".to_string());
+    let settings = details.network_settings.unwrap_or_default();
+
+    let mut ports: Vec<String> = settings
+        .ports
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|(private, bindings)| {
+            bindings
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(move |binding| {
+                    let host_port = binding.host_port?;
+                    let host_ip = binding.host_ip.unwrap_or_default();
+                    let host = if host_ip.is_empty() || host_ip == "0.0.0.0" {
+                        String::new()
+                    } else {
+                        format!("{}:", host_ip)
+                    };
+                    Some(format!("{}{} → {}", host, host_port, private))
+                })
+        })
+        .collect();
+    ports.sort();
+    ports.dedup();
+
+    let mut links: Vec<TopologyLink> = settings
+        .networks
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(network_name, endpoint)| {
+            // Inspect data keys endpoints by network name; map back to the listed id
+            let network_id = networks
+                .iter()
+                .find(|network| network.name == network_name)
+                .map(|network| network.id.clone())?;
+            Some(TopologyLink {
+                network_id,
+                container_id: id.clone(),
+                ip_address: endpoint
+                    .ip_address
+                    .filter(|address| !address.is_empty())
+                    .unwrap_or_else(|| "--".to_string()),
+                aliases: endpoint
+                    .aliases
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|alias| !id.starts_with(alias.as_str()))
+                    .collect(),
+            })
+        })
+        .collect();
+    links.sort_by(|a, b| a.network_id.cmp(&b.network_id));
+
+    (
+        TopologyContainer {
+            id,
+            name,
+            state,
+            ports,
+        },
+        links,
+    )
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Network,
+    Container,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphNode {
+    pub id: String,
+    pub label: String,
+    pub kind: NodeKind,
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphEdge {
+    pub network_id: String,
+    pub container_id: String,
+    pub label: String,
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphLayout {
+    pub width: f64,
+    pub height: f64,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+const GRAPH_WIDTH: f64 = 760.0;
+const COLUMN_MARGIN: f64 = 150.0;
+const ROW_SPACING: f64 = 72.0;
+const PADDING: f64 = 48.0;
+
+/// Lays the graph out in two columns: networks on the left, containers on the right.
+/// The shorter column is centred against the taller one so edges fan out evenly.
+pub fn layout(topology: &NetworkTopology) -> GraphLayout {
+    let rows = topology
+        .networks
+        .len()
+        .max(topology.containers.len())
+        .max(1);
+    let height = PADDING * 2.0 + (rows - 1) as f64 * ROW_SPACING;
+
+    let column = |count: usize, index: usize| {
+        let offset = (rows - count) as f64 * ROW_SPACING / 2.0;
+        PADDING + offset + index as f64 * ROW_SPACING
+    };
+
+    let mut nodes = Vec::new();
+    for (index, network) in topology.networks.iter().enumerate() {
+        nodes.push(GraphNode {
+            id: network.id.clone(),
+            label: network.name.clone(),
+            kind: NodeKind::Network,
+            x: COLUMN_MARGIN,
+            y: column(topology.networks.len(), index),
+        });
+    }
+    for (index, container) in topology.containers.iter().enumerate() {
+        nodes.push(GraphNode {
+            id: container.id.clone(),
+            label: container.name.clone(),
+            kind: NodeKind::Container,
+            x: GRAPH_WIDTH - COLUMN_MARGIN,
+            y: column(topology.containers.len(), index),
+        });
+    }
+
+    let position = |kind: NodeKind, id: &str| {
+        nodes
+            .iter()
+            .find(|node| node.kind == kind && node.id == id)
+            .map(|node| (node.x, node.y))
+    };
+
+    let edges = topology
+        .links
+        .iter()
+        .filter_map(|link| {
+            Some(GraphEdge {
+                network_id: link.network_id.clone(),
+                container_id: link.container_id.clone(),
+                label: link.ip_address.clone(),
+                from: position(NodeKind::Network, &link.network_id)?,
+                to: position(NodeKind::Container, &link.container_id)?,
+            })
+        })
+        .collect();
+
+    GraphLayout {
+        width: GRAPH_WIDTH,
+        height,
+        nodes,
+        edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_topology() -> NetworkTopology {
+        let network = |id: &str| TopologyNetwork {
+            id: id.to_string(),
+            name: format!("{}-net", id),
+            driver: "bridge".to_string(),
+        };
+        let container = |id: &str| TopologyContainer {
+            id: id.to_string(),
+            name: id.to_string(),
+            state: ContainerState::Running,
+            ports: Vec::new(),
+        };
+        NetworkTopology {
+            networks: vec![network("front")],
+            containers: vec![container("web"), container("api"), container("db")],
+            links: vec![
+                TopologyLink {
+                    network_id: "front".to_string(),
+                    container_id: "web".to_string(),
+                    ip_address: "172.18.0.2".to_string(),
+                    aliases: Vec::new(),
+                },
+                TopologyLink {
+                    network_id: "missing".to_string(),
+                    container_id: "db".to_string(),
+                    ip_address: "--".to_string(),
+                    aliases: Vec::new(),
+                },
+            ],
+            skipped: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn layout_centres_shorter_column_and_skips_dangling_links() {
+        let layout = layout(&sample_topology());
+
+        let network = &layout.nodes[0];
+        let api = layout.nodes.iter().find(|node| node.id == "api").unwrap();
+        assert_eq!(network.y, api.y);
+        assert_eq!(layout.edges.len(), 1);
+        assert_eq!(layout.edges[0].label, "172.18.0.2");
+        assert_eq!(layout.height, PADDING * 2.0 + 2.0 * ROW_SPACING);
+    }
+}
@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+
+use super::ErrorReport;
+
+/// How many entries the log keeps before dropping the oldest.
+const MAX_ENTRIES: usize = 200;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Succeeded,
+    Failed(ErrorReport),
+}
+
+/// One operation the app ran against the engine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActivityEntry {
+    pub id: u64,
+    pub at: DateTime<Local>,
+    pub resource: String,
+    pub operation: String,
+    pub outcome: Outcome,
+    pub duration: Duration,
+}
+
+impl ActivityEntry {
+    pub fn summary(&self) -> String {
+        format!("{} {}", self.operation, self.resource)
+    }
+
+    pub fn time_label(&self) -> String {
+        self.at.format("%H:%M:%S").to_string()
+    }
+
+    pub fn duration_label(&self) -> String {
+        let millis = self.duration.as_millis();
+        if millis < 1000 {
+            format!("{} ms", millis)
+        } else {
+            format!("{:.1} s", self.duration.as_secs_f64())
+        }
+    }
+
+    pub fn failure(&self) -> Option<&ErrorReport> {
+        match &self.outcome {
+            Outcome::Succeeded => None,
+            Outcome::Failed(report) => Some(report),
+        }
+    }
+}
+
+/// Append-only history of operations, newest last. Recording a success never
+/// touches earlier failures, so an error stays visible until the user reads it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ActivityLog {
+    entries: Vec<ActivityEntry>,
+    next_id: u64,
+}
+
+impl ActivityLog {
+    pub fn push(
+        &mut self,
+        resource: String,
+        operation: String,
+        outcome: Outcome,
+        duration: Duration,
+    ) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(ActivityEntry {
+            id,
+            at: Local::now(),
+            resource,
+            operation,
+            outcome,
+            duration,
+        });
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        id
+    }
+
+    pub fn entries(&self) -> &[ActivityEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: u64) -> Option<&ActivityEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    pub fn failure_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.failure().is_some())
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::DockerError;
+
+    #[test]
+    fn successes_do_not_clear_failures() {
+        let mut log = ActivityLog::default();
+        let report = ErrorReport::from_error(
+            "Failed to stop container web",
+            DockerError::Conflict("container is restarting".to_string()),
+        );
+        let failed = log.push(
+            "container web".to_string(),
+            "Stop".to_string(),
+            Outcome::Failed(report.clone()),
+            Duration::from_millis(40),
+        );
+        let listed = log.push(
+            "images".to_string(),
+            "List".to_string(),
+            Outcome::Succeeded,
+            Duration::from_millis(1500),
+        );
+
+        assert_eq!(log.entries().len(), 2);
+        assert_eq!(log.failure_count(), 1);
+        assert_eq!(log.get(failed).unwrap().failure(), Some(&report));
+        assert_eq!(log.get(failed).unwrap().duration_label(), "40 ms");
+        assert_eq!(log.get(listed).unwrap().summary(), "List images");
+        assert_eq!(log.get(listed).unwrap().duration_label(), "1.5 s");
+    }
+}
@@ -1,22 +1,111 @@
+mod browsers;
+mod bulk;
+mod compose;
+mod containers;
+mod limits;
+mod networks;
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use dioxus::prelude::*;
 
-use crate::services::{ContainerInfo, ContainerState, DockerService, ImageInfo, VolumeInfo};
+use super::activity::{ActivityLog, Outcome};
+use super::confirmation::Confirmation;
+use super::notifications::notify;
+use super::polling::{PollSettings, PollState};
+use super::preferences::{Preferences, load_preferences, save_preferences};
+use super::presets::{FilterPreset, load_presets, save_presets};
+use super::table_query::{TableKind, TableQuery};
+use super::{ErrorReport, ResourceStatus};
+use crate::services::{
+    ContainerHealth, ContainerInfo, ContainerLimits, ContainerSpec, DiskUsage, DockerBackend,
+    DockerError, EngineInfo, ImageInfo, NetworkDetails, NetworkInfo, NetworkTopology,
+    PublishedPort, VolumeInfo, connect, health_alerts, mark_in_use,
+};
+
+pub use browsers::FileBrowser;
+pub use bulk::{BulkAction, BulkRun};
+pub use compose::{ComposeCommand, ComposeSession, ProjectAction};
+pub use containers::{ContainerChanges, ContainerProcesses};
+
+/// How many toasts are stacked before the oldest is dropped.
+const MAX_TOASTS: usize = 4;
+/// How long a success toast stays on screen.
+const TOAST_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// How often the background refresher wakes to check whether a refresh is due.
+const POLL_TICK: Duration = Duration::from_secs(1);
+/// Reports `document.hidden` now and whenever it changes.
+const VISIBILITY_JS: &str = r#"
+    dioxus.send(document.hidden);
+    document.addEventListener("visibilitychange", () => dioxus.send(document.hidden));
+"#;
 
 #[derive(Clone)]
 pub struct AppState {
     pub docker_host: Signal<String>,
+    pub engine: Signal<Option<EngineInfo>>,
+    pub disk_usage: Signal<Option<DiskUsage>>,
     pub containers: Signal<Vec<ContainerInfo>>,
+    /// Host ports each container was created with, by id. The container list only
+    /// reports the ports of running containers, so these come from inspect; they
+    /// never change after creation, so each container is inspected once.
+    pub port_bindings: Signal<HashMap<String, Vec<PublishedPort>>>,
     pub images: Signal<Vec<ImageInfo>>,
     pub volumes: Signal<Vec<VolumeInfo>>,
-    pub last_action: Signal<Option<String>>,
-    pub error_message: Signal<Option<String>>,
-    pub is_loading: Signal<bool>,
-    docker_service: Option<DockerService>,
+    pub networks: Signal<Vec<NetworkInfo>>,
+    pub selected_network: Signal<Option<NetworkDetails>>,
+    pub topology: Signal<Option<NetworkTopology>>,
+    pub activity: Signal<ActivityLog>,
+    /// Ids of the activity entries currently shown as toasts, oldest first.
+    pub toasts: Signal<Vec<u64>>,
+    pub containers_status: Signal<ResourceStatus>,
+    pub images_status: Signal<ResourceStatus>,
+    pub volumes_status: Signal<ResourceStatus>,
+    pub networks_status: Signal<ResourceStatus>,
+    pub volume_browser: Signal<Option<FileBrowser>>,
+    pub container_browser: Signal<Option<FileBrowser>>,
+    pub container_changes: Signal<Option<ContainerChanges>>,
+    pub container_processes: Signal<Option<ContainerProcesses>>,
+    /// Limits of the container on the detail page, keyed by its id.
+    pub container_limits: Signal<Option<(String, ContainerLimits)>>,
+    /// What the create-container form starts from while it is open, numbered so
+    /// the form starts over each time a new draft replaces an open one.
+    pub container_draft: Signal<Option<(u64, ContainerSpec)>>,
+    /// Settings of the container on the detail page, keyed by its id.
+    pub container_spec: Signal<Option<(String, ContainerSpec)>>,
+    /// Health check results of the container on the detail page, keyed by its id.
+    pub container_health: Signal<Option<(String, ContainerHealth)>>,
+    pub compose: Signal<Option<ComposeSession>>,
+    pub container_query: Signal<TableQuery>,
+    pub image_query: Signal<TableQuery>,
+    pub volume_query: Signal<TableQuery>,
+    pub presets: Signal<Vec<FilterPreset>>,
+    /// Ids of the selected rows of each table.
+    pub selection: Signal<HashMap<TableKind, HashSet<String>>>,
+    pub bulk: Signal<Option<BulkRun>>,
+    /// Destructive operations waiting on the confirmation modal.
+    pub confirmation: Signal<Option<Confirmation>>,
+    /// Requires typed confirmation and refuses to touch protected containers.
+    pub safe_mode: Signal<bool>,
+    /// Shows a desktop notification when a container turns unhealthy or crashes.
+    pub notifications: Signal<bool>,
+    pub polling: Signal<PollSettings>,
+    pub poll_state: Signal<PollState>,
+    window_hidden: Signal<bool>,
+    /// Containers the app is stopping or restarting itself, whose exits are not
+    /// worth a notification. Marked `true` once the action is done, and dropped
+    /// by the next container refresh that starts after that.
+    own_stops: Signal<HashMap<String, bool>>,
+    docker_service: Option<Arc<dyn DockerBackend>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
-        let docker_service = match DockerService::new() {
+        let docker_service = match connect() {
             Ok(service) => Some(service),
             Err(e) => {
                 eprintln!("Failed to connect to Docker: {}", e);
@@ -24,164 +113,762 @@ impl AppState {
             }
         };
 
+        Self::with_backend(docker_service)
+    }
+
+    /// Builds the state around an already chosen backend, such as a `FakeEngine`.
+    pub fn with_backend(docker_service: Option<Arc<dyn DockerBackend>>) -> Self {
+        let preferences = use_hook(load_preferences);
         let docker_host = use_signal(|| {
-            std::env::var("DOCKER_HOST")
-                .unwrap_or_else(|_| "unix:///var/run/docker.sock".to_string())
+            docker_service
+                .as_ref()
+                .map(|service| service.host())
+                .unwrap_or_else(|| {
+                    std::env::var("DOCKER_HOST")
+                        .unwrap_or_else(|_| "unix:///var/run/docker.sock".to_string())
+                })
         });
+        let engine = use_signal(|| None);
+        let disk_usage = use_signal(|| None);
         let containers = use_signal(Vec::new);
+        let port_bindings = use_signal(HashMap::new);
         let images = use_signal(Vec::new);
         let volumes = use_signal(Vec::new);
-        let last_action = use_signal(|| None);
-        let error_message = use_signal(|| None);
-        let is_loading = use_signal(|| false);
+        let networks = use_signal(Vec::new);
+        let selected_network = use_signal(|| None);
+        let topology = use_signal(|| None);
+        let activity = use_signal(ActivityLog::default);
+        let toasts = use_signal(Vec::new);
+        let containers_status = use_signal(ResourceStatus::default);
+        let images_status = use_signal(ResourceStatus::default);
+        let volumes_status = use_signal(ResourceStatus::default);
+        let networks_status = use_signal(ResourceStatus::default);
+        let volume_browser = use_signal(|| None);
+        let container_browser = use_signal(|| None);
+        let container_changes = use_signal(|| None);
+        let container_processes = use_signal(|| None);
+        let container_limits = use_signal(|| None);
+        let container_draft = use_signal(|| None);
+        let container_spec = use_signal(|| None);
+        let container_health = use_signal(|| None);
+        let compose = use_signal(|| None);
+        let container_query = use_signal(TableQuery::default);
+        let image_query = use_signal(TableQuery::default);
+        let volume_query = use_signal(TableQuery::default);
+        let presets = use_signal(load_presets);
+        let selection = use_signal(HashMap::new);
+        let bulk = use_signal(|| None);
+        let confirmation = use_signal(|| None);
+        let safe_mode = use_signal(|| preferences.safe_mode);
+        let notifications = use_signal(|| preferences.notifications);
+        let polling = use_signal(|| preferences.polling);
+        let poll_state = use_signal(|| PollState::Off);
+        let window_hidden = use_signal(|| false);
+        let own_stops = use_signal(HashMap::new);
 
         let state = Self {
             docker_host,
+            engine,
+            disk_usage,
             containers,
+            port_bindings,
             images,
             volumes,
-            last_action,
-            error_message,
-            is_loading,
+            networks,
+            selected_network,
+            topology,
+            activity,
+            toasts,
+            containers_status,
+            images_status,
+            volumes_status,
+            networks_status,
+            volume_browser,
+            container_browser,
+            container_changes,
+            container_processes,
+            container_limits,
+            container_draft,
+            container_spec,
+            container_health,
+            compose,
+            container_query,
+            image_query,
+            volume_query,
+            presets,
+            selection,
+            bulk,
+            confirmation,
+            safe_mode,
+            notifications,
+            polling,
+            poll_state,
+            window_hidden,
+            own_stops,
             docker_service,
         };
 
         // Spawn initial data load
         state.refresh_all();
+        state.start_polling();
 
         state
     }
 
     pub fn refresh_all(&self) {
+        self.refresh_engine();
+        self.refresh_lists();
+        self.refresh_disk_usage();
+    }
+
+    /// Refreshes the resource lists but not engine details or disk usage, which
+    /// are slow to compute on busy hosts and only change on a manual refresh.
+    pub fn refresh_lists(&self) {
         self.refresh_containers();
         self.refresh_images();
         self.refresh_volumes();
+        self.refresh_networks();
+    }
+
+    /// Re-runs `refresh_lists` every `polling` interval and backs off while the
+    /// daemon is unreachable. While the window is hidden only containers are
+    /// refreshed, for notifications, or nothing when those are off.
+    fn start_polling(&self) {
+        if self.docker_service.is_none() {
+            return;
+        }
+
+        let mut window_hidden = self.window_hidden;
+        spawn(async move {
+            let mut visibility = document::eval(VISIBILITY_JS);
+            while let Ok(hidden) = visibility.recv::<bool>().await {
+                window_hidden.set(hidden);
+            }
+        });
+
+        let app_state = self.clone();
+        spawn(async move {
+            let mut poll_state = app_state.poll_state;
+            let mut last_refresh = Instant::now();
+            let mut awaiting_result = false;
+            let mut failures = 0;
+
+            loop {
+                tokio::time::sleep(POLL_TICK).await;
+
+                // Count the last refresh once it has finished, to decide on backoff
+                let status = (app_state.containers_status)();
+                if awaiting_result && !status.is_loading {
+                    awaiting_result = false;
+                    failures = match status.error {
+                        Some(DockerError::Unreachable(_) | DockerError::Timeout) => failures + 1,
+                        _ => 0,
+                    };
+                }
+
+                let settings = (app_state.polling)();
+                let hidden = window_hidden();
+                let state = if !settings.enabled {
+                    PollState::Off
+                } else if hidden && !(app_state.notifications)() {
+                    PollState::Paused
+                } else if failures > 0 {
+                    PollState::BackingOff(settings.delay(failures))
+                } else if hidden {
+                    PollState::Watching(settings.delay(0))
+                } else {
+                    PollState::Waiting(settings.delay(0))
+                };
+                if *poll_state.peek() != state {
+                    poll_state.set(state);
+                }
+
+                let (PollState::Waiting(delay)
+                | PollState::Watching(delay)
+                | PollState::BackingOff(delay)) = state
+                else {
+                    continue;
+                };
+                if awaiting_result || last_refresh.elapsed() < delay {
+                    continue;
+                }
+                last_refresh = Instant::now();
+                awaiting_result = true;
+                if hidden {
+                    app_state.refresh_containers();
+                } else {
+                    app_state.refresh_lists();
+                }
+            }
+        });
+    }
+
+    pub fn set_polling(&self, settings: PollSettings) {
+        let mut polling = self.polling;
+        polling.set(settings);
+        self.store_preferences();
+    }
+
+    pub fn set_safe_mode(&self, enabled: bool) {
+        let mut safe_mode = self.safe_mode;
+        safe_mode.set(enabled);
+        self.store_preferences();
+    }
+
+    pub fn set_notifications(&self, enabled: bool) {
+        let mut notifications = self.notifications;
+        notifications.set(enabled);
+        self.store_preferences();
+    }
+
+    /// Marks `ids` as being stopped or restarted by the app, until `end_own_stops`.
+    fn begin_own_stops(&self, ids: &[String]) {
+        let mut own_stops = self.own_stops;
+        own_stops
+            .write()
+            .extend(ids.iter().map(|id| (id.clone(), false)));
+    }
+
+    fn end_own_stops(&self, ids: &[String]) {
+        let mut own_stops = self.own_stops;
+        let mut own_stops = own_stops.write();
+        for id in ids {
+            if let Some(done) = own_stops.get_mut(id) {
+                *done = true;
+            }
+        }
+    }
+
+    /// Saves the Settings page choices; only a failure is worth a toast.
+    fn store_preferences(&self) {
+        let started = Instant::now();
+        let preferences = Preferences {
+            polling: *self.polling.peek(),
+            safe_mode: *self.safe_mode.peek(),
+            notifications: *self.notifications.peek(),
+        };
+        if let Err(e) = save_preferences(&preferences) {
+            self.record_failure(
+                "settings",
+                "Save",
+                started,
+                ErrorReport::new("Failed to save settings", &e),
+            );
+        }
+    }
+
+    // Background refreshes only log their failures; a successful list is not news.
+
+    pub fn refresh_engine(&self) {
+        let mut engine = self.engine;
+        self.run(
+            EngineCall::new("engine info", "Read", "Failed to read engine info")
+                .quiet()
+                .with_retry(self.retry_refresh())
+                .on_failure(move |_| engine.set(None)),
+            |service| async move { service.engine_info().await },
+            move |data| engine.set(Some(data)),
+        );
     }
 
     pub fn refresh_containers(&self) {
+        let mut status = self.containers_status;
         if let Some(service) = &self.docker_service {
             let service = service.clone();
-            let mut containers = self.containers.clone();
-            let mut error_message = self.error_message.clone();
-            let mut is_loading = self.is_loading.clone();
+            let mut containers = self.containers;
+            let mut own_stops = self.own_stops;
+            let app_state = self.clone();
+            let started = Instant::now();
+            // Actions finished before this list was asked for are reflected in it
+            let settled: Vec<String> = own_stops
+                .peek()
+                .iter()
+                .filter(|(_, done)| **done)
+                .map(|(id, _)| id.clone())
+                .collect();
 
             spawn(async move {
-                is_loading.set(true);
+                status.write().begin();
                 match service.list_containers().await {
                     Ok(data) => {
+                        let watched: Vec<ContainerInfo> = data
+                            .iter()
+                            .filter(|container| !own_stops.peek().contains_key(&container.id))
+                            .cloned()
+                            .collect();
+                        let alerts = health_alerts(&containers.peek(), &watched);
+                        own_stops.write().retain(|id, _| !settled.contains(id));
                         containers.set(data);
-                        error_message.set(None);
+                        status.write().succeed();
+                        if *app_state.notifications.peek() {
+                            alerts.iter().for_each(notify);
+                        }
+                        app_state.refresh_port_bindings();
                     }
                     Err(e) => {
-                        error_message.set(Some(format!("Failed to list containers: {}", e)));
+                        status.write().fail(DockerError::from(&e));
+                        app_state.record_failure(
+                            "containers",
+                            "List",
+                            started,
+                            ErrorReport::new("Failed to list containers", &e)
+                                .with_retry(app_state.retry_refresh()),
+                        );
                     }
                 }
-                is_loading.set(false);
             });
         } else {
-            self.error_message
-                .clone()
-                .set(Some("Docker service not available".to_string()));
+            status.write().fail(not_connected());
+            self.record_failure(
+                "containers",
+                "List",
+                Instant::now(),
+                ErrorReport::from_error("Docker service not available", not_connected()),
+            );
+        }
+    }
+
+    /// Reads the host port bindings of listed containers not inspected yet, and
+    /// forgets the ones of containers that are gone.
+    fn refresh_port_bindings(&self) {
+        let mut port_bindings = self.port_bindings;
+        let listed: HashSet<String> = self
+            .containers
+            .peek()
+            .iter()
+            .map(|container| container.id.clone())
+            .collect();
+        if port_bindings.peek().keys().any(|id| !listed.contains(id)) {
+            port_bindings.write().retain(|id, _| listed.contains(id));
+        }
+        let ids: Vec<String> = listed
+            .into_iter()
+            .filter(|id| !port_bindings.peek().contains_key(id))
+            .collect();
+        if ids.is_empty() {
+            return;
         }
+
+        self.run(
+            EngineCall::new(
+                "port bindings",
+                "Inspect",
+                "Failed to read container port bindings",
+            )
+            .quiet(),
+            |service| async move { service.port_bindings(&ids).await },
+            move |found| port_bindings.write().extend(found),
+        );
     }
 
     pub fn refresh_images(&self) {
-        if let Some(service) = &self.docker_service {
-            let service = service.clone();
-            let mut images = self.images.clone();
-            let mut error_message = self.error_message.clone();
+        self.refresh_list(
+            self.images,
+            self.images_status,
+            EngineCall::new("images", "List", "Failed to list images"),
+            |service| async move { service.list_images().await },
+        );
+    }
 
-            spawn(async move {
-                match service.list_images().await {
-                    Ok(data) => {
-                        images.set(data);
-                        error_message.set(None);
-                    }
-                    Err(e) => {
-                        error_message.set(Some(format!("Failed to list images: {}", e)));
-                    }
+    pub fn refresh_volumes(&self) {
+        self.refresh_list(
+            self.volumes,
+            self.volumes_status,
+            EngineCall::new("volumes", "List", "Failed to list volumes"),
+            |service| async move { service.list_volumes().await },
+        );
+    }
+
+    pub fn refresh_networks(&self) {
+        self.refresh_list(
+            self.networks,
+            self.networks_status,
+            EngineCall::new("networks", "List", "Failed to list networks"),
+            |service| async move { service.list_networks().await },
+        );
+    }
+
+    /// Loads one table's rows into `list`, tracking the load in `status`.
+    fn refresh_list<T: 'static, F: Future<Output = anyhow::Result<Vec<T>>> + 'static>(
+        &self,
+        mut list: Signal<Vec<T>>,
+        mut status: Signal<ResourceStatus>,
+        call: EngineCall,
+        task: impl FnOnce(Arc<dyn DockerBackend>) -> F,
+    ) {
+        if self.docker_service.is_none() {
+            status.write().fail(not_connected());
+            return;
+        }
+        self.run(
+            call.quiet()
+                .with_retry(self.retry_refresh())
+                .on_failure(move |e| status.write().fail(DockerError::from(e))),
+            move |service| {
+                let load = task(service);
+                async move {
+                    status.write().begin();
+                    load.await
                 }
-            });
+            },
+            move |data| {
+                list.set(data);
+                status.write().succeed();
+            },
+        );
+    }
+
+    pub fn refresh_disk_usage(&self) {
+        let mut disk_usage = self.disk_usage;
+        self.run(
+            EngineCall::new("disk usage", "Read", "Failed to read disk usage")
+                .quiet()
+                .with_retry(self.retry_refresh()),
+            |service| async move { service.disk_usage().await },
+            move |data| disk_usage.set(Some(data)),
+        );
+    }
+
+    /// Offered on a failed refresh: reloads everything, as the engine may have come back.
+    fn retry_refresh(&self) -> impl Fn() + 'static {
+        let app_state = self.clone();
+        move || app_state.refresh_all()
+    }
+
+    pub fn query(&self, table: TableKind) -> Signal<TableQuery> {
+        match table {
+            TableKind::Containers => self.container_query,
+            TableKind::Images => self.image_query,
+            TableKind::Volumes => self.volume_query,
         }
     }
 
-    pub fn refresh_volumes(&self) {
-        if let Some(service) = &self.docker_service {
-            let service = service.clone();
-            let mut volumes = self.volumes.clone();
-            let mut error_message = self.error_message.clone();
+    /// Updates what `table` shows.
+    pub fn set_query(&self, table: TableKind, query: TableQuery) {
+        let mut current = self.query(table);
+        current.set(query);
+    }
 
-            spawn(async move {
-                match service.list_volumes().await {
-                    Ok(data) => {
-                        volumes.set(data);
-                        error_message.set(None);
-                    }
-                    Err(e) => {
-                        error_message.set(Some(format!("Failed to list volumes: {}", e)));
-                    }
-                }
-            });
+    /// Every volume, marked with whether a container mounts it.
+    pub fn volume_rows(&self) -> Vec<VolumeInfo> {
+        mark_in_use(self.volumes.read().clone(), &self.containers.read())
+    }
+
+    /// Saves the current query of `table` under `name`, replacing a preset of that name.
+    pub fn save_preset(&self, table: TableKind, name: String) {
+        let query = self.query(table).peek().clone();
+        let mut presets = self.presets;
+        presets
+            .write()
+            .retain(|preset| !(preset.table == table && preset.name == name));
+        presets.write().push(FilterPreset {
+            table,
+            name: name.clone(),
+            query,
+        });
+        self.store_presets(format!("preset {}", name), "Save");
+    }
+
+    pub fn delete_preset(&self, table: TableKind, name: String) {
+        let mut presets = self.presets;
+        presets
+            .write()
+            .retain(|preset| !(preset.table == table && preset.name == name));
+        self.store_presets(format!("preset {}", name), "Delete");
+    }
+
+    fn store_presets(&self, resource: String, operation: &str) {
+        let started = Instant::now();
+        match save_presets(&self.presets.peek()) {
+            Ok(()) => self.record_success(resource, operation, started),
+            Err(e) => self.record_failure(
+                resource,
+                operation,
+                started,
+                ErrorReport::new("Failed to save filter presets", &e),
+            ),
         }
     }
 
-    pub fn start_container(&self, id: String) {
-        if let Some(service) = &self.docker_service {
-            let service = service.clone();
-            let mut last_action = self.last_action.clone();
-            let mut error_message = self.error_message.clone();
-            let id_clone = id.clone();
-            let app_state = self.clone();
+    fn item_label(&self, table: TableKind, id: &str) -> String {
+        let label = match table {
+            TableKind::Containers => self
+                .containers
+                .peek()
+                .iter()
+                .find(|container| container.id == id)
+                .map(|container| container.name.clone()),
+            TableKind::Images => self
+                .images
+                .peek()
+                .iter()
+                .find(|image| image.id == id)
+                .filter(|image| image.repository != "<none>")
+                .map(|image| format!("{}:{}", image.repository, image.tag)),
+            TableKind::Volumes => None,
+        };
+        label.unwrap_or_else(|| id.to_string())
+    }
 
-            spawn(async move {
-                match service.start_container(&id_clone).await {
-                    Ok(_) => {
-                        last_action.set(Some(format!("Started container {}", id_clone)));
-                        error_message.set(None);
-                        // Refresh containers to get updated state
-                        app_state.refresh_containers();
-                    }
-                    Err(e) => {
-                        error_message.set(Some(format!("Failed to start container: {}", e)));
-                    }
-                }
-            });
+    fn ask(&self, confirmation: Confirmation) {
+        let mut pending = self.confirmation;
+        pending.set(Some(confirmation));
+    }
+
+    /// Closes the modal and runs the operation it was guarding.
+    pub fn confirm(&self) {
+        let mut pending = self.confirmation;
+        let confirmation = pending.take();
+        if let Some(confirmation) = confirmation {
+            confirmation.confirm();
         }
     }
 
-    pub fn stop_container(&self, id: String) {
-        if let Some(service) = &self.docker_service {
-            let service = service.clone();
-            let mut last_action = self.last_action.clone();
-            let mut error_message = self.error_message.clone();
-            let id_clone = id.clone();
-            let app_state = self.clone();
+    pub fn cancel_confirmation(&self) {
+        let mut pending = self.confirmation;
+        pending.set(None);
+    }
 
-            spawn(async move {
-                match service.stop_container(&id_clone).await {
-                    Ok(_) => {
-                        last_action.set(Some(format!("Stopped container {}", id_clone)));
-                        error_message.set(None);
-                        // Refresh containers to get updated state
-                        app_state.refresh_containers();
+    /// Runs the engine call `task` builds, timed from now, and logs how it went;
+    /// `on_success` gets the result. Nothing runs without a connection.
+    fn run<T: 'static, F: Future<Output = anyhow::Result<T>> + 'static>(
+        &self,
+        call: EngineCall,
+        task: impl FnOnce(Arc<dyn DockerBackend>) -> F,
+        on_success: impl FnOnce(T) + 'static,
+    ) {
+        let Some(service) = self.docker_service.clone() else {
+            return;
+        };
+        let task = task(service);
+        let app_state = self.clone();
+        let started = Instant::now();
+
+        spawn(async move {
+            match task.await {
+                Ok(value) => {
+                    if call.log_success {
+                        app_state.record_success(call.resource, call.operation, started);
                     }
-                    Err(e) => {
-                        error_message.set(Some(format!("Failed to stop container: {}", e)));
+                    on_success(value);
+                }
+                Err(e) => {
+                    if let Some(on_failure) = call.on_failure {
+                        on_failure(&e);
                     }
+                    let mut report = ErrorReport::new(call.failure, &e);
+                    if let Some(retry) = call.retry {
+                        report = report.with_retry(move || retry());
+                    }
+                    app_state.record_failure(call.resource, call.operation, started, report);
                 }
+            }
+        });
+    }
+
+    pub fn record_success(
+        &self,
+        resource: impl Into<String>,
+        operation: impl Into<String>,
+        started: Instant,
+    ) {
+        self.record(
+            resource.into(),
+            operation.into(),
+            started,
+            Outcome::Succeeded,
+        );
+    }
+
+    pub fn record_failure(
+        &self,
+        resource: impl Into<String>,
+        operation: impl Into<String>,
+        started: Instant,
+        report: ErrorReport,
+    ) {
+        self.record(
+            resource.into(),
+            operation.into(),
+            started,
+            Outcome::Failed(report),
+        );
+    }
+
+    /// Appends to the activity log and shows the entry as a toast. Success toasts
+    /// hide themselves after a few seconds; failures stay until dismissed.
+    fn record(&self, resource: String, operation: String, started: Instant, outcome: Outcome) {
+        let mut activity = self.activity;
+        let mut toasts = self.toasts;
+        let succeeded = outcome == Outcome::Succeeded;
+
+        let id = activity
+            .write()
+            .push(resource, operation, outcome, started.elapsed());
+        {
+            let activity = activity.read();
+            let summary = activity.get(id).map(|entry| entry.summary());
+            let mut toasts = toasts.write();
+            // A repeat of the same operation, such as a refresh retried by the poller,
+            // replaces its earlier toast instead of stacking up
+            toasts.retain(|toast| activity.get(*toast).map(|entry| entry.summary()) != summary);
+            toasts.push(id);
+            let overflow = toasts.len().saturating_sub(MAX_TOASTS);
+            toasts.drain(..overflow);
+        }
+
+        if succeeded {
+            spawn(async move {
+                tokio::time::sleep(TOAST_TIMEOUT).await;
+                toasts.write().retain(|toast| *toast != id);
             });
         }
     }
 
-    pub fn set_container_state(&self, id: &str, next_state: ContainerState) {
-        match next_state {
-            ContainerState::Running => self.start_container(id.to_string()),
-            ContainerState::Stopped => self.stop_container(id.to_string()),
+    pub fn dismiss_toast(&self, id: u64) {
+        let mut toasts = self.toasts;
+        toasts.write().retain(|toast| *toast != id);
+    }
+}
+
+/// Runs when an engine call fails, before the failure is logged.
+type FailureHook = Box<dyn FnOnce(&anyhow::Error)>;
+
+/// How `AppState::run` logs an engine call.
+struct EngineCall {
+    resource: String,
+    operation: &'static str,
+    /// What the error report says failed.
+    failure: String,
+    retry: Option<Rc<dyn Fn()>>,
+    on_failure: Option<FailureHook>,
+    log_success: bool,
+}
+
+impl EngineCall {
+    fn new(
+        resource: impl Into<String>,
+        operation: &'static str,
+        failure: impl Into<String>,
+    ) -> Self {
+        Self {
+            resource: resource.into(),
+            operation,
+            failure: failure.into(),
+            retry: None,
+            on_failure: None,
+            log_success: true,
         }
     }
 
-    pub fn record_action(&self, message: impl Into<String>) {
-        let mut last_action_signal = self.last_action.clone();
-        last_action_signal.set(Some(message.into()));
+    /// Only logs a failure, for loads where success is not news.
+    fn quiet(mut self) -> Self {
+        self.log_success = false;
+        self
+    }
+
+    /// Offers `retry` on the failure's error report.
+    fn with_retry(mut self, retry: impl Fn() + 'static) -> Self {
+        self.retry = Some(Rc::new(retry));
+        self
+    }
+
+    /// Runs `on_failure` before a failure is logged, such as to clear a spinner.
+    fn on_failure(mut self, on_failure: impl FnOnce(&anyhow::Error) + 'static) -> Self {
+        self.on_failure = Some(Box::new(on_failure));
+        self
+    }
+}
+
+fn not_connected() -> DockerError {
+    DockerError::Unreachable("no connection to the engine".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use dioxus::dioxus_core::NoOpMutations;
+
+    use super::*;
+    use crate::services::FakeEngine;
+
+    fn app() -> Element {
+        let app_state = AppState::with_backend(Some(Arc::new(FakeEngine::demo().unwrap())));
+        use_context_provider(|| app_state);
+        rsx! {}
+    }
+
+    /// Runs spawned tasks until the dom goes idle.
+    async fn settle(dom: &mut VirtualDom) {
+        while tokio::time::timeout(Duration::from_millis(50), dom.wait_for_work())
+            .await
+            .is_ok()
+        {
+            dom.render_immediate(&mut NoOpMutations);
+        }
+    }
+
+    fn app_state(dom: &VirtualDom) -> AppState {
+        dom.in_scope(ScopeId::APP, consume_context::<AppState>)
+    }
+
+    #[tokio::test]
+    async fn app_state_loads_from_fake_engine() {
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        settle(&mut dom).await;
+
+        dom.in_scope(ScopeId::APP, || {
+            let app_state = consume_context::<AppState>();
+            assert_eq!((app_state.docker_host)(), "fake://in-memory");
+            assert_eq!((app_state.containers)().len(), 4);
+            assert_eq!((app_state.networks)().len(), 4);
+            let status = (app_state.containers_status)();
+            assert!(!status.is_loading && status.error.is_none());
+            assert!(status.last_refreshed.is_some());
+            assert!((app_state.engine)().is_some());
+            // Background refreshes only log failures, so a healthy engine leaves no trace
+            assert!((app_state.activity)().entries().is_empty());
+        });
+    }
+
+    #[tokio::test]
+    async fn bulk_remove_keeps_failed_rows_selected() {
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        settle(&mut dom).await;
+
+        let ids: HashMap<String, String> = dom.in_scope(ScopeId::APP, || {
+            (app_state(&dom).containers)()
+                .into_iter()
+                .map(|container| (container.name, container.id))
+                .collect()
+        });
+        dom.in_scope(ScopeId::APP, || {
+            let app_state = app_state(&dom);
+            app_state.set_selected(
+                TableKind::Containers,
+                ["cache", "docs", "shop-web-1"]
+                    .into_iter()
+                    .map(|name| ids[name].clone())
+                    .collect(),
+            );
+            app_state.run_bulk(TableKind::Containers, BulkAction::Remove);
+        });
+        settle(&mut dom).await;
+
+        dom.in_scope(ScopeId::APP, || {
+            let app_state = app_state(&dom);
+            let run = (app_state.bulk)().unwrap();
+            assert!(!run.is_running);
+            assert_eq!(run.finished(), 3);
+            assert_eq!(run.failed(), 1);
+            // The running container cannot be removed and stays selected for a retry
+            assert_eq!(
+                app_state.selected(TableKind::Containers),
+                HashSet::from([ids["shop-web-1"].clone()])
+            );
+            assert_eq!((app_state.containers)().len(), 2);
+            assert_eq!((app_state.activity)().failure_count(), 1);
+        });
     }
 }
@@ -0,0 +1,194 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use dioxus::prelude::*;
+
+use super::{AppState, EngineCall};
+use crate::services::{DockerBackend, FileEntry};
+use crate::utils::TableKind;
+
+/// The directory currently shown by a file browser; `target` is the volume name
+/// or container id being browsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileBrowser {
+    pub target: String,
+    pub path: String,
+    pub entries: Vec<FileEntry>,
+    pub is_loading: bool,
+}
+
+impl FileBrowser {
+    /// Whether a listing of `path` in `target` is still the one wanted. A slow
+    /// listing that lands after the user moved on must not replace the newer one.
+    fn awaits(browser: &Option<Self>, target: &str, path: &str) -> bool {
+        browser
+            .as_ref()
+            .is_some_and(|browser| browser.target == target && browser.path == path)
+    }
+}
+
+impl AppState {
+    pub fn browse_volume(&self, volume: String, path: String) {
+        let call = EngineCall::new(
+            format!("{}:{}", volume, path),
+            "Browse",
+            "Failed to browse volume",
+        );
+        let (listed, dir) = (volume.clone(), path.clone());
+        self.browse(
+            self.volume_browser,
+            volume,
+            path,
+            call,
+            |service| async move { service.list_volume_files(&listed, &dir).await },
+        );
+    }
+
+    pub fn close_volume_browser(&self) {
+        let mut volume_browser = self.volume_browser;
+        volume_browser.set(None);
+    }
+
+    pub fn download_volume_path(&self, volume: String, path: String, destination: PathBuf) {
+        self.run(
+            EngineCall::new(
+                format!("{}:{} to {}", volume, path, destination.display()),
+                "Download",
+                "Failed to download from volume",
+            ),
+            |service| async move {
+                service
+                    .download_volume_path(&volume, &path, &destination)
+                    .await
+            },
+            |_| {},
+        );
+    }
+
+    pub fn upload_to_volume(&self, volume: String, dir: String, source: PathBuf) {
+        let app_state = self.clone();
+        let (uploaded, into) = (volume.clone(), dir.clone());
+        self.run(
+            EngineCall::new(
+                format!("{} to {}:{}", source.display(), volume, dir),
+                "Upload",
+                "Failed to upload to volume",
+            ),
+            |service| async move { service.upload_to_volume(&volume, &dir, vec![source]).await },
+            // Refresh the listing so the new file shows up
+            move |_| app_state.browse_volume(uploaded, into),
+        );
+    }
+
+    pub fn browse_container(&self, id: String, path: String) {
+        let call = EngineCall::new(
+            format!("{}:{}", self.item_label(TableKind::Containers, &id), path),
+            "Browse",
+            "Failed to browse container",
+        );
+        let (listed, dir) = (id.clone(), path.clone());
+        self.browse(
+            self.container_browser,
+            id,
+            path,
+            call,
+            |service| async move { service.list_container_files(&listed, &dir).await },
+        );
+    }
+
+    pub fn download_container_path(&self, id: String, path: String, destination: PathBuf) {
+        self.run(
+            EngineCall::new(
+                format!(
+                    "{}:{} to {}",
+                    self.item_label(TableKind::Containers, &id),
+                    path,
+                    destination.display()
+                ),
+                "Download",
+                "Failed to download from container",
+            ),
+            |service| async move {
+                service
+                    .download_container_path(&id, &path, &destination)
+                    .await
+            },
+            |_| {},
+        );
+    }
+
+    /// Copies `sources` into `dir` inside the container, like `docker cp`.
+    pub fn upload_to_container(&self, id: String, dir: String, sources: Vec<PathBuf>) {
+        let app_state = self.clone();
+        let (uploaded, into) = (id.clone(), dir.clone());
+        self.run(
+            EngineCall::new(
+                format!(
+                    "{} files to {}:{}",
+                    sources.len(),
+                    self.item_label(TableKind::Containers, &id),
+                    dir
+                ),
+                "Upload",
+                "Failed to upload to container",
+            ),
+            |service| async move { service.upload_to_container(&id, &dir, sources).await },
+            // Refresh the listing so the new files show up
+            move |_| app_state.browse_container(uploaded, into),
+        );
+    }
+
+    /// Lists `path` of `target` into `browser`. The previous entries stay on
+    /// screen while it loads, and a listing that lands after the user moved on
+    /// is dropped.
+    fn browse<F>(
+        &self,
+        mut browser: Signal<Option<FileBrowser>>,
+        target: String,
+        path: String,
+        call: EngineCall,
+        task: impl FnOnce(Arc<dyn DockerBackend>) -> F,
+    ) where
+        F: Future<Output = Result<Vec<FileEntry>>> + 'static,
+    {
+        let (failed_target, failed_path) = (target.clone(), path.clone());
+        let call = call.on_failure(move |_| {
+            if FileBrowser::awaits(&browser.peek(), &failed_target, &failed_path)
+                && let Some(browser) = browser.write().as_mut()
+            {
+                browser.is_loading = false;
+            }
+        });
+        let (loading_target, loading_path) = (target.clone(), path.clone());
+        self.run(
+            call,
+            move |service| {
+                // Peek so an effect calling this does not re-run when the listing lands
+                let previous = browser
+                    .peek()
+                    .clone()
+                    .filter(|browser| browser.target == loading_target)
+                    .map(|browser| browser.entries)
+                    .unwrap_or_default();
+                browser.set(Some(FileBrowser {
+                    target: loading_target,
+                    path: loading_path,
+                    entries: previous,
+                    is_loading: true,
+                }));
+                task(service)
+            },
+            move |entries| {
+                if FileBrowser::awaits(&browser.peek(), &target, &path) {
+                    browser.set(Some(FileBrowser {
+                        target,
+                        path,
+                        entries,
+                        is_loading: false,
+                    }));
+                }
+            },
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use dioxus::prelude::*;
+use futures_util::StreamExt;
+
+use super::AppState;
+use super::compose::ServiceProgress;
+use crate::services::{DockerError, mark_in_use};
+use crate::utils::confirmation::{Confirmation, is_protected};
+use crate::utils::{ErrorReport, TableKind};
+
+/// How many items of a bulk action run against the engine at once.
+const BULK_CONCURRENCY: usize = 4;
+
+/// An operation applied to every selected row of a table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BulkAction {
+    Start,
+    Stop,
+    Restart,
+    Remove,
+}
+
+impl BulkAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BulkAction::Start => "Start",
+            BulkAction::Stop => "Stop",
+            BulkAction::Restart => "Restart",
+            BulkAction::Remove => "Remove",
+        }
+    }
+
+    fn past_tense(&self) -> &'static str {
+        match self {
+            BulkAction::Start => "Started",
+            BulkAction::Stop => "Stopped",
+            BulkAction::Restart => "Restarted",
+            BulkAction::Remove => "Removed",
+        }
+    }
+
+    /// Images and volumes can only be removed.
+    pub fn for_table(table: TableKind) -> &'static [BulkAction] {
+        match table {
+            TableKind::Containers => &[
+                BulkAction::Start,
+                BulkAction::Stop,
+                BulkAction::Restart,
+                BulkAction::Remove,
+            ],
+            TableKind::Images | TableKind::Volumes => &[BulkAction::Remove],
+        }
+    }
+}
+
+/// The most recent bulk action and how each of its items went.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BulkRun {
+    pub table: TableKind,
+    pub action: BulkAction,
+    pub items: Vec<BulkItem>,
+    pub is_running: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BulkItem {
+    pub id: String,
+    pub label: String,
+    pub progress: ServiceProgress,
+}
+
+impl BulkRun {
+    pub fn failed(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| matches!(item.progress, ServiceProgress::Failed(_)))
+            .count()
+    }
+
+    pub fn finished(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| {
+                matches!(
+                    item.progress,
+                    ServiceProgress::Done(_) | ServiceProgress::Failed(_)
+                )
+            })
+            .count()
+    }
+}
+
+impl AppState {
+    pub fn selected(&self, table: TableKind) -> HashSet<String> {
+        (self.selection)().get(&table).cloned().unwrap_or_default()
+    }
+
+    pub fn toggle_selected(&self, table: TableKind, id: String) {
+        let mut selection = self.selection;
+        let mut selection = selection.write();
+        let selected = selection.entry(table).or_default();
+        if !selected.remove(&id) {
+            selected.insert(id);
+        }
+    }
+
+    pub fn set_selected(&self, table: TableKind, ids: HashSet<String>) {
+        let mut selection = self.selection;
+        selection.write().insert(table, ids);
+    }
+
+    /// The selected rows of `table` its current query shows, sorted. Bulk actions
+    /// only touch these, so rows selected before the filter changed are left alone.
+    pub fn bulk_targets(&self, table: TableKind) -> Vec<String> {
+        let query = self.query(table).peek().clone();
+        let shown: HashSet<String> = match table {
+            TableKind::Containers => query
+                .apply(&self.containers.peek())
+                .into_iter()
+                .map(|container| container.id)
+                .collect(),
+            TableKind::Images => query
+                .apply(&self.images.peek())
+                .into_iter()
+                .map(|image| image.id)
+                .collect(),
+            TableKind::Volumes => query
+                .apply(&mark_in_use(
+                    self.volumes.peek().clone(),
+                    &self.containers.peek(),
+                ))
+                .into_iter()
+                .map(|volume| volume.name)
+                .collect(),
+        };
+        let mut ids: Vec<String> = self
+            .selection
+            .peek()
+            .get(&table)
+            .into_iter()
+            .flatten()
+            .filter(|id| shown.contains(*id))
+            .cloned()
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Containers among `ids` that safe mode keeps `action` away from.
+    fn protected_ids(&self, table: TableKind, action: BulkAction, ids: &[String]) -> Vec<String> {
+        if table != TableKind::Containers || action != BulkAction::Remove || !*self.safe_mode.peek()
+        {
+            return Vec::new();
+        }
+        self.containers
+            .peek()
+            .iter()
+            .filter(|container| ids.contains(&container.id) && is_protected(&container.labels))
+            .map(|container| container.id.clone())
+            .collect()
+    }
+
+    /// Runs `action` on the shown selection of `table`, asking first when it
+    /// removes anything.
+    pub fn request_bulk(&self, table: TableKind, action: BulkAction) {
+        if action != BulkAction::Remove {
+            self.run_bulk(table, action);
+            return;
+        }
+        let ids = self.bulk_targets(table);
+        if ids.is_empty() {
+            return;
+        }
+        let protected = self.protected_ids(table, action, &ids);
+        let (blocked, allowed): (Vec<String>, Vec<String>) =
+            ids.into_iter().partition(|id| protected.contains(id));
+        let label = |ids: Vec<String>| -> Vec<String> {
+            ids.iter().map(|id| self.item_label(table, id)).collect()
+        };
+
+        let app_state = self.clone();
+        let confirmation = Confirmation::new(
+            format!("Remove {} {}", allowed.len(), table.noun()),
+            "This cannot be undone.",
+            label(allowed),
+            move || app_state.run_bulk(table, action),
+        )
+        .with_blocked(label(blocked))
+        .typed_in_safe_mode(*self.safe_mode.peek(), table.noun());
+        self.ask(confirmation);
+    }
+
+    /// Applies `action` to every shown, selected row of `table`, a few at a time,
+    /// and leaves the rows that failed selected so the action can be retried on them.
+    pub fn run_bulk(&self, table: TableKind, action: BulkAction) {
+        let Some(service) = &self.docker_service else {
+            return;
+        };
+        if self.bulk.peek().as_ref().is_some_and(|run| run.is_running) {
+            return;
+        }
+        let mut ids = self.bulk_targets(table);
+        let protected = self.protected_ids(table, action, &ids);
+        ids.retain(|id| !protected.contains(id));
+        if ids.is_empty() {
+            return;
+        }
+        let image_references: HashMap<String, Vec<String>> = self
+            .images
+            .peek()
+            .iter()
+            .map(|image| (image.id.clone(), image.removal_references()))
+            .collect();
+
+        let service = service.clone();
+        let mut bulk = self.bulk;
+        let mut selection = self.selection;
+        let app_state = self.clone();
+        let started = Instant::now();
+        let stops = table == TableKind::Containers
+            && matches!(action, BulkAction::Stop | BulkAction::Restart);
+        if stops {
+            self.begin_own_stops(&ids);
+        }
+
+        bulk.set(Some(BulkRun {
+            table,
+            action,
+            items: ids
+                .iter()
+                .map(|id| BulkItem {
+                    id: id.clone(),
+                    label: self.item_label(table, id),
+                    progress: ServiceProgress::Pending,
+                })
+                .collect(),
+            is_running: true,
+        }));
+
+        spawn(async move {
+            let mut set_progress = move |id: &str, progress: ServiceProgress| {
+                if let Some(run) = bulk.write().as_mut()
+                    && let Some(item) = run.items.iter_mut().find(|item| item.id == id)
+                {
+                    item.progress = progress;
+                }
+            };
+
+            let targets = ids.clone();
+            let total = ids.len();
+            let mut results = futures_util::stream::iter(ids)
+                .map(move |id| {
+                    let service = service.clone();
+                    let references = image_references
+                        .get(&id)
+                        .cloned()
+                        .unwrap_or_else(|| vec![id.clone()]);
+                    async move {
+                        set_progress(&id, ServiceProgress::Working);
+                        let result = match (table, action) {
+                            (TableKind::Containers, BulkAction::Start) => {
+                                service.start_container(&id).await
+                            }
+                            (TableKind::Containers, BulkAction::Stop) => {
+                                service.stop_container(&id).await
+                            }
+                            (TableKind::Containers, BulkAction::Restart) => {
+                                service.restart_container(&id).await
+                            }
+                            (TableKind::Containers, BulkAction::Remove) => {
+                                service.remove_container(&id).await
+                            }
+                            (TableKind::Images, _) => {
+                                let mut result = Ok(());
+                                for reference in &references {
+                                    result = service.remove_image(reference).await;
+                                    if result.is_err() {
+                                        break;
+                                    }
+                                }
+                                result
+                            }
+                            (TableKind::Volumes, _) => service.remove_volume(&id).await,
+                        };
+                        (id, result)
+                    }
+                })
+                .buffer_unordered(BULK_CONCURRENCY);
+
+            let mut failures = Vec::new();
+            let mut succeeded = Vec::new();
+            while let Some((id, result)) = results.next().await {
+                match result {
+                    Ok(()) => {
+                        set_progress(&id, ServiceProgress::Done(action.past_tense()));
+                        succeeded.push(id);
+                    }
+                    Err(e) => {
+                        set_progress(
+                            &id,
+                            ServiceProgress::Failed(DockerError::from(&e).to_string()),
+                        );
+                        failures.push((id, e));
+                    }
+                }
+            }
+
+            if let Some(run) = bulk.write().as_mut() {
+                run.is_running = false;
+            }
+            if stops {
+                app_state.end_own_stops(&targets);
+            }
+            if let Some(selected) = selection.write().get_mut(&table) {
+                selected.retain(|id| !succeeded.contains(id));
+            }
+
+            let resource = format!("{} {}", total, table.noun());
+            if failures.is_empty() {
+                app_state.record_success(resource, action.label(), started);
+            } else {
+                let retry_state = app_state.clone();
+                app_state.record_failure(
+                    resource,
+                    action.label(),
+                    started,
+                    ErrorReport::new(
+                        format!(
+                            "Failed to {} {} of {} {}",
+                            action.label().to_lowercase(),
+                            failures.len(),
+                            total,
+                            table.noun()
+                        ),
+                        &failures[0].1,
+                    )
+                    .with_retry(move || retry_state.run_bulk(table, action)),
+                );
+            }
+
+            match table {
+                TableKind::Containers => app_state.refresh_containers(),
+                TableKind::Images => app_state.refresh_images(),
+                TableKind::Volumes => app_state.refresh_volumes(),
+            }
+            app_state.refresh_disk_usage();
+        });
+    }
+
+    pub fn close_bulk_results(&self) {
+        let mut bulk = self.bulk;
+        bulk.set(None);
+    }
+}
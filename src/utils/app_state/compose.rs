@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use dioxus::prelude::*;
+
+use super::AppState;
+use crate::services::{ComposeProject, ContainerState};
+use crate::utils::ErrorReport;
+use crate::utils::confirmation::{Confirmation, is_protected};
+
+/// An operation applied to every container of a Compose project at once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl ProjectAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProjectAction::Start => "Start",
+            ProjectAction::Stop => "Stop",
+            ProjectAction::Restart => "Restart",
+        }
+    }
+
+    /// Starting a running container or stopping a stopped one is a no-op, so skip them.
+    fn applies_to(&self, state: ContainerState) -> bool {
+        match self {
+            ProjectAction::Start => state == ContainerState::Stopped,
+            ProjectAction::Stop => state == ContainerState::Running,
+            ProjectAction::Restart => true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComposeCommand {
+    Up,
+    Down,
+    Restart,
+}
+
+impl ComposeCommand {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ComposeCommand::Up => "Up",
+            ComposeCommand::Down => "Down",
+            ComposeCommand::Restart => "Restart",
+        }
+    }
+}
+
+/// Where a service is in the current `ComposeCommand` run.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServiceProgress {
+    Idle,
+    Pending,
+    Working,
+    Done(&'static str),
+    Failed(String),
+}
+
+impl ServiceProgress {
+    pub fn label(&self) -> String {
+        match self {
+            ServiceProgress::Idle => "--".to_string(),
+            ServiceProgress::Pending => "Waiting".to_string(),
+            ServiceProgress::Working => "Working…".to_string(),
+            ServiceProgress::Done(message) => message.to_string(),
+            ServiceProgress::Failed(error) => format!("Failed: {}", error),
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            ServiceProgress::Idle | ServiceProgress::Pending => "idle",
+            ServiceProgress::Working => "working",
+            ServiceProgress::Done(_) => "running",
+            ServiceProgress::Failed(_) => "stopped",
+        }
+    }
+}
+
+/// A Compose file opened in the app together with per-service progress.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComposeSession {
+    pub project: ComposeProject,
+    pub progress: HashMap<String, ServiceProgress>,
+    pub is_running: bool,
+}
+
+impl ComposeSession {
+    pub fn progress_for(&self, service: &str) -> ServiceProgress {
+        self.progress
+            .get(service)
+            .cloned()
+            .unwrap_or(ServiceProgress::Idle)
+    }
+}
+
+impl AppState {
+    pub fn apply_project_action(&self, project: String, action: ProjectAction) {
+        if let Some(service) = &self.docker_service {
+            let service = service.clone();
+            let app_state = self.clone();
+            let started = Instant::now();
+
+            let targets: Vec<String> = (self.containers)()
+                .into_iter()
+                .filter(|container| container.compose_project.as_deref() == Some(project.as_str()))
+                .filter(|container| action.applies_to(container.state))
+                .map(|container| container.id)
+                .collect();
+
+            if action != ProjectAction::Start {
+                self.begin_own_stops(&targets);
+            }
+            spawn(async move {
+                let mut failures = Vec::new();
+                for id in &targets {
+                    let result = match action {
+                        ProjectAction::Start => service.start_container(id).await,
+                        ProjectAction::Stop => service.stop_container(id).await,
+                        ProjectAction::Restart => service.restart_container(id).await,
+                    };
+                    if let Err(e) = result {
+                        failures.push((id.clone(), e));
+                    }
+                }
+                app_state.end_own_stops(&targets);
+
+                let resource = format!("project {} ({} containers)", project, targets.len());
+                if failures.is_empty() {
+                    app_state.record_success(resource, action.label(), started);
+                } else {
+                    let failed: Vec<&str> = failures.iter().map(|(id, _)| id.as_str()).collect();
+                    let retry_state = app_state.clone();
+                    let retry_project = project.clone();
+                    // Retrying only touches containers still not in the wanted state
+                    app_state.record_failure(
+                        resource,
+                        action.label(),
+                        started,
+                        ErrorReport::new(
+                            format!(
+                                "Failed to {} project {} ({})",
+                                action.label().to_lowercase(),
+                                project,
+                                failed.join(", ")
+                            ),
+                            &failures[0].1,
+                        )
+                        .with_retry(move || {
+                            retry_state.apply_project_action(retry_project.clone(), action)
+                        }),
+                    );
+                }
+                app_state.refresh_containers();
+            });
+        }
+    }
+
+    pub fn load_compose_file(&self, path: PathBuf) {
+        let mut compose = self.compose;
+        let started = Instant::now();
+        let resource = format!("compose file {}", path.display());
+
+        match ComposeProject::load(&path) {
+            Ok(project) => {
+                self.record_success(resource, "Open", started);
+                compose.set(Some(ComposeSession {
+                    project,
+                    progress: HashMap::new(),
+                    is_running: false,
+                }));
+            }
+            Err(e) => self.record_failure(
+                resource,
+                "Open",
+                started,
+                ErrorReport::new(format!("Failed to read {}", path.display()), &e),
+            ),
+        }
+    }
+
+    pub fn close_compose_file(&self) {
+        let mut compose = self.compose;
+        compose.set(None);
+    }
+
+    /// `Down` removes the project's containers, so it goes through the
+    /// confirmation modal; safe mode refuses it while any of them is protected.
+    pub fn request_compose(&self, command: ComposeCommand) {
+        if command != ComposeCommand::Down {
+            self.run_compose(command);
+            return;
+        }
+        let Some(session) = self.compose.peek().clone() else {
+            return;
+        };
+        let project = session.project.name;
+        let services: Vec<String> = session
+            .project
+            .services
+            .iter()
+            .map(|definition| definition.name.clone())
+            .collect();
+        let blocked: Vec<String> = if *self.safe_mode.peek() {
+            self.containers
+                .peek()
+                .iter()
+                .filter(|container| container.compose_project.as_deref() == Some(&project))
+                .filter(|container| is_protected(&container.labels))
+                .map(|container| container.name.clone())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let app_state = self.clone();
+        let confirmation = Confirmation::new(
+            format!("Compose down {}", project),
+            "Stops and removes every service container and the project's networks.",
+            if blocked.is_empty() {
+                services
+            } else {
+                Vec::new()
+            },
+            move || app_state.run_compose(command),
+        )
+        .with_blocked(blocked)
+        .typed_in_safe_mode(*self.safe_mode.peek(), "services");
+        self.ask(confirmation);
+    }
+
+    /// Runs `command` over the open project one service at a time, in dependency
+    /// order (reversed for `Down`), stopping at the first failure.
+    pub fn run_compose(&self, command: ComposeCommand) {
+        let Some(service) = &self.docker_service else {
+            return;
+        };
+        let Some(session) = (self.compose)() else {
+            return;
+        };
+        if session.is_running {
+            return;
+        }
+
+        let service = service.clone();
+        let mut compose = self.compose;
+        let app_state = self.clone();
+        let started = Instant::now();
+        let project = session.project;
+
+        let mut services = project.services.clone();
+        if command == ComposeCommand::Down {
+            services.reverse();
+        }
+
+        if let Some(session) = compose.write().as_mut() {
+            session.is_running = true;
+            session.progress = services
+                .iter()
+                .map(|definition| (definition.name.clone(), ServiceProgress::Pending))
+                .collect();
+        }
+
+        spawn(async move {
+            let mut set_progress = move |name: &str, progress: ServiceProgress| {
+                if let Some(session) = compose.write().as_mut() {
+                    session.progress.insert(name.to_string(), progress);
+                }
+            };
+
+            let mut failure = None;
+            if command == ComposeCommand::Up
+                && let Err(e) = service.compose_prepare(&project).await
+            {
+                failure = Some(("failed to create networks and volumes".to_string(), e));
+            }
+
+            for definition in services.iter() {
+                if failure.is_some() {
+                    break;
+                }
+                set_progress(&definition.name, ServiceProgress::Working);
+                let result = match command {
+                    ComposeCommand::Up => service.compose_up_service(&project, definition).await,
+                    ComposeCommand::Down => service.compose_down_service(definition).await,
+                    ComposeCommand::Restart => service.compose_restart_service(definition).await,
+                };
+                match result {
+                    Ok(message) => set_progress(&definition.name, ServiceProgress::Done(message)),
+                    Err(e) => {
+                        set_progress(&definition.name, ServiceProgress::Failed(e.to_string()));
+                        failure = Some((format!("service {} failed", definition.name), e));
+                    }
+                }
+            }
+
+            if failure.is_none()
+                && command == ComposeCommand::Down
+                && let Err(e) = service.compose_teardown(&project).await
+            {
+                failure = Some(("failed to remove networks".to_string(), e));
+            }
+
+            let resource = format!("compose project {}", project.name);
+            let operation = format!("Compose {}", command.label().to_lowercase());
+            match failure {
+                None => app_state.record_success(resource, operation, started),
+                Some((reason, e)) => {
+                    let retry_state = app_state.clone();
+                    app_state.record_failure(
+                        resource,
+                        operation,
+                        started,
+                        ErrorReport::new(
+                            format!(
+                                "Compose {} for {} stopped: {}",
+                                command.label().to_lowercase(),
+                                project.name,
+                                reason
+                            ),
+                            &e,
+                        )
+                        .with_retry(move || retry_state.run_compose(command)),
+                    );
+                }
+            }
+
+            if let Some(session) = compose.write().as_mut() {
+                session.is_running = false;
+            }
+            app_state.refresh_all();
+        });
+    }
+}
@@ -0,0 +1,328 @@
+use std::path::PathBuf;
+use std::time::Instant;
+
+use dioxus::prelude::*;
+
+use super::{AppState, EngineCall};
+use crate::services::{CommitSpec, ContainerSpec, ContainerState, PathChange, ProcessList};
+use crate::utils::{ErrorReport, TableKind};
+
+/// The writable-layer changes of the container on the detail page.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContainerChanges {
+    pub id: String,
+    pub changes: Vec<PathChange>,
+    pub is_loading: bool,
+}
+
+/// `ps` output for the container on the detail page.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContainerProcesses {
+    pub id: String,
+    pub ps_args: String,
+    pub list: Option<ProcessList>,
+    pub is_loading: bool,
+}
+
+impl AppState {
+    pub fn load_container_changes(&self, id: String) {
+        let mut container_changes = self.container_changes;
+        let loaded = id.clone();
+        self.run(
+            EngineCall::new(
+                format!("container {}", self.item_label(TableKind::Containers, &id)),
+                "Diff",
+                "Failed to list container changes",
+            )
+            .quiet()
+            .on_failure(move |_| {
+                if let Some(changes) = container_changes.write().as_mut() {
+                    changes.is_loading = false;
+                }
+            }),
+            move |service| {
+                let previous = container_changes
+                    .peek()
+                    .clone()
+                    .filter(|changes| changes.id == id)
+                    .map(|changes| changes.changes)
+                    .unwrap_or_default();
+                container_changes.set(Some(ContainerChanges {
+                    id: id.clone(),
+                    changes: previous,
+                    is_loading: true,
+                }));
+                async move { service.container_changes(&id).await }
+            },
+            move |changes| {
+                container_changes.set(Some(ContainerChanges {
+                    id: loaded,
+                    changes,
+                    is_loading: false,
+                }))
+            },
+        );
+    }
+
+    /// Runs `ps` with `ps_args` in the container. The previous listing stays on
+    /// screen while the next one loads, so periodic refreshes do not flicker.
+    pub fn load_processes(&self, id: String, ps_args: String) {
+        let mut container_processes = self.container_processes;
+        let (loaded, loaded_args) = (id.clone(), ps_args.clone());
+        self.run(
+            EngineCall::new(
+                format!("container {}", self.item_label(TableKind::Containers, &id)),
+                "Top",
+                "Failed to list container processes",
+            )
+            .quiet()
+            .on_failure(move |_| {
+                if let Some(processes) = container_processes.write().as_mut() {
+                    processes.is_loading = false;
+                }
+            }),
+            move |service| {
+                let previous = container_processes
+                    .peek()
+                    .clone()
+                    .filter(|processes| processes.id == id)
+                    .and_then(|processes| processes.list);
+                container_processes.set(Some(ContainerProcesses {
+                    id: id.clone(),
+                    ps_args: ps_args.clone(),
+                    list: previous,
+                    is_loading: true,
+                }));
+                async move { service.top_processes(&id, &ps_args).await }
+            },
+            move |list| {
+                container_processes.set(Some(ContainerProcesses {
+                    id: loaded,
+                    ps_args: loaded_args,
+                    list: Some(list),
+                    is_loading: false,
+                }))
+            },
+        );
+    }
+
+    pub fn load_health(&self, id: String) {
+        let mut container_health = self.container_health;
+        let loaded = id.clone();
+        self.run(
+            EngineCall::new(
+                format!("container {}", self.item_label(TableKind::Containers, &id)),
+                "Inspect",
+                "Failed to read container health",
+            )
+            .quiet(),
+            |service| async move { service.container_health(&id).await },
+            move |health| container_health.set(Some((loaded, health))),
+        );
+    }
+
+    pub fn new_container(&self) {
+        self.open_container_draft(ContainerSpec::default());
+    }
+
+    fn open_container_draft(&self, spec: ContainerSpec) {
+        let mut container_draft = self.container_draft;
+        let generation = container_draft
+            .peek()
+            .as_ref()
+            .map_or(0, |(generation, _)| generation + 1);
+        container_draft.set(Some((generation, spec)));
+    }
+
+    /// Opens the create-container form filled in from `id`'s configuration.
+    pub fn duplicate_container(&self, id: String) {
+        let app_state = self.clone();
+        self.run(
+            EngineCall::new(
+                format!("container {}", self.item_label(TableKind::Containers, &id)),
+                "Duplicate",
+                "Failed to read container configuration",
+            )
+            .quiet(),
+            |service| async move { service.container_spec(&id).await },
+            move |spec| app_state.open_container_draft(spec.duplicate()),
+        );
+    }
+
+    pub fn load_container_spec(&self, id: String) {
+        let mut container_spec = self.container_spec;
+        let loaded = id.clone();
+        self.run(
+            EngineCall::new(
+                format!("container {}", self.item_label(TableKind::Containers, &id)),
+                "Inspect",
+                "Failed to read container configuration",
+            )
+            .quiet(),
+            |service| async move { service.container_spec(&id).await },
+            move |spec| container_spec.set(Some((loaded, spec))),
+        );
+    }
+
+    pub fn close_container_draft(&self) {
+        let mut container_draft = self.container_draft;
+        container_draft.set(None);
+    }
+
+    /// Creates a container from the form; the form stays open when creating
+    /// fails so the spec can be fixed and submitted again. A container that was
+    /// created but failed to start exists either way, so the form closes.
+    pub fn create_container(&self, spec: ContainerSpec, start: bool) {
+        if let Some(service) = &self.docker_service {
+            let service = service.clone();
+            let mut container_draft = self.container_draft;
+            let app_state = self.clone();
+            let started = Instant::now();
+
+            spawn(async move {
+                let resource = if spec.name.is_empty() {
+                    format!("container from {}", spec.image)
+                } else {
+                    format!("container {}", spec.name)
+                };
+                match service.create_container(&spec).await {
+                    Ok(id) => {
+                        container_draft.set(None);
+                        let result = if start {
+                            service.start_container(&id).await
+                        } else {
+                            Ok(())
+                        };
+                        match result {
+                            Ok(()) => app_state.record_success(resource, "Create", started),
+                            Err(e) => app_state.record_failure(
+                                resource,
+                                "Create",
+                                started,
+                                ErrorReport::new(
+                                    "Created the container, but failed to start it",
+                                    &e,
+                                ),
+                            ),
+                        }
+                    }
+                    Err(e) => app_state.record_failure(
+                        resource,
+                        "Create",
+                        started,
+                        ErrorReport::new("Failed to create container", &e),
+                    ),
+                }
+                app_state.refresh_containers();
+            });
+        }
+    }
+
+    pub fn rename_container(&self, id: String, name: String) {
+        let app_state = self.clone();
+        self.run(
+            EngineCall::new(
+                format!(
+                    "container {} to {}",
+                    self.item_label(TableKind::Containers, &id),
+                    name
+                ),
+                "Rename",
+                "Failed to rename container",
+            ),
+            |service| async move { service.rename_container(&id, &name).await },
+            move |_| app_state.refresh_containers(),
+        );
+    }
+
+    /// Snapshots the container as a new image, then reloads the image list.
+    pub fn commit_container(&self, id: String, spec: CommitSpec) {
+        let app_state = self.clone();
+        self.run(
+            EngineCall::new(
+                format!(
+                    "{} as {}:{}",
+                    self.item_label(TableKind::Containers, &id),
+                    spec.repo,
+                    if spec.tag.is_empty() {
+                        "latest"
+                    } else {
+                        &spec.tag
+                    }
+                ),
+                "Commit",
+                "Failed to commit container",
+            ),
+            |service| async move { service.commit_container(&id, &spec).await },
+            move |_| {
+                app_state.refresh_images();
+                app_state.refresh_disk_usage();
+            },
+        );
+    }
+
+    pub fn export_container(&self, id: String, destination: PathBuf) {
+        self.run(
+            EngineCall::new(
+                format!(
+                    "{} to {}",
+                    self.item_label(TableKind::Containers, &id),
+                    destination.display()
+                ),
+                "Export",
+                "Failed to export container filesystem",
+            ),
+            |service| async move { service.export_container(&id, &destination).await },
+            |_| {},
+        );
+    }
+
+    pub fn start_container(&self, id: String) {
+        let retry_state = self.clone();
+        let retry_id = id.clone();
+        let app_state = self.clone();
+        self.run(
+            EngineCall::new(
+                format!("container {}", id),
+                "Start",
+                format!("Failed to start container {}", id),
+            )
+            .with_retry(move || retry_state.start_container(retry_id.clone())),
+            |service| async move { service.start_container(&id).await },
+            // Refresh containers to get updated state
+            move |_| app_state.refresh_containers(),
+        );
+    }
+
+    pub fn stop_container(&self, id: String) {
+        let retry_state = self.clone();
+        let retry_id = id.clone();
+        let app_state = self.clone();
+        self.run(
+            EngineCall::new(
+                format!("container {}", id),
+                "Stop",
+                format!("Failed to stop container {}", id),
+            )
+            .with_retry(move || retry_state.stop_container(retry_id.clone())),
+            |service| {
+                let app_state = self.clone();
+                self.begin_own_stops(std::slice::from_ref(&id));
+                async move {
+                    let result = service.stop_container(&id).await;
+                    app_state.end_own_stops(std::slice::from_ref(&id));
+                    result
+                }
+            },
+            // Refresh containers to get updated state
+            move |_| app_state.refresh_containers(),
+        );
+    }
+
+    pub fn set_container_state(&self, id: &str, next_state: ContainerState) {
+        match next_state {
+            ContainerState::Running => self.start_container(id.to_string()),
+            ContainerState::Stopped => self.stop_container(id.to_string()),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use dioxus::prelude::*;
+
+use super::{AppState, EngineCall};
+use crate::services::ContainerLimits;
+use crate::utils::TableKind;
+
+impl AppState {
+    pub fn load_limits(&self, id: String) {
+        let mut container_limits = self.container_limits;
+        let loaded = id.clone();
+        self.run(
+            EngineCall::new(
+                format!("container {}", self.item_label(TableKind::Containers, &id)),
+                "Inspect",
+                "Failed to read container limits",
+            )
+            .quiet(),
+            |service| async move { service.container_limits(&id).await },
+            move |limits| container_limits.set(Some((loaded, limits))),
+        );
+    }
+
+    /// Changes limits on the live container from `current` to `limits`, like
+    /// `docker update`, then reads them back so the form shows what the engine applied.
+    pub fn update_limits(&self, id: String, current: ContainerLimits, limits: ContainerLimits) {
+        let retry_state = self.clone();
+        let (retry_id, retry_current, retry_limits) = (id.clone(), current.clone(), limits.clone());
+        let (failed_state, failed_id) = (self.clone(), id.clone());
+        let (app_state, updated) = (self.clone(), id.clone());
+        self.run(
+            EngineCall::new(
+                format!("limits of {}", self.item_label(TableKind::Containers, &id)),
+                "Update",
+                "Failed to update container limits",
+            )
+            .with_retry(move || {
+                retry_state.update_limits(
+                    retry_id.clone(),
+                    retry_current.clone(),
+                    retry_limits.clone(),
+                )
+            })
+            .on_failure(move |_| failed_state.load_limits(failed_id)),
+            |service| async move { service.update_limits(&id, &current, &limits).await },
+            move |_| app_state.load_limits(updated),
+        );
+    }
+}
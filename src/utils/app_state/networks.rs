@@ -0,0 +1,126 @@
+use dioxus::prelude::*;
+
+use super::{AppState, EngineCall};
+use crate::services::NetworkSpec;
+use crate::utils::confirmation::Confirmation;
+
+impl AppState {
+    pub fn inspect_network(&self, id: String) {
+        let mut selected_network = self.selected_network;
+        self.run(
+            EngineCall::new(
+                format!("network {}", id),
+                "Inspect",
+                "Failed to inspect network",
+            )
+            .quiet(),
+            |service| async move { service.inspect_network(&id).await },
+            move |details| selected_network.set(Some(details)),
+        );
+    }
+
+    pub fn close_network_details(&self) {
+        let mut selected_network = self.selected_network;
+        selected_network.set(None);
+    }
+
+    pub fn refresh_topology(&self) {
+        let mut topology = self.topology;
+        let retry_state = self.clone();
+        self.run(
+            EngineCall::new(
+                "network topology",
+                "Load",
+                "Failed to load network topology",
+            )
+            .quiet()
+            .with_retry(move || retry_state.refresh_topology()),
+            |service| async move { service.network_topology().await },
+            move |data| topology.set(Some(data)),
+        );
+    }
+
+    /// Creates a network; `on_created` runs only once the engine accepted it,
+    /// so a form can keep its input when creating fails.
+    pub fn create_network(&self, spec: NetworkSpec, on_created: impl FnOnce() + 'static) {
+        let app_state = self.clone();
+        self.run(
+            EngineCall::new(
+                format!("network {}", spec.name),
+                "Create",
+                "Failed to create network",
+            ),
+            |service| async move { service.create_network(&spec).await },
+            move |_| {
+                on_created();
+                app_state.refresh_networks();
+            },
+        );
+    }
+
+    /// Asks before removing a network; in safe mode the name has to be typed.
+    pub fn request_remove_network(&self, id: String, name: String) {
+        let app_state = self.clone();
+        self.ask(
+            Confirmation::new(
+                format!("Remove network {}", name),
+                "Containers attached to it lose this connection.",
+                vec![name],
+                move || app_state.remove_network(id.clone()),
+            )
+            .typed_in_safe_mode(*self.safe_mode.peek(), "networks"),
+        );
+    }
+
+    pub fn remove_network(&self, id: String) {
+        let mut selected_network = self.selected_network;
+        let app_state = self.clone();
+        let removed = id.clone();
+        self.run(
+            EngineCall::new(
+                format!("network {}", id),
+                "Remove",
+                "Failed to remove network",
+            ),
+            |service| async move { service.remove_network(&id).await },
+            move |_| {
+                if selected_network().is_some_and(|details| details.info.id == removed) {
+                    selected_network.set(None);
+                }
+                app_state.refresh_networks();
+            },
+        );
+    }
+
+    /// Connects a container to a network; `on_connected` runs only on success.
+    pub fn connect_network(
+        &self,
+        network: String,
+        container: String,
+        aliases: Vec<String>,
+        on_connected: impl FnOnce() + 'static,
+    ) {
+        let app_state = self.clone();
+        let resource = format!("{} to network {}", container, network);
+        let connected = network.clone();
+        self.run(
+            EngineCall::new(resource, "Connect", "Failed to connect container"),
+            |service| async move { service.connect_network(&network, &container, aliases).await },
+            move |_| {
+                on_connected();
+                app_state.inspect_network(connected);
+            },
+        );
+    }
+
+    pub fn disconnect_network(&self, network: String, container: String) {
+        let app_state = self.clone();
+        let resource = format!("{} from network {}", container, network);
+        let disconnected = network.clone();
+        self.run(
+            EngineCall::new(resource, "Disconnect", "Failed to disconnect container"),
+            |service| async move { service.disconnect_network(&network, &container).await },
+            move |_| app_state.inspect_network(disconnected),
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use dioxus::prelude::*;
+
+/// Puts `text` on the system clipboard through the webview.
+pub fn copy_to_clipboard(text: &str) {
+    // A JSON string is also a valid JavaScript string literal
+    let literal = serde_json::to_string(text).unwrap_or_default();
+    let script = format!("navigator.clipboard.writeText({});", literal);
+    spawn(async move {
+        if let Err(e) = document::eval(&script).await {
+            eprintln!("Failed to copy to the clipboard: {:?}", e);
+        }
+    });
+}
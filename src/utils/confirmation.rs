@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Containers carrying this label (with any value but `false`) cannot be removed
+/// or torn down while safe mode is on.
+pub const PROTECTED_LABEL: &str = "doctainr.protected";
+
+pub fn is_protected(labels: &HashMap<String, String>) -> bool {
+    labels
+        .get(PROTECTED_LABEL)
+        .is_some_and(|value| value != "false")
+}
+
+/// A destructive operation waiting for the user to confirm it in the modal.
+#[derive(Clone)]
+pub struct Confirmation {
+    pub title: String,
+    pub message: String,
+    /// What the operation will touch, listed in the modal.
+    pub items: Vec<String>,
+    /// Items left out because they are protected.
+    pub blocked: Vec<String>,
+    /// Text the user has to type before confirming; set in safe mode.
+    pub typed: Option<String>,
+    on_confirm: Rc<dyn Fn()>,
+}
+
+impl Confirmation {
+    pub fn new(
+        title: impl Into<String>,
+        message: impl Into<String>,
+        items: Vec<String>,
+        on_confirm: impl Fn() + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            items,
+            blocked: Vec::new(),
+            typed: None,
+            on_confirm: Rc::new(on_confirm),
+        }
+    }
+
+    pub fn with_blocked(mut self, blocked: Vec<String>) -> Self {
+        self.blocked = blocked;
+        self
+    }
+
+    /// Requires typing the only item's name, or the item count when there are several.
+    pub fn typed_in_safe_mode(mut self, safe_mode: bool, noun: &str) -> Self {
+        if safe_mode {
+            self.typed = Some(match self.items.as_slice() {
+                [item] => item.clone(),
+                items => format!("{} {}", items.len(), noun),
+            });
+        }
+        self
+    }
+
+    /// Nothing is left to do once every item is blocked.
+    pub fn can_proceed(&self, input: &str) -> bool {
+        !self.items.is_empty()
+            && self
+                .typed
+                .as_deref()
+                .is_none_or(|typed| input.trim() == typed)
+    }
+
+    pub fn confirm(&self) {
+        (self.on_confirm)();
+    }
+}
+
+impl PartialEq for Confirmation {
+    fn eq(&self, other: &Self) -> bool {
+        self.title == other.title
+            && self.message == other.message
+            && self.items == other.items
+            && self.blocked == other.blocked
+            && self.typed == other.typed
+    }
+}
+
+impl std::fmt::Debug for Confirmation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Confirmation")
+            .field("title", &self.title)
+            .field("items", &self.items)
+            .field("blocked", &self.blocked)
+            .field("typed", &self.typed)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_mode_requires_typed_text() {
+        let single = Confirmation::new("Remove", "", vec!["web".to_string()], || {})
+            .typed_in_safe_mode(true, "containers");
+        assert_eq!(single.typed.as_deref(), Some("web"));
+        assert!(!single.can_proceed(""));
+        assert!(single.can_proceed(" web "));
+
+        let several = Confirmation::new("Remove", "", vec!["a".into(), "b".into()], || {})
+            .typed_in_safe_mode(true, "containers");
+        assert_eq!(several.typed.as_deref(), Some("2 containers"));
+
+        let relaxed = Confirmation::new("Remove", "", vec!["a".into()], || {})
+            .typed_in_safe_mode(false, "containers");
+        assert!(relaxed.can_proceed(""));
+
+        let blocked = Confirmation::new("Remove", "", Vec::new(), || {});
+        assert!(!blocked.can_proceed(""));
+
+        let labels = HashMap::from([(PROTECTED_LABEL.to_string(), "true".to_string())]);
+        assert!(is_protected(&labels));
+        assert!(!is_protected(&HashMap::from([(
+            PROTECTED_LABEL.to_string(),
+            "false".to_string()
+        )])));
+    }
+}
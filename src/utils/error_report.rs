@@ -0,0 +1,58 @@
+use std::rc::Rc;
+
+use crate::services::DockerError;
+
+/// A failed operation as shown to the user: what was attempted, why it failed,
+/// and optionally how to try it again.
+#[derive(Clone)]
+pub struct ErrorReport {
+    pub action: String,
+    pub error: DockerError,
+    retry: Option<Rc<dyn Fn()>>,
+}
+
+impl ErrorReport {
+    pub fn new(action: impl Into<String>, error: &anyhow::Error) -> Self {
+        Self::from_error(action, DockerError::from(error))
+    }
+
+    pub fn from_error(action: impl Into<String>, error: DockerError) -> Self {
+        Self {
+            action: action.into(),
+            error,
+            retry: None,
+        }
+    }
+
+    /// Offers a Retry button that runs `retry`, for errors where retrying can help.
+    pub fn with_retry(mut self, retry: impl Fn() + 'static) -> Self {
+        self.retry = Some(Rc::new(retry));
+        self
+    }
+
+    pub fn can_retry(&self) -> bool {
+        self.retry.is_some() && self.error.is_retryable()
+    }
+
+    pub fn retry(&self) {
+        if let Some(retry) = &self.retry {
+            retry();
+        }
+    }
+}
+
+impl PartialEq for ErrorReport {
+    fn eq(&self, other: &Self) -> bool {
+        self.action == other.action && self.error == other.error
+    }
+}
+
+impl std::fmt::Debug for ErrorReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErrorReport")
+            .field("action", &self.action)
+            .field("error", &self.error)
+            .field("retry", &self.retry.is_some())
+            .finish()
+    }
+}
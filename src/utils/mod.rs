@@ -1,3 +1,20 @@
+mod activity;
 mod app_state;
+mod clipboard;
+mod confirmation;
+mod error_report;
+mod notifications;
+mod polling;
+mod preferences;
+mod presets;
+mod resource_status;
+mod table_query;
 
-pub use app_state::AppState;
+pub use activity::Outcome;
+pub use app_state::{AppState, BulkAction, ComposeCommand, ProjectAction};
+pub use clipboard::copy_to_clipboard;
+pub use confirmation::PROTECTED_LABEL;
+pub use error_report::ErrorReport;
+pub use polling::{POLL_INTERVALS, PollSettings};
+pub use resource_status::ResourceStatus;
+pub use table_query::{TableKind, TableQuery};
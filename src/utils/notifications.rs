@@ -0,0 +1,19 @@
+use notify_rust::Notification;
+
+use crate::services::HealthAlert;
+
+/// Shows `alert` as a desktop notification. Talking to the notification daemon
+/// can block, so it happens off the UI thread.
+pub fn notify(alert: &HealthAlert) {
+    let (title, body) = (alert.title(), alert.body());
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = Notification::new()
+            .appname("Doctainr")
+            .summary(&title)
+            .body(&body)
+            .show()
+        {
+            eprintln!("Failed to show a desktop notification: {}", e);
+        }
+    });
+}
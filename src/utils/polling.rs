@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Intervals offered in Settings, in seconds.
+pub const POLL_INTERVALS: [u64; 5] = [5, 10, 30, 60, 300];
+
+/// Backoff never waits longer than this between refreshes.
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// How the background refresher re-runs `refresh_lists`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollSettings {
+    pub enabled: bool,
+    pub interval_secs: u64,
+}
+
+impl Default for PollSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 10,
+        }
+    }
+}
+
+impl PollSettings {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    /// The wait before the next refresh after `failures` refreshes in a row found
+    /// the daemon unreachable: the interval, doubled per failure, capped at five
+    /// minutes (or the interval itself when that is longer).
+    pub fn delay(&self, failures: u32) -> Duration {
+        let backoff = self
+            .interval()
+            .saturating_mul(2u32.saturating_pow(failures));
+        backoff.min(MAX_BACKOFF.max(self.interval()))
+    }
+}
+
+/// What the background refresher is doing right now, for Settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PollState {
+    Off,
+    /// The window is hidden, so nobody would see the refreshed lists.
+    Paused,
+    /// The window is hidden, but containers are still checked for notifications.
+    Watching(Duration),
+    Waiting(Duration),
+    /// The daemon was unreachable on the last refresh, so the wait is stretched.
+    BackingOff(Duration),
+}
+
+impl PollState {
+    pub fn label(&self) -> String {
+        match self {
+            PollState::Off => "Automatic refresh is off".to_string(),
+            PollState::Paused => "Paused while the window is hidden".to_string(),
+            PollState::Watching(delay) => format!(
+                "Window hidden, checking containers every {} s for notifications",
+                delay.as_secs()
+            ),
+            PollState::Waiting(delay) => format!("Refreshing every {} s", delay.as_secs()),
+            PollState::BackingOff(delay) => {
+                format!("Engine unreachable, retrying every {} s", delay.as_secs())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_doubles_up_to_the_cap() {
+        let settings = PollSettings {
+            enabled: true,
+            interval_secs: 10,
+        };
+        assert_eq!(settings.delay(0), Duration::from_secs(10));
+        assert_eq!(settings.delay(1), Duration::from_secs(20));
+        assert_eq!(settings.delay(3), Duration::from_secs(80));
+        assert_eq!(settings.delay(40), Duration::from_secs(300));
+
+        let slow = PollSettings {
+            enabled: true,
+            interval_secs: 600,
+        };
+        assert_eq!(slow.delay(2), Duration::from_secs(600));
+    }
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::polling::PollSettings;
+
+const PREFERENCES_FILE: &str = "settings.json";
+
+/// Settings chosen on the Settings page that outlive a session.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub polling: PollSettings,
+    pub safe_mode: bool,
+    pub notifications: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            polling: PollSettings::default(),
+            safe_mode: false,
+            notifications: true,
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/doctainr/<file>`, falling back to `~/.config`.
+pub(super) fn config_path(file: &str) -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("doctainr").join(file))
+}
+
+/// Preferences saved by earlier sessions; a missing or unreadable file means defaults.
+pub fn load_preferences() -> Preferences {
+    config_path(PREFERENCES_FILE)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_preferences(preferences: &Preferences) -> Result<()> {
+    let path = config_path(PREFERENCES_FILE).context("No config directory to save settings in")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let json = serde_json::to_string_pretty(preferences)?;
+    std::fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_keep_their_defaults() {
+        let preferences: Preferences = serde_json::from_str("{}").unwrap();
+        assert_eq!(preferences, Preferences::default());
+        assert!(preferences.notifications);
+
+        let saved = Preferences {
+            polling: PollSettings {
+                enabled: false,
+                interval_secs: 60,
+            },
+            safe_mode: true,
+            notifications: false,
+        };
+        let json = serde_json::to_string(&saved).unwrap();
+        assert_eq!(serde_json::from_str::<Preferences>(&json).unwrap(), saved);
+    }
+}
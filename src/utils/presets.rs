@@ -0,0 +1,33 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::preferences::config_path;
+use super::table_query::{TableKind, TableQuery};
+
+const PRESETS_FILE: &str = "filter-presets.json";
+
+/// A named `TableQuery` the user saved for one of the tables.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FilterPreset {
+    pub table: TableKind,
+    pub name: String,
+    pub query: TableQuery,
+}
+
+/// Presets saved by earlier sessions; a missing or unreadable file means none.
+pub fn load_presets() -> Vec<FilterPreset> {
+    config_path(PRESETS_FILE)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_presets(presets: &[FilterPreset]) -> Result<()> {
+    let path = config_path(PRESETS_FILE).context("No config directory to save presets in")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let json = serde_json::to_string_pretty(presets)?;
+    std::fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,75 @@
+use chrono::{DateTime, Local};
+
+use crate::services::DockerError;
+
+/// Where one resource list stands: loading, failed, or loaded at some point.
+///
+/// Each list has its own status, so a failed image refresh never hides the
+/// containers table and a slow volume listing shows up as its own spinner.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ResourceStatus {
+    pub is_loading: bool,
+    pub error: Option<DockerError>,
+    pub last_refreshed: Option<DateTime<Local>>,
+}
+
+impl ResourceStatus {
+    pub fn begin(&mut self) {
+        self.is_loading = true;
+    }
+
+    pub fn succeed(&mut self) {
+        self.is_loading = false;
+        self.error = None;
+        self.last_refreshed = Some(Local::now());
+    }
+
+    /// Keeps `last_refreshed`, since the rows from the previous load are still shown.
+    pub fn fail(&mut self, error: DockerError) {
+        self.is_loading = false;
+        self.error = Some(error);
+    }
+
+    /// Nothing to show yet, so the view renders placeholder rows.
+    pub fn is_first_load(&self) -> bool {
+        self.is_loading && self.last_refreshed.is_none()
+    }
+
+    pub fn label(&self) -> String {
+        match (self.is_loading, self.last_refreshed) {
+            (true, _) => "Loading…".to_string(),
+            (false, Some(at)) => format!("Updated {}", at.format("%H:%M:%S")),
+            (false, None) => "Not loaded yet".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failure_keeps_previous_refresh_time() {
+        let mut status = ResourceStatus::default();
+        assert_eq!(status.label(), "Not loaded yet");
+
+        status.begin();
+        assert!(status.is_first_load());
+        assert_eq!(status.label(), "Loading…");
+
+        status.succeed();
+        let refreshed = status.last_refreshed;
+        assert!(refreshed.is_some());
+        assert!(status.label().starts_with("Updated "));
+
+        status.begin();
+        assert!(!status.is_first_load());
+        status.fail(DockerError::Timeout);
+        assert_eq!(status.error, Some(DockerError::Timeout));
+        assert_eq!(status.last_refreshed, refreshed);
+
+        status.begin();
+        status.succeed();
+        assert_eq!(status.error, None);
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::{ContainerInfo, ContainerState, ImageInfo, VolumeInfo, label_matches};
+
+/// The resource tables that share the search/sort/filter toolbar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TableKind {
+    Containers,
+    Images,
+    Volumes,
+}
+
+impl TableKind {
+    pub fn noun(&self) -> &'static str {
+        match self {
+            TableKind::Containers => "containers",
+            TableKind::Images => "images",
+            TableKind::Volumes => "volumes",
+        }
+    }
+
+    /// Sortable columns as `(key, label)`.
+    pub fn columns(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            TableKind::Containers => &[
+                ("name", "Name"),
+                ("image", "Image"),
+                ("state", "State"),
+                ("project", "Project"),
+            ],
+            TableKind::Images => &[
+                ("repository", "Repository"),
+                ("tag", "Tag"),
+                ("size", "Size"),
+                ("created", "Created"),
+            ],
+            TableKind::Volumes => &[("name", "Name"), ("driver", "Driver")],
+        }
+    }
+
+    pub fn states(&self) -> &'static [&'static str] {
+        match self {
+            TableKind::Containers => &["running", "stopped"],
+            TableKind::Images => &["dangling", "tagged"],
+            TableKind::Volumes => &["unused", "in use"],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    pub fn toggled(&self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "↑",
+            SortDirection::Descending => "↓",
+        }
+    }
+}
+
+/// What a resource table shows: a text search, an optional state and label
+/// filter, and a sort column.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableQuery {
+    pub search: String,
+    pub state: Option<String>,
+    /// `key` or `key=value`.
+    pub label: String,
+    pub sort_by: Option<String>,
+    pub direction: SortDirection,
+}
+
+impl TableQuery {
+    pub fn apply<R: TableRow + Clone>(&self, rows: &[R]) -> Vec<R> {
+        let needle = self.search.trim().to_lowercase();
+        let label = self.label.trim();
+
+        let mut rows: Vec<R> = rows
+            .iter()
+            .filter(|row| needle.is_empty() || row.matches_search(&needle))
+            .filter(|row| label.is_empty() || label_matches(label, row.labels()))
+            .filter(|row| {
+                self.state
+                    .as_deref()
+                    .is_none_or(|wanted| wanted == row.state())
+            })
+            .cloned()
+            .collect();
+
+        if let Some(column) = &self.sort_by {
+            rows.sort_by(|a, b| {
+                let ordering = a.sort_key(column).cmp(&b.sort_key(column));
+                match self.direction {
+                    SortDirection::Ascending => ordering,
+                    SortDirection::Descending => ordering.reverse(),
+                }
+            });
+        }
+        rows
+    }
+}
+
+/// A value to sort a column by; numbers sort before text.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SortKey {
+    Number(i64),
+    Text(String),
+}
+
+fn text(value: &str) -> SortKey {
+    SortKey::Text(value.to_lowercase())
+}
+
+/// A row that `TableQuery` can search, filter and sort.
+pub trait TableRow {
+    /// `needle` is already lowercased.
+    fn matches_search(&self, needle: &str) -> bool;
+    fn sort_key(&self, column: &str) -> SortKey;
+    fn state(&self) -> &'static str;
+    fn labels(&self) -> &HashMap<String, String>;
+}
+
+fn contains(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(needle)
+}
+
+impl TableRow for ContainerInfo {
+    fn matches_search(&self, needle: &str) -> bool {
+        contains(&self.name, needle)
+            || contains(&self.image, needle)
+            || self.id.starts_with(needle)
+            || self
+                .compose_project
+                .as_deref()
+                .is_some_and(|project| contains(project, needle))
+    }
+
+    fn sort_key(&self, column: &str) -> SortKey {
+        match column {
+            "image" => text(&self.image),
+            "state" => text(self.state.label()),
+            "project" => text(self.compose_project.as_deref().unwrap_or_default()),
+            _ => text(&self.name),
+        }
+    }
+
+    fn state(&self) -> &'static str {
+        match self.state {
+            ContainerState::Running => "running",
+            ContainerState::Stopped => "stopped",
+        }
+    }
+
+    fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+}
+
+impl TableRow for ImageInfo {
+    fn matches_search(&self, needle: &str) -> bool {
+        contains(&self.repository, needle)
+            || contains(&self.tag, needle)
+            || self.id.trim_start_matches("sha256:").starts_with(needle)
+    }
+
+    fn sort_key(&self, column: &str) -> SortKey {
+        match column {
+            "tag" => text(&self.tag),
+            "size" => SortKey::Number(self.size_bytes),
+            "created" => SortKey::Number(self.created),
+            _ => text(&self.repository),
+        }
+    }
+
+    fn state(&self) -> &'static str {
+        if self.repository == "<none>" {
+            "dangling"
+        } else {
+            "tagged"
+        }
+    }
+
+    fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+}
+
+impl TableRow for VolumeInfo {
+    fn matches_search(&self, needle: &str) -> bool {
+        contains(&self.name, needle)
+            || contains(&self.driver, needle)
+            || contains(&self.mountpoint, needle)
+    }
+
+    fn sort_key(&self, column: &str) -> SortKey {
+        match column {
+            "driver" => text(&self.driver),
+            _ => text(&self.name),
+        }
+    }
+
+    fn state(&self) -> &'static str {
+        if self.in_use { "in use" } else { "unused" }
+    }
+
+    fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(repository: &str, size_bytes: i64, labels: &[(&str, &str)]) -> ImageInfo {
+        ImageInfo {
+            id: format!("sha256:{}", repository.len()),
+            repository: repository.to_string(),
+            tag: "latest".to_string(),
+            size: String::new(),
+            size_bytes,
+            created: 0,
+            labels: labels
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            tags: vec![format!("{}:latest", repository)],
+        }
+    }
+
+    #[test]
+    fn filters_then_sorts_rows() {
+        let images = vec![
+            image("nginx", 190, &[("team", "web")]),
+            image("<none>", 2, &[]),
+            image("postgres", 430, &[("team", "data")]),
+            image("nginx-proxy", 80, &[("team", "web")]),
+        ];
+        let names = |rows: Vec<ImageInfo>| -> Vec<String> {
+            rows.into_iter().map(|image| image.repository).collect()
+        };
+
+        let query = TableQuery {
+            search: "NGINX".to_string(),
+            sort_by: Some("size".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(names(query.apply(&images)), ["nginx-proxy", "nginx"]);
+
+        let query = TableQuery {
+            label: "team=web".to_string(),
+            sort_by: Some("repository".to_string()),
+            direction: SortDirection::Descending,
+            ..Default::default()
+        };
+        assert_eq!(names(query.apply(&images)), ["nginx-proxy", "nginx"]);
+
+        let query = TableQuery {
+            state: Some("dangling".to_string()),
+            label: "team".to_string(),
+            ..Default::default()
+        };
+        assert!(query.apply(&images).is_empty());
+    }
+
+    #[test]
+    fn filters_volumes_by_use() {
+        let volume = |name: &str, in_use: bool| VolumeInfo {
+            name: name.to_string(),
+            driver: "local".to_string(),
+            mountpoint: String::new(),
+            size: "--".to_string(),
+            labels: HashMap::new(),
+            in_use,
+        };
+        let volumes = vec![volume("db-data", true), volume("scratch", false)];
+
+        let query = TableQuery {
+            state: Some("unused".to_string()),
+            ..Default::default()
+        };
+        let names: Vec<String> = query
+            .apply(&volumes)
+            .into_iter()
+            .map(|volume| volume.name)
+            .collect();
+        assert_eq!(names, ["scratch"]);
+    }
+}
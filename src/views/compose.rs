@@ -0,0 +1,125 @@
+use dioxus::prelude::*;
+
+use crate::components::{SectionHeader, StatusPill};
+use crate::utils::{AppState, ComposeCommand};
+
+#[component]
+pub fn Compose() -> Element {
+    let app_state = use_context::<AppState>();
+    let session = (app_state.compose)();
+
+    let app_state_for_open = app_state.clone();
+
+    rsx! {
+        SectionHeader {
+            title: "Compose".to_string(),
+            subtitle: Some("Run multi-container projects from a compose file".to_string())
+        }
+
+        div { class: "action-bar",
+            button {
+                class: "button primary",
+                onclick: move |_| {
+                    let app_state = app_state_for_open.clone();
+                    spawn(async move {
+                        if let Some(file) = rfd::AsyncFileDialog::new()
+                            .add_filter("Compose file", &["yaml", "yml"])
+                            .pick_file()
+                            .await
+                        {
+                            app_state.load_compose_file(file.path().to_path_buf());
+                        }
+                    });
+                },
+                "Open compose file…"
+            }
+        }
+
+        if let Some(session) = session {
+            div { class: "card details-card",
+                div { class: "file-browser-header",
+                    div {
+                        h3 { "{session.project.name}" }
+                        p { class: "row-subtitle", "{session.project.file.display()}" }
+                    }
+                    div { class: "button-row compact",
+                        for command in [ComposeCommand::Up, ComposeCommand::Down, ComposeCommand::Restart] {
+                            {
+                                let app_state_for_command = app_state.clone();
+                                rsx! {
+                                    button {
+                                        class: if command == ComposeCommand::Up { "button primary" } else { "button secondary" },
+                                        disabled: session.is_running,
+                                        onclick: move |_| app_state_for_command.request_compose(command),
+                                        "{command.label()}"
+                                    }
+                                }
+                            }
+                        }
+                        {
+                            let app_state_for_close = app_state.clone();
+                            rsx! {
+                                button {
+                                    class: "button secondary",
+                                    disabled: session.is_running,
+                                    onclick: move |_| app_state_for_close.close_compose_file(),
+                                    "Close"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div { class: "table",
+                    div { class: "row header compose-row",
+                        span { "Service" }
+                        span { "Image" }
+                        span { "Ports" }
+                        span { "Depends on" }
+                        span { "Progress" }
+                    }
+                    for definition in session.project.services.iter() {
+                        {
+                            let progress = session.progress_for(&definition.name);
+                            let ports = if definition.ports.is_empty() {
+                                "--".to_string()
+                            } else {
+                                definition.ports.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+                            };
+                            let depends_on = if definition.depends_on.is_empty() {
+                                "--".to_string()
+                            } else {
+                                definition.depends_on.join(", ")
+                            };
+                            rsx! {
+                                div { class: "row item compose-row",
+                                    div {
+                                        p { class: "row-title", "{definition.name}" }
+                                        p { class: "row-subtitle", "{definition.container_name}" }
+                                    }
+                                    span { "{definition.image}" }
+                                    span { "{ports}" }
+                                    span { "{depends_on}" }
+                                    StatusPill { label: progress.label(), class_name: progress.css_class().to_string() }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                p { class: "row-subtitle",
+                    "Networks: "
+                    {session.project.networks.iter().map(|network| network.name.clone()).collect::<Vec<_>>().join(", ")}
+                }
+                if !session.project.volumes.is_empty() {
+                    p { class: "row-subtitle",
+                        "Volumes: "
+                        {session.project.volumes.iter().map(|volume| volume.name.clone()).collect::<Vec<_>>().join(", ")}
+                    }
+                }
+            }
+        } else {
+            p { class: "row-subtitle", "Open a compose.yaml to bring its services up or down." }
+        }
+    }
+}
@@ -0,0 +1,1005 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use dioxus::prelude::*;
+
+use crate::Route;
+use crate::components::{SectionHeader, StatusPill};
+use crate::services::{
+    ChangeKind, CommitSpec, ContainerLimits, ContainerState, DEFAULT_PS_ARGS, FileKind,
+    HealthStatus, PortConflict, RestartPolicyKind, change_rows, compose_service,
+    docker_run_command, format_bytes, parent_path, parse_bytes, parse_changes, parse_count,
+    port_conflicts,
+};
+use crate::utils::{AppState, copy_to_clipboard};
+
+/// Automatic refresh intervals offered on the Processes tab, in seconds.
+const PROCESS_REFRESH_SECS: [u64; 3] = [2, 5, 10];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DetailTab {
+    Files,
+    Changes,
+    Processes,
+    Ports,
+    Health,
+    Limits,
+    Recreate,
+}
+
+impl DetailTab {
+    const ALL: [DetailTab; 7] = [
+        DetailTab::Files,
+        DetailTab::Changes,
+        DetailTab::Processes,
+        DetailTab::Ports,
+        DetailTab::Health,
+        DetailTab::Limits,
+        DetailTab::Recreate,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            DetailTab::Files => "Files",
+            DetailTab::Changes => "Changes",
+            DetailTab::Processes => "Processes",
+            DetailTab::Ports => "Ports",
+            DetailTab::Health => "Health",
+            DetailTab::Limits => "Limits",
+            DetailTab::Recreate => "Run command",
+        }
+    }
+}
+
+#[component]
+pub fn ContainerDetail(id: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let app_state_for_load = app_state.clone();
+    use_effect(use_reactive((&id,), move |(id,)| {
+        app_state_for_load.browse_container(id.clone(), "/".to_string());
+        app_state_for_load.load_container_changes(id);
+    }));
+    let mut tab = use_signal(|| DetailTab::Files);
+
+    let mut show_commit = use_signal(|| false);
+    let mut show_rename = use_signal(|| false);
+
+    let container = (app_state.containers)()
+        .into_iter()
+        .find(|container| container.id == id);
+    let (title, subtitle) = match &container {
+        Some(container) => (container.name.clone(), container.image.clone()),
+        None => ("Container".to_string(), id.clone()),
+    };
+    let health = container.as_ref().and_then(|container| container.health);
+    let is_running = container
+        .as_ref()
+        .is_some_and(|container| container.state == ContainerState::Running);
+    let file_name = format!("{}.tar", title);
+    let title_for_rename = title.clone();
+    let id_for_export = id.clone();
+    let id_for_duplicate = id.clone();
+    let app_state_for_duplicate = app_state.clone();
+
+    rsx! {
+        SectionHeader { title, subtitle: Some(subtitle) }
+
+        div { class: "action-bar",
+            Link { to: Route::Containers {}, class: "button secondary", "Back to containers" }
+            if let Some(container) = &container {
+                StatusPill {
+                    label: container.state.label().to_string(),
+                    class_name: container.state.css_class().to_string()
+                }
+                if let Some(health) = container.health {
+                    StatusPill {
+                        label: health.label().to_string(),
+                        class_name: health.css_class().to_string()
+                    }
+                }
+                span { class: "row-subtitle", "{container.status}" }
+            }
+            div { class: "button-row compact",
+                button {
+                    class: "button secondary",
+                    onclick: move |_| show_rename.toggle(),
+                    "Rename…"
+                }
+                button {
+                    class: "button secondary",
+                    onclick: move |_| {
+                        app_state_for_duplicate.duplicate_container(id_for_duplicate.clone());
+                        navigator().push(Route::Containers {});
+                    },
+                    "Duplicate…"
+                }
+                button {
+                    class: "button secondary",
+                    onclick: move |_| show_commit.toggle(),
+                    "Commit as image…"
+                }
+                button {
+                    class: "button secondary",
+                    onclick: move |_| {
+                        let app_state = app_state.clone();
+                        let id = id_for_export.clone();
+                        let file_name = file_name.clone();
+                        spawn(async move {
+                            if let Some(file) = rfd::AsyncFileDialog::new()
+                                .set_file_name(file_name)
+                                .save_file()
+                                .await
+                            {
+                                app_state.export_container(id, file.path().to_path_buf());
+                            }
+                        });
+                    },
+                    "Export filesystem…"
+                }
+            }
+        }
+
+        if show_rename() {
+            RenameForm {
+                id: id.clone(),
+                current: title_for_rename,
+                on_close: move |_| show_rename.set(false),
+            }
+        }
+
+        if show_commit() {
+            CommitForm { id: id.clone(), on_close: move |_| show_commit.set(false) }
+        }
+
+        div { class: "tab-bar",
+            for option in DetailTab::ALL {
+                button {
+                    class: if tab() == option { "tab active" } else { "tab" },
+                    onclick: move |_| tab.set(option),
+                    "{option.label()}"
+                }
+            }
+        }
+
+        match tab() {
+            DetailTab::Files => rsx! { ContainerFiles { id: id.clone() } },
+            DetailTab::Changes => rsx! { ContainerChangesTree { id: id.clone() } },
+            DetailTab::Processes => rsx! {
+                // Keyed so the poll loop, which holds on to the id it started with,
+                // restarts when the page moves to another container.
+                ContainerProcessTable { key: "{id}", id: id.clone(), running: is_running }
+            },
+            DetailTab::Ports => rsx! { ContainerPorts { id: id.clone() } },
+            DetailTab::Health => rsx! {
+                ContainerHealthPanel { id: id.clone(), status: health }
+            },
+            DetailTab::Limits => rsx! { ContainerLimitsPanel { id: id.clone() } },
+            DetailTab::Recreate => rsx! { RecreateSnippets { id: id.clone() } },
+        }
+    }
+}
+
+#[component]
+fn RenameForm(id: String, current: String, on_close: EventHandler<()>) -> Element {
+    let app_state = use_context::<AppState>();
+    let mut name = use_signal(|| current.clone());
+    let is_valid = !name().trim().is_empty() && name().trim() != current;
+
+    rsx! {
+        form {
+            class: "card form-card path-form",
+            onsubmit: move |event| {
+                event.prevent_default();
+                if is_valid {
+                    app_state.rename_container(id.clone(), name().trim().to_string());
+                    on_close.call(());
+                }
+            },
+            input {
+                class: "text-input",
+                value: name,
+                oninput: move |event| name.set(event.value()),
+                placeholder: "New name"
+            }
+            button { class: "button primary", r#type: "submit", disabled: !is_valid, "Rename" }
+            button {
+                class: "button secondary",
+                r#type: "button",
+                onclick: move |_| on_close.call(()),
+                "Cancel"
+            }
+        }
+    }
+}
+
+/// Snapshots the container as a new image, like `docker commit`.
+#[component]
+fn CommitForm(id: String, on_close: EventHandler<()>) -> Element {
+    let app_state = use_context::<AppState>();
+    let mut repo = use_signal(String::new);
+    let mut tag = use_signal(String::new);
+    let mut comment = use_signal(String::new);
+    let mut author = use_signal(String::new);
+    let mut pause = use_signal(|| true);
+    let mut changes = use_signal(String::new);
+    let parsed = parse_changes(&changes());
+
+    rsx! {
+        div { class: "card form-card",
+            h3 { "Commit as image" }
+            div { class: "form-grid",
+                div {
+                    label { class: "form-label", "Repository" }
+                    input {
+                        class: "text-input",
+                        value: repo,
+                        oninput: move |event| repo.set(event.value()),
+                        placeholder: "debug/api"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Tag" }
+                    input {
+                        class: "text-input",
+                        value: tag,
+                        oninput: move |event| tag.set(event.value()),
+                        placeholder: "latest"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Author" }
+                    input {
+                        class: "text-input",
+                        value: author,
+                        oninput: move |event| author.set(event.value()),
+                        placeholder: "Jane Doe <jane@example.com>"
+                    }
+                }
+            }
+            label { class: "form-label", "Message" }
+            input {
+                class: "text-input",
+                value: comment,
+                oninput: move |event| comment.set(event.value()),
+                placeholder: "Reproduces the stuck worker"
+            }
+            label { class: "form-label", "Config changes" }
+            textarea {
+                class: "text-input",
+                rows: 3,
+                value: changes,
+                oninput: move |event| changes.set(event.value()),
+                placeholder: "One Dockerfile instruction per line, e.g. ENV DEBUG=1"
+            }
+            if let Err(error) = &parsed {
+                p { class: "activity-error", "{error}" }
+            }
+            label { class: "checkbox-label",
+                input {
+                    r#type: "checkbox",
+                    checked: pause(),
+                    onchange: move |event| pause.set(event.checked()),
+                }
+                "Pause the container while committing"
+            }
+            div { class: "button-row",
+                button {
+                    class: "button primary",
+                    disabled: repo().trim().is_empty() || parsed.is_err(),
+                    onclick: move |_| {
+                        if let Ok(changes) = parse_changes(&changes()) {
+                            app_state.commit_container(id.clone(), CommitSpec {
+                                repo: repo().trim().to_string(),
+                                tag: tag().trim().to_string(),
+                                comment: comment(),
+                                author: author().trim().to_string(),
+                                pause: pause(),
+                                changes,
+                            });
+                            on_close.call(());
+                        }
+                    },
+                    "Commit"
+                }
+                button {
+                    class: "button secondary",
+                    onclick: move |_| on_close.call(()),
+                    "Cancel"
+                }
+            }
+        }
+    }
+}
+
+/// The container's exposed ports, with links for the published ones and a
+/// warning for host ports another container also binds.
+#[component]
+fn ContainerPorts(id: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let containers = (app_state.containers)();
+    let Some(container) = containers.iter().find(|container| container.id == id) else {
+        return rsx! {};
+    };
+    let conflicts: Vec<PortConflict> = port_conflicts(&containers, &(app_state.port_bindings)())
+        .into_iter()
+        .filter(|conflict| conflict.containers.contains(&container.name))
+        .collect();
+
+    rsx! {
+        div { class: "card details-card",
+            if container.ports.is_empty() {
+                p { class: "row-subtitle", "This container exposes no ports" }
+            } else {
+                div { class: "table",
+                    div { class: "row header ports-row",
+                        span { "Host address" }
+                        span { "Container port" }
+                        span { "Open" }
+                    }
+                    for port in container.ports.clone() {
+                        div { class: "row item ports-row",
+                            span { "{port.host_address().unwrap_or_else(|| \"Not published\".to_string())}" }
+                            span { "{port.private_port}/{port.protocol}" }
+                            if let Some(url) = port.url() {
+                                a { class: "port-link", href: "{url}", "{url}" }
+                            } else {
+                                span { class: "row-subtitle", "--" }
+                            }
+                        }
+                    }
+                }
+            }
+            for conflict in conflicts {
+                p { class: "activity-error", "{conflict.message()}. {conflict.consequence()}." }
+            }
+        }
+    }
+}
+
+/// Runs `ps` in a running container, re-running it every few seconds so
+/// runaway or zombie processes show up without exec-ing in.
+#[component]
+fn ContainerProcessTable(id: String, running: bool) -> Element {
+    let app_state = use_context::<AppState>();
+    // What is typed into the arguments field, and what was last applied with it.
+    let mut ps_args = use_signal(|| DEFAULT_PS_ARGS.to_string());
+    let mut applied_args = use_signal(|| DEFAULT_PS_ARGS.to_string());
+    let mut refresh_secs = use_signal(|| 5u64);
+
+    let app_state_for_load = app_state.clone();
+    use_effect(use_reactive((&id, &running), move |(id, running)| {
+        if running {
+            app_state_for_load.load_processes(id, applied_args.peek().clone());
+        }
+    }));
+
+    let app_state_for_poll = app_state.clone();
+    let id_for_poll = id.clone();
+    use_future(move || {
+        let app_state = app_state_for_poll.clone();
+        let id = id_for_poll.clone();
+        async move {
+            loop {
+                let secs = *refresh_secs.peek();
+                tokio::time::sleep(Duration::from_secs(secs.max(1))).await;
+                let is_loading = app_state
+                    .container_processes
+                    .peek()
+                    .as_ref()
+                    .is_some_and(|processes| processes.is_loading);
+                let is_running = app_state.containers.peek().iter().any(|container| {
+                    container.id == id && container.state == ContainerState::Running
+                });
+                if secs > 0 && is_running && !is_loading {
+                    app_state.load_processes(id.clone(), applied_args.peek().clone());
+                }
+            }
+        }
+    });
+
+    if !running {
+        return rsx! {
+            div { class: "card file-browser",
+                p { class: "row-subtitle", "Start the container to see its processes" }
+            }
+        };
+    }
+
+    let processes = (app_state.container_processes)().filter(|processes| processes.id == id);
+    let is_loading = processes
+        .as_ref()
+        .is_some_and(|processes| processes.is_loading);
+    let list = processes
+        .and_then(|processes| processes.list)
+        .unwrap_or_default();
+    let zombies = list.zombie_count();
+    let app_state_for_args = app_state.clone();
+    let id_for_args = id.clone();
+
+    rsx! {
+        div { class: "card file-browser",
+            div { class: "file-browser-header",
+                div {
+                    h3 { "Processes" }
+                    p { class: "row-subtitle",
+                        "{list.processes.len()} processes"
+                        if zombies > 0 {
+                            " · {zombies} zombie"
+                        }
+                    }
+                }
+                div { class: "button-row",
+                    select {
+                        class: "text-input",
+                        value: "{refresh_secs}",
+                        onchange: move |event| {
+                            if let Ok(secs) = event.value().parse() {
+                                refresh_secs.set(secs);
+                            }
+                        },
+                        option { value: "0", "Manual refresh" }
+                        for secs in PROCESS_REFRESH_SECS {
+                            option { value: "{secs}", "Every {secs} s" }
+                        }
+                    }
+                    button {
+                        class: "button secondary",
+                        disabled: is_loading,
+                        onclick: move |_| app_state.load_processes(id.clone(), applied_args()),
+                        "Refresh"
+                    }
+                }
+            }
+
+            form {
+                class: "path-form",
+                onsubmit: move |event| {
+                    event.prevent_default();
+                    applied_args.set(ps_args());
+                    app_state_for_args.load_processes(id_for_args.clone(), ps_args());
+                },
+                label { class: "form-label", "ps arguments" }
+                input {
+                    class: "text-input",
+                    value: "{ps_args}",
+                    placeholder: DEFAULT_PS_ARGS,
+                    oninput: move |event| ps_args.set(event.value()),
+                }
+                button { class: "button secondary", r#type: "submit", "Apply" }
+            }
+
+            table { class: "process-table",
+                thead {
+                    tr {
+                        for title in list.titles.iter() {
+                            th { "{title}" }
+                        }
+                    }
+                }
+                tbody {
+                    for (index, process) in list.processes.iter().enumerate() {
+                        tr {
+                            key: "{index}",
+                            class: if list.is_zombie(process) { "zombie" },
+                            for value in process.iter() {
+                                td { "{value}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The health check command and its latest results. `status` comes from the
+/// container list, so the results reload whenever a refresh sees it change.
+#[component]
+fn ContainerHealthPanel(id: String, status: Option<HealthStatus>) -> Element {
+    let app_state = use_context::<AppState>();
+    let app_state_for_load = app_state.clone();
+    use_effect(use_reactive((&id, &status), move |(id, _)| {
+        app_state_for_load.load_health(id)
+    }));
+
+    let Some((_, health)) =
+        (app_state.container_health)().filter(|(health_id, _)| *health_id == id)
+    else {
+        return rsx! {
+            div { class: "card file-browser",
+                p { class: "row-subtitle", "Loading…" }
+            }
+        };
+    };
+    if health.test.is_empty() && health.status.is_none() {
+        return rsx! {
+            div { class: "card details-card",
+                p { class: "row-subtitle", "This container has no health check" }
+            }
+        };
+    }
+    let command = health.test.join(" ");
+    let id_for_refresh = id.clone();
+
+    rsx! {
+        div { class: "card details-card",
+            div { class: "file-browser-header",
+                div {
+                    h3 { "Health check" }
+                    p { class: "row-subtitle", "{command}" }
+                }
+                div { class: "button-row compact",
+                    if let Some(status) = health.status {
+                        StatusPill {
+                            label: status.label().to_string(),
+                            class_name: status.css_class().to_string()
+                        }
+                    }
+                    button {
+                        class: "button secondary",
+                        onclick: move |_| app_state.load_health(id_for_refresh.clone()),
+                        "Refresh"
+                    }
+                }
+            }
+            if health.failing_streak > 0 {
+                p { class: "activity-error", "Failed {health.failing_streak} times in a row" }
+            }
+            if health.log.is_empty() {
+                p { class: "row-subtitle", "No results yet" }
+            } else {
+                table { class: "process-table",
+                    thead {
+                        tr {
+                            th { "Started" }
+                            th { "Exit code" }
+                            th { "Output" }
+                        }
+                    }
+                    tbody {
+                        for check in health.log {
+                            tr { class: if check.passed() { "" } else { "failed" },
+                                td { "{check.started}" }
+                                td { "{check.exit_code}" }
+                                td { "{check.output}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Resource limits and restart policy, changed in place like `docker update`.
+#[component]
+fn ContainerLimitsPanel(id: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let app_state_for_load = app_state.clone();
+    use_effect(use_reactive((&id,), move |(id,)| {
+        app_state_for_load.load_limits(id)
+    }));
+
+    let Some((_, current)) =
+        (app_state.container_limits)().filter(|(limits_id, _)| *limits_id == id)
+    else {
+        return rsx! {
+            div { class: "card file-browser",
+                p { class: "row-subtitle", "Loading…" }
+            }
+        };
+    };
+
+    rsx! {
+        LimitsForm { key: "{id}", id, current }
+    }
+}
+
+#[component]
+fn LimitsForm(id: String, current: ContainerLimits) -> Element {
+    let app_state = use_context::<AppState>();
+    let number = |value: i64| {
+        if value == 0 {
+            String::new()
+        } else {
+            value.to_string()
+        }
+    };
+    let mut cpu_shares = use_signal(|| number(current.cpu_shares));
+    let mut cpu_quota = use_signal(|| number(current.cpu_quota));
+    let mut cpu_period = use_signal(|| number(current.cpu_period));
+    let mut memory = use_signal(|| format_bytes(current.memory));
+    let mut memory_swap = use_signal(|| format_bytes(current.memory_swap));
+    let mut pids_limit = use_signal(|| number(current.pids_limit));
+    let mut restart_policy = use_signal(|| current.restart_policy);
+    let mut max_retries = use_signal(|| number(current.max_retries));
+
+    // Start over from what the engine reports whenever it reports new values,
+    // such as after Apply
+    use_effect(use_reactive((&current,), move |(current,)| {
+        cpu_shares.set(number(current.cpu_shares));
+        cpu_quota.set(number(current.cpu_quota));
+        cpu_period.set(number(current.cpu_period));
+        memory.set(format_bytes(current.memory));
+        memory_swap.set(format_bytes(current.memory_swap));
+        pids_limit.set(number(current.pids_limit));
+        restart_policy.set(current.restart_policy);
+        max_retries.set(number(current.max_retries));
+    }));
+
+    let parse_number = |label: &str, input: &str, allow_unlimited: bool| {
+        parse_count(input, allow_unlimited).map_err(|e| format!("{}: {}", label, e))
+    };
+    let limits = (|| -> Result<ContainerLimits, String> {
+        Ok(ContainerLimits {
+            cpu_shares: parse_number("CPU shares", &cpu_shares(), false)?,
+            cpu_quota: parse_number("CPU quota", &cpu_quota(), false)?,
+            cpu_period: parse_number("CPU period", &cpu_period(), false)?,
+            memory: parse_bytes(&memory(), false).map_err(|e| format!("Memory: {}", e))?,
+            memory_swap: parse_bytes(&memory_swap(), true)
+                .map_err(|e| format!("Memory + swap: {}", e))?,
+            pids_limit: parse_number("PIDs limit", &pids_limit(), true)?,
+            restart_policy: restart_policy(),
+            max_retries: parse_number("Maximum retries", &max_retries(), false)?,
+        })
+    })();
+    let changed = limits.as_ref().is_ok_and(|limits| *limits != current);
+
+    rsx! {
+        div { class: "card form-card",
+            h3 { "Limits" }
+            div { class: "form-grid",
+                div {
+                    label { class: "form-label", "CPU shares" }
+                    input {
+                        class: "text-input",
+                        value: cpu_shares,
+                        oninput: move |event| cpu_shares.set(event.value()),
+                        placeholder: "1024"
+                    }
+                }
+                div {
+                    label { class: "form-label", "CPU quota (µs per period)" }
+                    input {
+                        class: "text-input",
+                        value: cpu_quota,
+                        oninput: move |event| cpu_quota.set(event.value()),
+                        placeholder: "50000"
+                    }
+                }
+                div {
+                    label { class: "form-label", "CPU period (µs)" }
+                    input {
+                        class: "text-input",
+                        value: cpu_period,
+                        oninput: move |event| cpu_period.set(event.value()),
+                        placeholder: "100000"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Memory" }
+                    input {
+                        class: "text-input",
+                        value: memory,
+                        oninput: move |event| memory.set(event.value()),
+                        placeholder: "512m"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Memory + swap" }
+                    input {
+                        class: "text-input",
+                        value: memory_swap,
+                        oninput: move |event| memory_swap.set(event.value()),
+                        placeholder: "1g, or -1 for unlimited"
+                    }
+                }
+                div {
+                    label { class: "form-label", "PIDs limit" }
+                    input {
+                        class: "text-input",
+                        value: pids_limit,
+                        oninput: move |event| pids_limit.set(event.value()),
+                        placeholder: "Unlimited"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Restart policy" }
+                    select {
+                        class: "text-input",
+                        value: restart_policy().value(),
+                        onchange: move |event| restart_policy.set(RestartPolicyKind::from_value(&event.value())),
+                        for kind in RestartPolicyKind::ALL {
+                            option { value: kind.value(), "{kind.value()}" }
+                        }
+                    }
+                }
+                div {
+                    label { class: "form-label", "Maximum retries" }
+                    input {
+                        class: "text-input",
+                        disabled: restart_policy() != RestartPolicyKind::OnFailure,
+                        value: max_retries,
+                        oninput: move |event| max_retries.set(event.value()),
+                        placeholder: "Unlimited"
+                    }
+                }
+            }
+            p { class: "row-subtitle",
+                "Only changed fields are sent. The engine cannot lift a CPU or memory limit on an existing container, so clearing those fields keeps the current value; clearing the PIDs limit removes it."
+            }
+            if let Err(error) = &limits {
+                p { class: "activity-error", "{error}" }
+            }
+            div { class: "button-row",
+                button {
+                    class: "button primary",
+                    disabled: !changed,
+                    onclick: move |_| {
+                        if let Ok(limits) = limits.clone() {
+                            app_state.update_limits(id.clone(), current.clone(), limits);
+                        }
+                    },
+                    "Apply"
+                }
+            }
+        }
+    }
+}
+
+/// The `docker run` command and Compose service that recreate the container.
+#[component]
+fn RecreateSnippets(id: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let app_state_for_load = app_state.clone();
+    use_effect(use_reactive((&id,), move |(id,)| {
+        app_state_for_load.load_container_spec(id)
+    }));
+
+    let Some((_, spec)) = (app_state.container_spec)().filter(|(spec_id, _)| *spec_id == id) else {
+        return rsx! {
+            div { class: "card file-browser",
+                p { class: "row-subtitle", "Loading…" }
+            }
+        };
+    };
+    let run = docker_run_command(&spec);
+    let compose = compose_service(&spec).unwrap_or_else(|e| format!("# {}", e));
+
+    rsx! {
+        for (title, snippet) in [("docker run", run), ("Compose service", compose)] {
+            div { class: "card file-browser",
+                div { class: "file-browser-header",
+                    h3 { "{title}" }
+                    button {
+                        class: "button secondary",
+                        onclick: {
+                            let snippet = snippet.clone();
+                            move |_| copy_to_clipboard(&snippet)
+                        },
+                        "Copy"
+                    }
+                }
+                pre { class: "snippet", "{snippet}" }
+            }
+        }
+        p { class: "row-subtitle",
+            "Settings the image already provides are left out."
+        }
+    }
+}
+
+/// What the container wrote to its writable layer, as a tree filtered by kind.
+#[component]
+fn ContainerChangesTree(id: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let mut kinds = use_signal(|| ChangeKind::ALL.into_iter().collect::<HashSet<_>>());
+    let Some(changes) = (app_state.container_changes)().filter(|changes| changes.id == id) else {
+        return rsx! {};
+    };
+
+    let rows = change_rows(&changes.changes, &kinds());
+    let count = |kind: ChangeKind| {
+        changes
+            .changes
+            .iter()
+            .filter(|change| change.kind == kind)
+            .count()
+    };
+
+    rsx! {
+        div { class: "card file-browser",
+            div { class: "file-browser-header",
+                div {
+                    h3 { "Changes" }
+                    p { class: "row-subtitle", "Paths that differ from the image" }
+                }
+                div { class: "button-row",
+                    for kind in ChangeKind::ALL {
+                        label { class: "checkbox-label",
+                            input {
+                                r#type: "checkbox",
+                                checked: kinds().contains(&kind),
+                                onchange: move |event| {
+                                    if event.checked() {
+                                        kinds.write().insert(kind);
+                                    } else {
+                                        kinds.write().remove(&kind);
+                                    }
+                                }
+                            }
+                            "{kind.label()} ({count(kind)})"
+                        }
+                    }
+                    button {
+                        class: "button secondary",
+                        disabled: changes.is_loading,
+                        onclick: move |_| app_state.load_container_changes(id.clone()),
+                        "Refresh"
+                    }
+                }
+            }
+
+            if changes.is_loading {
+                p { class: "row-subtitle", "Loading…" }
+            } else if changes.changes.is_empty() {
+                p { class: "row-subtitle", "Nothing has changed since the container was created" }
+            } else if rows.is_empty() {
+                p { class: "row-subtitle", "No changes of the selected kinds" }
+            }
+
+            ul { class: "change-tree",
+                for row in rows {
+                    li {
+                        key: "{row.path}",
+                        class: "change-row",
+                        style: "padding-left: {row.depth * 18}px",
+                        title: "{row.path}",
+                        span { "{row.name}" }
+                        if let Some(kind) = row.kind {
+                            span { class: "change-kind {kind.css_class()}", "{kind.label()}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Lists, downloads and uploads files inside the container, like `docker cp`.
+#[component]
+fn ContainerFiles(id: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let mut go_to = use_signal(String::new);
+    let Some(browser) = (app_state.container_browser)().filter(|browser| browser.target == id)
+    else {
+        return rsx! {};
+    };
+
+    let path = browser.path.clone();
+    let parent = parent_path(&path);
+
+    let app_state_for_go = app_state.clone();
+    let id_for_go = id.clone();
+    let app_state_for_up = app_state.clone();
+    let id_for_up = id.clone();
+    let app_state_for_refresh = app_state.clone();
+    let id_for_refresh = id.clone();
+    let path_for_refresh = path.clone();
+    let app_state_for_upload = app_state.clone();
+    let id_for_upload = id.clone();
+    let path_for_upload = path.clone();
+
+    rsx! {
+        div { class: "card file-browser",
+            div { class: "file-browser-header",
+                div {
+                    h3 { "Files" }
+                    p { class: "row-subtitle", "{path}" }
+                }
+                div { class: "button-row",
+                    button {
+                        class: "button secondary",
+                        disabled: path == "/",
+                        onclick: move |_| app_state_for_up.browse_container(id_for_up.clone(), parent.clone()),
+                        "Up"
+                    }
+                    button {
+                        class: "button secondary",
+                        disabled: browser.is_loading,
+                        onclick: move |_| app_state_for_refresh.browse_container(id_for_refresh.clone(), path_for_refresh.clone()),
+                        "Refresh"
+                    }
+                    button {
+                        class: "button primary",
+                        onclick: move |_| {
+                            let app_state = app_state_for_upload.clone();
+                            let id = id_for_upload.clone();
+                            let dir = path_for_upload.clone();
+                            spawn(async move {
+                                if let Some(files) = rfd::AsyncFileDialog::new().pick_files().await {
+                                    let sources = files.iter().map(|file| file.path().to_path_buf()).collect();
+                                    app_state.upload_to_container(id, dir, sources);
+                                }
+                            });
+                        },
+                        "Upload files"
+                    }
+                }
+            }
+
+            form {
+                class: "path-form",
+                onsubmit: move |event| {
+                    event.prevent_default();
+                    let target = go_to().trim().to_string();
+                    if target.starts_with('/') {
+                        app_state_for_go.browse_container(id_for_go.clone(), target);
+                        go_to.set(String::new());
+                    }
+                },
+                input {
+                    class: "text-input",
+                    value: "{go_to}",
+                    placeholder: "Go to an absolute path, e.g. /var/log",
+                    oninput: move |event| go_to.set(event.value()),
+                }
+                button { class: "button secondary", r#type: "submit", "Go" }
+            }
+
+            if browser.is_loading {
+                p { class: "row-subtitle", "Loading…" }
+            } else if browser.entries.is_empty() {
+                p { class: "row-subtitle", "This directory is empty" }
+            }
+
+            div { class: "table",
+                {browser.entries.iter().map(|entry| {
+                    let entry_path = entry.path.clone();
+                    let is_dir = entry.kind == FileKind::Directory;
+                    let app_state_for_open = app_state.clone();
+                    let id_for_open = id.clone();
+                    let path_for_open = entry_path.clone();
+                    let app_state_for_download = app_state.clone();
+                    let id_for_download = id.clone();
+                    let file_name = format!("{}.tar", entry.name);
+
+                    rsx! {
+                        div { class: "row item files-row",
+                            if is_dir {
+                                button {
+                                    class: "link-button",
+                                    onclick: move |_| app_state_for_open.browse_container(id_for_open.clone(), path_for_open.clone()),
+                                    "{entry.name}/"
+                                }
+                            } else {
+                                span { "{entry.name}" }
+                            }
+                            span { "{entry.kind.label()}" }
+                            span { "{entry.size}" }
+                            button {
+                                class: "button secondary",
+                                onclick: move |_| {
+                                    let app_state = app_state_for_download.clone();
+                                    let id = id_for_download.clone();
+                                    let path = entry_path.clone();
+                                    let file_name = file_name.clone();
+                                    spawn(async move {
+                                        if let Some(file) = rfd::AsyncFileDialog::new()
+                                            .set_file_name(file_name)
+                                            .save_file()
+                                            .await
+                                        {
+                                            app_state.download_container_path(id, path, file.path().to_path_buf());
+                                        }
+                                    });
+                                },
+                                "Download"
+                            }
+                        }
+                    }
+                })}
+            }
+        }
+    }
+}
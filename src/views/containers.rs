@@ -1,14 +1,32 @@
+use std::collections::HashSet;
+
 use dioxus::prelude::*;
 
-use crate::components::{SectionHeader, StatusPill};
-use crate::services::ContainerState;
-use crate::utils::AppState;
+use crate::Route;
+use crate::components::{
+    BulkBar, PortLinks, ResourceStatusBar, SectionHeader, SelectBox, SkeletonRows, StatusPill,
+    TableToolbar,
+};
+use crate::services::{
+    ContainerInfo, ContainerSpec, ContainerSpecText, ContainerState, RestartPolicyKind,
+    group_by_project, port_conflicts, taken_ports,
+};
+use crate::utils::{AppState, ProjectAction, TableKind, TableQuery};
 
 #[component]
 pub fn Containers() -> Element {
     let app_state = use_context::<AppState>();
     // AppState fields are Signals, call them to get the inner value
-    let containers = (app_state.containers)();
+    let query = (app_state.container_query)();
+    let containers = query.apply(&(app_state.containers)());
+    let filtered = query != TableQuery::default();
+    let status = (app_state.containers_status)();
+    let app_state_for_retry = app_state.clone();
+    let app_state_for_new = app_state.clone();
+    let draft = (app_state.container_draft)();
+    let groups = group_by_project(&containers);
+    let conflicts = port_conflicts(&(app_state.containers)(), &(app_state.port_bindings)());
+    let mut collapsed = use_signal(HashSet::<String>::new);
 
     rsx! {
         SectionHeader {
@@ -17,13 +35,41 @@ pub fn Containers() -> Element {
         }
 
         div { class: "action-bar",
+            ResourceStatusBar {
+                status: status.clone(),
+                on_retry: move |_| app_state_for_retry.refresh_containers(),
+            }
+            button {
+                class: "button secondary",
+                onclick: move |_| app_state_for_new.new_container(),
+                "New container"
+            }
             button {
                 class: "button primary",
+                disabled: status.is_loading,
                 onclick: move |_| app_state.refresh_containers(),
                 "Refresh"
             }
         }
 
+        if let Some((generation, draft)) = draft {
+            CreateContainerForm { key: "{generation}", draft }
+        }
+
+        if !conflicts.is_empty() {
+            div { class: "port-conflicts",
+                for conflict in conflicts {
+                    p { "{conflict.message()}. {conflict.consequence()}." }
+                }
+            }
+        }
+
+        TableToolbar { table: TableKind::Containers }
+        BulkBar {
+            table: TableKind::Containers,
+            visible: containers.iter().map(|container| container.id.clone()).collect::<Vec<_>>(),
+        }
+
         div { class: "table",
             div { class: "row header",
                 span { "Name" }
@@ -32,45 +78,349 @@ pub fn Containers() -> Element {
                 span { "State" }
                 span { "Action" }
             }
+            if status.is_first_load() {
+                SkeletonRows { row_class: "".to_string(), columns: 5 }
+            }
+            if filtered && containers.is_empty() && !status.is_loading {
+                p { class: "row-subtitle", "Nothing matches the current filters" }
+            }
+            for group in groups {
+                {
+                    let running = group.running();
+                    let total = group.containers.len();
+                    match group.project.clone() {
+                        Some(project) => {
+                            let is_collapsed = collapsed().contains(&project);
+                            let project_for_toggle = project.clone();
+                            rsx! {
+                                div { class: "project-header",
+                                    button {
+                                        class: "link-button project-toggle",
+                                        onclick: move |_| {
+                                            let mut set = collapsed.write();
+                                            if !set.remove(&project_for_toggle) {
+                                                set.insert(project_for_toggle.clone());
+                                            }
+                                        },
+                                        if is_collapsed { "▸ " } else { "▾ " }
+                                        "{project}"
+                                    }
+                                    span { class: "row-subtitle", "{running}/{total} running" }
+                                    div { class: "button-row compact",
+                                        for action in [ProjectAction::Start, ProjectAction::Stop, ProjectAction::Restart] {
+                                            {
+                                                let app_state_for_project = app_state.clone();
+                                                let project = project.clone();
+                                                rsx! {
+                                                    button {
+                                                        class: "button secondary",
+                                                        onclick: move |_| app_state_for_project.apply_project_action(project.clone(), action),
+                                                        "{action.label()}"
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                if !is_collapsed {
+                                    for container in group.containers {
+                                        ContainerRow { key: "{container.id}", container }
+                                    }
+                                }
+                            }
+                        }
+                        None => rsx! {
+                            div { class: "project-header",
+                                span { class: "project-toggle", "Standalone containers" }
+                                span { class: "row-subtitle", "{running}/{total} running" }
+                            }
+                            for container in group.containers {
+                                ContainerRow { key: "{container.id}", container }
+                            }
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
 
-            // Use an iterator expression in braces. Each closure builds an rsx! element.
-            // Clone only the data we need so closures are 'static' friendly.
-            {containers.iter().map(|container| {
-                let id = container.id.clone();
-                let name = container.name.clone();
-                let status = container.status.clone();
-                let image = container.image.clone();
-                let ports = container.ports.clone();
-                let app_state_for_btn = app_state.clone();
+#[component]
+fn ContainerRow(container: ContainerInfo) -> Element {
+    let app_state = use_context::<AppState>();
 
-                let next_state = if container.state == ContainerState::Running {
-                    ContainerState::Stopped
-                } else {
-                    ContainerState::Running
-                };
-                let button_label = container.state.action_label();
-                let pill_label = container.state.label();
-                let pill_class = container.state.css_class();
+    let id = container.id.clone();
+    let next_state = if container.state == ContainerState::Running {
+        ContainerState::Stopped
+    } else {
+        ContainerState::Running
+    };
+    let button_label = container.state.action_label();
+    let pill_label = container.state.label();
+    let pill_class = container.state.css_class();
+    let title = match &container.compose_service {
+        Some(service) => format!("{} ({})", container.name, service),
+        None => container.name.clone(),
+    };
 
-                rsx! {
-                    div { class: "row item",
-                        div {
-                            p { class: "row-title", "{name}" }
-                            p { class: "row-subtitle", "{status}" }
-                        }
-                        span { "{image}" }
-                        span { "{ports}" }
-                        StatusPill { label: pill_label.to_string(), class_name: pill_class.to_string() }
-                        button {
-                            class: "button secondary",
-                            // closure captures owned clones above so it's safe to be 'static'
-                            onclick: move |_| app_state_for_btn.set_container_state(&id, next_state),
-                            "{button_label}"
-                        }
+    rsx! {
+        div { class: "row item",
+            div { class: "row-select",
+                SelectBox { table: TableKind::Containers, id: container.id.clone() }
+                div {
+                    Link {
+                        to: Route::ContainerDetail { id: container.id.clone() },
+                        class: "row-title",
+                        "{title}"
                     }
+                    p { class: "row-subtitle", "{container.status}" }
                 }
-            })}
+            }
+            span { "{container.image}" }
+            PortLinks { ports: container.ports.clone() }
+            div { class: "pill-row",
+                StatusPill { label: pill_label.to_string(), class_name: pill_class.to_string() }
+                if let Some(health) = container.health {
+                    StatusPill {
+                        label: health.label().to_string(),
+                        class_name: health.css_class().to_string()
+                    }
+                }
+            }
+            button {
+                class: "button secondary",
+                onclick: move |_| app_state.set_container_state(&id, next_state),
+                "{button_label}"
+            }
+        }
+    }
+}
+
+/// Creates a container, either from scratch or from a duplicated container's settings.
+#[component]
+fn CreateContainerForm(draft: ContainerSpec) -> Element {
+    let app_state = use_context::<AppState>();
+    let text = draft.to_text();
+    let mut name = use_signal(|| draft.name.clone());
+    let mut image = use_signal(|| draft.image.clone());
+    let mut network = use_signal(|| draft.network.clone());
+    let mut working_dir = use_signal(|| draft.working_dir.clone());
+    let mut user = use_signal(|| draft.user.clone());
+    let mut restart_policy = use_signal(|| draft.restart_policy);
+    let mut max_retries = use_signal(|| match draft.max_retries {
+        0 => String::new(),
+        retries => retries.to_string(),
+    });
+    let mut command = use_signal(|| text.command.clone());
+    let mut entrypoint = use_signal(|| text.entrypoint.clone());
+    let mut env = use_signal(|| text.env.clone());
+    let mut ports = use_signal(|| text.ports.clone());
+    let mut binds = use_signal(|| text.binds.clone());
+    let mut labels = use_signal(|| text.labels.clone());
+    let mut start = use_signal(|| true);
+
+    let retries = match max_retries().trim() {
+        "" => Ok(0),
+        input => input
+            .parse::<u32>()
+            .map(i64::from)
+            .map_err(|_| "Maximum retries must be a whole number".to_string()),
+    };
+    let spec = retries.and_then(|max_retries| {
+        ContainerSpec {
+            name: name().trim().to_string(),
+            image: image().trim().to_string(),
+            network: network().trim().to_string(),
+            working_dir: working_dir().trim().to_string(),
+            user: user().trim().to_string(),
+            restart_policy: restart_policy(),
+            max_retries,
+            ..Default::default()
+        }
+        .with_text(&ContainerSpecText {
+            command: command(),
+            entrypoint: entrypoint(),
+            env: env(),
+            ports: ports(),
+            binds: binds(),
+            labels: labels(),
+        })
+        .map_err(|e| e.to_string())
+    });
+    let taken = match &spec {
+        Ok(spec) => taken_ports(
+            &spec.ports,
+            &(app_state.containers)(),
+            &(app_state.port_bindings)(),
+        ),
+        Err(_) => Vec::new(),
+    };
+    let app_state_for_close = app_state.clone();
 
+    rsx! {
+        div { class: "card form-card",
+            h3 { "New container" }
+            div { class: "form-grid",
+                div {
+                    label { class: "form-label", "Name" }
+                    input {
+                        class: "text-input",
+                        value: name,
+                        oninput: move |event| name.set(event.value()),
+                        placeholder: "Generated by the engine"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Image" }
+                    input {
+                        class: "text-input",
+                        value: image,
+                        oninput: move |event| image.set(event.value()),
+                        placeholder: "nginx:latest"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Network" }
+                    input {
+                        class: "text-input",
+                        value: network,
+                        oninput: move |event| network.set(event.value()),
+                        placeholder: "bridge"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Restart policy" }
+                    select {
+                        class: "text-input",
+                        value: restart_policy().value(),
+                        onchange: move |event| restart_policy.set(RestartPolicyKind::from_value(&event.value())),
+                        for kind in RestartPolicyKind::ALL {
+                            option { value: kind.value(), "{kind.value()}" }
+                        }
+                    }
+                }
+                div {
+                    label { class: "form-label", "Maximum retries" }
+                    input {
+                        class: "text-input",
+                        disabled: restart_policy() != RestartPolicyKind::OnFailure,
+                        value: max_retries,
+                        oninput: move |event| max_retries.set(event.value()),
+                        placeholder: "Unlimited"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Working directory" }
+                    input {
+                        class: "text-input",
+                        value: working_dir,
+                        oninput: move |event| working_dir.set(event.value()),
+                        placeholder: "From the image"
+                    }
+                }
+                div {
+                    label { class: "form-label", "User" }
+                    input {
+                        class: "text-input",
+                        value: user,
+                        oninput: move |event| user.set(event.value()),
+                        placeholder: "From the image"
+                    }
+                }
+            }
+            div { class: "form-grid",
+                div {
+                    label { class: "form-label", "Environment (KEY=value per line)" }
+                    textarea {
+                        class: "text-input",
+                        rows: 5,
+                        value: env,
+                        oninput: move |event| env.set(event.value()),
+                    }
+                }
+                div {
+                    label { class: "form-label", "Ports (host:container per line)" }
+                    textarea {
+                        class: "text-input",
+                        rows: 5,
+                        value: ports,
+                        oninput: move |event| ports.set(event.value()),
+                        placeholder: "8080:80"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Volumes (source:target per line)" }
+                    textarea {
+                        class: "text-input",
+                        rows: 5,
+                        value: binds,
+                        oninput: move |event| binds.set(event.value()),
+                        placeholder: "data:/var/lib/data"
+                    }
+                }
+            }
+            div { class: "form-grid",
+                div {
+                    label { class: "form-label", "Command (one argument per line)" }
+                    textarea {
+                        class: "text-input",
+                        rows: 3,
+                        value: command,
+                        oninput: move |event| command.set(event.value()),
+                        placeholder: "From the image"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Entrypoint (one argument per line)" }
+                    textarea {
+                        class: "text-input",
+                        rows: 3,
+                        value: entrypoint,
+                        oninput: move |event| entrypoint.set(event.value()),
+                        placeholder: "From the image"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Labels (key=value per line)" }
+                    textarea {
+                        class: "text-input",
+                        rows: 3,
+                        value: labels,
+                        oninput: move |event| labels.set(event.value()),
+                    }
+                }
+            }
+            if let Err(error) = &spec {
+                p { class: "activity-error", "{error}" }
+            }
+            for (port, owner) in taken {
+                p { class: "activity-error", "Port {port} is already published by {owner}" }
+            }
+            label { class: "checkbox-label",
+                input {
+                    r#type: "checkbox",
+                    checked: start(),
+                    onchange: move |event| start.set(event.checked()),
+                }
+                "Start after creating"
+            }
+            div { class: "button-row",
+                button {
+                    class: "button primary",
+                    disabled: image().trim().is_empty() || spec.is_err(),
+                    onclick: move |_| {
+                        if let Ok(spec) = spec.clone() {
+                            app_state.create_container(spec, start());
+                        }
+                    },
+                    "Create"
+                }
+                button {
+                    class: "button secondary",
+                    onclick: move |_| app_state_for_close.close_container_draft(),
+                    "Cancel"
+                }
+            }
         }
     }
 }
@@ -1,7 +1,7 @@
 use dioxus::prelude::*;
 
 use crate::components::{MetricCard, SectionHeader};
-use crate::services::ContainerState;
+use crate::services::{ContainerState, DiskUsageKind};
 use crate::utils::AppState;
 
 #[component]
@@ -12,7 +12,9 @@ pub fn Dashboard() -> Element {
     let images = (app_state.images)();
     let volumes = (app_state.volumes)();
     let docker_host = (app_state.docker_host)();
-    let error_message = (app_state.error_message)();
+    let engine = (app_state.engine)();
+    let disk_usage = (app_state.disk_usage)();
+    let mut expanded = use_signal(|| None::<DiskUsageKind>);
 
     let running = containers
         .iter()
@@ -26,12 +28,6 @@ pub fn Dashboard() -> Element {
             subtitle: Some("Overview of your local Docker engine".to_string())
         }
 
-        if let Some(error) = error_message {
-            div { class: "error-message",
-                "⚠️ {error}"
-            }
-        }
-
         div { class: "action-bar",
             button {
                 class: "button primary",
@@ -66,8 +62,90 @@ pub fn Dashboard() -> Element {
         div { class: "card",
             h3 { "Engine" }
             p { class: "engine-row", "Host: {docker_host}" }
-            p { class: "engine-row", "Context: local" }
-            p { class: "engine-row", "Compose: ready" }
+            if let Some(engine) = engine {
+                div { class: "engine-grid",
+                    p { class: "engine-row", "Name: {engine.name}" }
+                    p { class: "engine-row", "Engine: {engine.engine_version}" }
+                    p { class: "engine-row", "API: {engine.api_version}" }
+                    p { class: "engine-row", "OS: {engine.operating_system} ({engine.architecture})" }
+                    p { class: "engine-row", "Kernel: {engine.kernel_version}" }
+                    p { class: "engine-row", "Storage driver: {engine.storage_driver}" }
+                    p { class: "engine-row", "Cgroup: {engine.cgroup_version}" }
+                    p { class: "engine-row", "CPUs: {engine.cpus}" }
+                    p { class: "engine-row", "Memory: {engine.memory}" }
+                    p { class: "engine-row",
+                        "Rootless: "
+                        if engine.rootless { "yes" } else { "no" }
+                    }
+                }
+                p { class: "engine-row",
+                    "Registry mirrors: "
+                    if engine.registry_mirrors.is_empty() {
+                        "none"
+                    } else {
+                        {engine.registry_mirrors.join(", ")}
+                    }
+                }
+            } else {
+                p { class: "engine-row", "Engine details unavailable" }
+            }
+        }
+
+        div { class: "card",
+            h3 { "Disk usage" }
+            if let Some(usage) = disk_usage {
+                p { class: "engine-row",
+                    "Total: {usage.total_label()} · Reclaimable: {usage.reclaimable_label()}"
+                }
+                div { class: "table",
+                    div { class: "row header disk-row",
+                        span { "Type" }
+                        span { "Total" }
+                        span { "Active" }
+                        span { "Size" }
+                        span { "Reclaimable" }
+                    }
+                    for category in usage.categories {
+                        {
+                            let kind = category.kind;
+                            let is_expanded = expanded() == Some(kind);
+                            rsx! {
+                                div { class: "row item disk-row",
+                                    button {
+                                        class: "link-button",
+                                        onclick: move |_| {
+                                            expanded.set(if is_expanded { None } else { Some(kind) });
+                                        },
+                                        if is_expanded { "▾ " } else { "▸ " }
+                                        "{kind.label()}"
+                                    }
+                                    span { "{category.items.len()}" }
+                                    span { "{category.active()}" }
+                                    span { "{category.size_label()}" }
+                                    span { "{category.reclaimable_label()}" }
+                                }
+                                if is_expanded {
+                                    if category.items.is_empty() {
+                                        p { class: "row-subtitle", "Nothing here." }
+                                    }
+                                    for item in category.items.iter() {
+                                        div { class: "row item disk-item-row",
+                                            div {
+                                                p { class: "row-title", "{item.name}" }
+                                                p { class: "row-subtitle", "{item.detail}" }
+                                            }
+                                            span { "{item.size_label()}" }
+                                            span { if item.in_use { "In use" } else { "Reclaimable" } }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            } else {
+                p { class: "engine-row", "Disk usage unavailable" }
+            }
         }
     }
 }
@@ -1,12 +1,18 @@
 use dioxus::prelude::*;
 
-use crate::components::SectionHeader;
-use crate::utils::AppState;
+use crate::components::{
+    BulkBar, ResourceStatusBar, SectionHeader, SelectBox, SkeletonRows, TableToolbar,
+};
+use crate::utils::{AppState, TableKind, TableQuery};
 
 #[component]
 pub fn Images() -> Element {
     let app_state = use_context::<AppState>();
-    let images = (app_state.images)();
+    let query = (app_state.image_query)();
+    let images = query.apply(&(app_state.images)());
+    let filtered = query != TableQuery::default();
+    let status = (app_state.images_status)();
+    let app_state_for_retry = app_state.clone();
 
     rsx! {
         SectionHeader {
@@ -15,13 +21,24 @@ pub fn Images() -> Element {
         }
 
         div { class: "action-bar",
+            ResourceStatusBar {
+                status: status.clone(),
+                on_retry: move |_| app_state_for_retry.refresh_images(),
+            }
             button {
                 class: "button primary",
+                disabled: status.is_loading,
                 onclick: move |_| app_state.refresh_images(),
                 "Refresh"
             }
         }
 
+        TableToolbar { table: TableKind::Images }
+        BulkBar {
+            table: TableKind::Images,
+            visible: images.iter().map(|image| image.id.clone()).collect::<Vec<_>>(),
+        }
+
         div { class: "table",
             div { class: "row header",
                 span { "Repository" }
@@ -29,9 +46,18 @@ pub fn Images() -> Element {
                 span { "Image ID" }
                 span { "Size" }
             }
+            if status.is_first_load() {
+                SkeletonRows { row_class: "images-row".to_string(), columns: 4 }
+            }
+            if filtered && images.is_empty() && !status.is_loading {
+                p { class: "row-subtitle", "Nothing matches the current filters" }
+            }
             for image in images {
                 div { class: "row item images-row",
-                    span { "{image.repository}" }
+                    label { class: "row-select",
+                        SelectBox { table: TableKind::Images, id: image.id.clone() }
+                        "{image.repository}"
+                    }
                     span { "{image.tag}" }
                     span { "{image.id}" }
                     span { "{image.size}" }
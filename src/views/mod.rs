@@ -9,11 +9,23 @@ pub use dashboard::Dashboard;
 mod containers;
 pub use containers::Containers;
 
+mod container_detail;
+pub use container_detail::ContainerDetail;
+
 mod images;
 pub use images::Images;
 
 mod volumes;
 pub use volumes::Volumes;
 
+mod networks;
+pub use networks::Networks;
+
+mod topology;
+pub use topology::Topology;
+
+mod compose;
+pub use compose::Compose;
+
 mod settings;
 pub use settings::Settings;
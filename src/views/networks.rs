@@ -0,0 +1,294 @@
+use dioxus::prelude::*;
+
+use crate::Route;
+use crate::components::{ResourceStatusBar, SectionHeader, SkeletonRows};
+use crate::services::{NETWORK_DRIVERS, NetworkSpec, parse_labels};
+use crate::utils::AppState;
+
+#[component]
+pub fn Networks() -> Element {
+    let app_state = use_context::<AppState>();
+    let networks = (app_state.networks)();
+    let status = (app_state.networks_status)();
+    let app_state_for_retry = app_state.clone();
+
+    let mut name = use_signal(String::new);
+    let mut driver = use_signal(|| NETWORK_DRIVERS[0].to_string());
+    let mut subnet = use_signal(String::new);
+    let mut gateway = use_signal(String::new);
+    let mut labels = use_signal(String::new);
+
+    let app_state_for_create = app_state.clone();
+
+    rsx! {
+        SectionHeader {
+            title: "Networks".to_string(),
+            subtitle: Some("Container connectivity".to_string())
+        }
+
+        div { class: "action-bar",
+            ResourceStatusBar {
+                status: status.clone(),
+                on_retry: move |_| app_state_for_retry.refresh_networks(),
+            }
+            Link { to: Route::Topology {}, class: "button secondary", "Topology" }
+            button {
+                class: "button primary",
+                disabled: status.is_loading,
+                onclick: move |_| app_state.refresh_networks(),
+                "Refresh"
+            }
+        }
+
+        div { class: "card form-card",
+            h3 { "Create network" }
+            div { class: "form-grid",
+                div {
+                    label { class: "form-label", "Name" }
+                    input {
+                        class: "text-input",
+                        value: name,
+                        oninput: move |event| name.set(event.value()),
+                        placeholder: "backend"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Driver" }
+                    select {
+                        class: "text-input",
+                        value: driver,
+                        onchange: move |event| driver.set(event.value()),
+                        for option in NETWORK_DRIVERS {
+                            option { value: option, "{option}" }
+                        }
+                    }
+                }
+                div {
+                    label { class: "form-label", "Subnet" }
+                    input {
+                        class: "text-input",
+                        value: subnet,
+                        oninput: move |event| subnet.set(event.value()),
+                        placeholder: "172.28.0.0/16"
+                    }
+                }
+                div {
+                    label { class: "form-label", "Gateway" }
+                    input {
+                        class: "text-input",
+                        value: gateway,
+                        oninput: move |event| gateway.set(event.value()),
+                        placeholder: "172.28.0.1"
+                    }
+                }
+            }
+            label { class: "form-label", "Labels" }
+            input {
+                class: "text-input",
+                value: labels,
+                oninput: move |event| labels.set(event.value()),
+                placeholder: "team=platform, env=dev"
+            }
+            div { class: "button-row",
+                button {
+                    class: "button primary",
+                    disabled: name().trim().is_empty(),
+                    onclick: move |_| {
+                        let optional = |value: String| {
+                            let value = value.trim().to_string();
+                            (!value.is_empty()).then_some(value)
+                        };
+                        app_state_for_create.create_network(NetworkSpec {
+                            name: name().trim().to_string(),
+                            driver: driver(),
+                            subnet: optional(subnet()),
+                            gateway: optional(gateway()),
+                            labels: parse_labels(&labels()),
+                        }, move || {
+                            name.set(String::new());
+                            subnet.set(String::new());
+                            gateway.set(String::new());
+                            labels.set(String::new());
+                        });
+                    },
+                    "Create"
+                }
+            }
+        }
+
+        div { class: "table",
+            div { class: "row header networks-row",
+                span { "Name" }
+                span { "Driver" }
+                span { "Scope" }
+                span { "Subnet" }
+                span { "Gateway" }
+                span { "Action" }
+            }
+            if status.is_first_load() {
+                SkeletonRows { row_class: "networks-row".to_string(), columns: 6 }
+            }
+            {networks.iter().map(|network| {
+                let id_for_inspect = network.id.clone();
+                let id_for_remove = network.id.clone();
+                let name_for_remove = network.name.clone();
+                let builtin = network.is_builtin();
+                let app_state_for_inspect = app_state.clone();
+                let app_state_for_remove = app_state.clone();
+
+                rsx! {
+                    div { class: "row item networks-row",
+                        div {
+                            p { class: "row-title", "{network.name}" }
+                            p { class: "row-subtitle", "{network.id}" }
+                        }
+                        span { "{network.driver}" }
+                        span { "{network.scope}" }
+                        span { "{network.subnet}" }
+                        span { "{network.gateway}" }
+                        div { class: "button-row compact",
+                            button {
+                                class: "button secondary",
+                                onclick: move |_| app_state_for_inspect.inspect_network(id_for_inspect.clone()),
+                                "Inspect"
+                            }
+                            button {
+                                class: "button secondary",
+                                disabled: builtin,
+                                onclick: move |_| app_state_for_remove.request_remove_network(id_for_remove.clone(), name_for_remove.clone()),
+                                "Remove"
+                            }
+                        }
+                    }
+                }
+            })}
+        }
+
+        NetworkDetailsPanel {}
+    }
+}
+
+/// Shows the containers attached to the inspected network and lets users connect more.
+#[component]
+fn NetworkDetailsPanel() -> Element {
+    let app_state = use_context::<AppState>();
+    let mut container = use_signal(String::new);
+    let mut aliases = use_signal(String::new);
+
+    let Some(details) = (app_state.selected_network)() else {
+        return rsx! {};
+    };
+    let containers = (app_state.containers)();
+
+    let network_id = details.info.id.clone();
+    let network_for_connect = network_id.clone();
+    let app_state_for_connect = app_state.clone();
+    let app_state_for_close = app_state.clone();
+
+    rsx! {
+        div { class: "card details-card",
+            div { class: "file-browser-header",
+                div {
+                    h3 { "{details.info.name}" }
+                    p { class: "row-subtitle",
+                        "{details.info.driver} · {details.info.subnet}"
+                        if details.info.internal {
+                            " · internal"
+                        }
+                    }
+                }
+                button {
+                    class: "button secondary",
+                    onclick: move |_| app_state_for_close.close_network_details(),
+                    "Close"
+                }
+            }
+
+            if !details.labels.is_empty() {
+                div { class: "label-list",
+                    for (key, value) in details.labels.iter() {
+                        span { class: "pill", "{key}={value}" }
+                    }
+                }
+            }
+
+            div { class: "table",
+                div { class: "row header endpoints-row",
+                    span { "Container" }
+                    span { "IPv4 address" }
+                    span { "MAC address" }
+                    span { "Action" }
+                }
+                if details.endpoints.is_empty() {
+                    p { class: "row-subtitle", "No containers are attached" }
+                }
+                {details.endpoints.iter().map(|endpoint| {
+                    let network = network_id.clone();
+                    let container_id = endpoint.container_id.clone();
+                    let app_state_for_disconnect = app_state.clone();
+
+                    rsx! {
+                        div { class: "row item endpoints-row",
+                            span { "{endpoint.container_name}" }
+                            span { "{endpoint.ipv4_address}" }
+                            span { "{endpoint.mac_address}" }
+                            button {
+                                class: "button secondary",
+                                onclick: move |_| app_state_for_disconnect.disconnect_network(network.clone(), container_id.clone()),
+                                "Disconnect"
+                            }
+                        }
+                    }
+                })}
+            }
+
+            div { class: "form-grid",
+                div {
+                    label { class: "form-label", "Container" }
+                    select {
+                        class: "text-input",
+                        value: container,
+                        onchange: move |event| container.set(event.value()),
+                        option { value: "", "Select a container" }
+                        for info in containers {
+                            option { value: "{info.id}", "{info.name}" }
+                        }
+                    }
+                }
+                div {
+                    label { class: "form-label", "Aliases" }
+                    input {
+                        class: "text-input",
+                        value: aliases,
+                        oninput: move |event| aliases.set(event.value()),
+                        placeholder: "db, postgres"
+                    }
+                }
+            }
+            div { class: "button-row",
+                button {
+                    class: "button primary",
+                    disabled: container().is_empty(),
+                    onclick: move |_| {
+                        let alias_list = aliases()
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|alias| !alias.is_empty())
+                            .map(str::to_string)
+                            .collect();
+                        app_state_for_connect.connect_network(
+                            network_for_connect.clone(),
+                            container(),
+                            alias_list,
+                            move || {
+                                container.set(String::new());
+                                aliases.set(String::new());
+                            },
+                        );
+                    },
+                    "Connect"
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
+use std::time::Instant;
+
 use dioxus::prelude::*;
 
 use crate::components::SectionHeader;
-use crate::utils::AppState;
+use crate::utils::{AppState, POLL_INTERVALS, PROTECTED_LABEL, PollSettings};
 
 #[component]
 pub fn Settings() -> Element {
@@ -9,6 +11,14 @@ pub fn Settings() -> Element {
     let mut docker_host = app_state.docker_host.clone();
     let app_state_for_test = app_state.clone();
     let app_state_for_save = app_state.clone();
+    let polling = (app_state.polling)();
+    let poll_state = (app_state.poll_state)();
+    let app_state_for_toggle = app_state.clone();
+    let app_state_for_interval = app_state.clone();
+    let safe_mode = (app_state.safe_mode)();
+    let app_state_for_safe_mode = app_state.clone();
+    let notifications = (app_state.notifications)();
+    let app_state_for_notifications = app_state.clone();
 
     rsx! {
         SectionHeader {
@@ -16,7 +26,7 @@ pub fn Settings() -> Element {
             subtitle: Some("Connection and preferences".to_string())
         }
 
-        div { class: "card",
+        div { class: "card form-card",
             label { class: "form-label", "Docker host" }
             input {
                 class: "text-input",
@@ -27,15 +37,84 @@ pub fn Settings() -> Element {
             div { class: "button-row",
                 button {
                     class: "button",
-                    onclick: move |_| app_state_for_test.record_action("Tested Docker connection"),
+                    onclick: move |_| {
+                        app_state_for_test.record_success("Docker connection", "Test", Instant::now())
+                    },
                     "Test connection"
                 }
                 button {
                     class: "button secondary",
-                    onclick: move |_| app_state_for_save.record_action("Saved settings"),
+                    onclick: move |_| {
+                        app_state_for_save.record_success("settings", "Save", Instant::now())
+                    },
                     "Save"
                 }
             }
         }
+
+        div { class: "card form-card",
+            h3 { "Automatic refresh" }
+            label { class: "checkbox-label",
+                input {
+                    r#type: "checkbox",
+                    checked: polling.enabled,
+                    onchange: move |event| {
+                        app_state_for_toggle.set_polling(PollSettings {
+                            enabled: event.checked(),
+                            ..polling
+                        })
+                    }
+                }
+                "Refresh all lists in the background"
+            }
+            label { class: "form-label", "Interval" }
+            select {
+                class: "text-input",
+                disabled: !polling.enabled,
+                value: "{polling.interval_secs}",
+                onchange: move |event| {
+                    if let Ok(interval_secs) = event.value().parse() {
+                        app_state_for_interval.set_polling(PollSettings {
+                            interval_secs,
+                            ..polling
+                        });
+                    }
+                },
+                for seconds in POLL_INTERVALS {
+                    option { value: "{seconds}", "{seconds} seconds" }
+                }
+            }
+            p { class: "row-subtitle", "{poll_state.label()}" }
+        }
+
+        div { class: "card form-card",
+            h3 { "Safe mode" }
+            label { class: "checkbox-label",
+                input {
+                    r#type: "checkbox",
+                    checked: safe_mode,
+                    onchange: move |event| app_state_for_safe_mode.set_safe_mode(event.checked()),
+                }
+                "Type to confirm destructive actions"
+            }
+            p { class: "row-subtitle",
+                "Containers labelled {PROTECTED_LABEL}=true cannot be removed or taken down while safe mode is on."
+            }
+        }
+
+        div { class: "card form-card",
+            h3 { "Notifications" }
+            label { class: "checkbox-label",
+                input {
+                    r#type: "checkbox",
+                    checked: notifications,
+                    onchange: move |event| app_state_for_notifications.set_notifications(event.checked()),
+                }
+                "Notify me when a container turns unhealthy or exits with an error"
+            }
+            p { class: "row-subtitle",
+                "Checked on every automatic refresh, which keeps watching containers while the window is hidden. Containers stopped or restarted from here do not notify."
+            }
+        }
     }
 }
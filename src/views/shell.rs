@@ -1,12 +1,14 @@
 use dioxus::prelude::*;
 
 use crate::Route;
+use crate::components::{ActivityDrawer, ConfirmModal, ToastStack};
 use crate::utils::AppState;
 
 #[component]
 pub fn AppShell() -> Element {
     let app_state = use_context::<AppState>();
-    let last_action = (app_state.last_action)();
+    let failures = app_state.activity.read().failure_count();
+    let mut show_activity = use_signal(|| false);
 
     rsx! {
         div { class: "app-shell",
@@ -17,6 +19,8 @@ pub fn AppShell() -> Element {
                     Link { to: Route::Containers {}, class: "nav-link", "Containers" }
                     Link { to: Route::Images {}, class: "nav-link", "Images" }
                     Link { to: Route::Volumes {}, class: "nav-link", "Volumes" }
+                    Link { to: Route::Networks {}, class: "nav-link", "Networks" }
+                    Link { to: Route::Compose {}, class: "nav-link", "Compose" }
                     Link { to: Route::Settings {}, class: "nav-link", "Settings" }
                 }
             }
@@ -26,12 +30,24 @@ pub fn AppShell() -> Element {
                         h1 { class: "app-title", "Doctainr Desktop" }
                         p { class: "app-subtitle", "Local engine workspace" }
                     }
-                    if let Some(action) = last_action {
-                        div { class: "header-action", "Last action: {action}" }
+                    button {
+                        class: "header-action",
+                        onclick: move |_| show_activity.toggle(),
+                        "Activity"
+                        if failures > 0 {
+                            span { class: "failure-badge", "{failures}" }
+                        }
                     }
                 }
-                main { class: "page", Outlet::<Route> {} }
+                main { class: "page",
+                    Outlet::<Route> {}
+                }
+            }
+            if show_activity() {
+                ActivityDrawer { on_close: move |_| show_activity.set(false) }
             }
+            ToastStack {}
+            ConfirmModal {}
         }
     }
 }
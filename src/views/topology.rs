@@ -0,0 +1,189 @@
+use dioxus::prelude::*;
+
+use crate::Route;
+use crate::components::{SectionHeader, StatusPill};
+use crate::services::{NetworkTopology, NodeKind, layout};
+use crate::utils::AppState;
+
+#[component]
+pub fn Topology() -> Element {
+    let app_state = use_context::<AppState>();
+    let app_state_for_load = app_state.clone();
+    use_hook(move || app_state_for_load.refresh_topology());
+
+    let mut selected = use_signal(|| None::<(NodeKind, String)>);
+    let topology = (app_state.topology)();
+
+    rsx! {
+        SectionHeader {
+            title: "Network topology".to_string(),
+            subtitle: Some("How containers reach each other".to_string())
+        }
+
+        div { class: "action-bar",
+            Link { to: Route::Networks {}, class: "button secondary", "Back to networks" }
+            button {
+                class: "button primary",
+                onclick: move |_| app_state.refresh_topology(),
+                "Refresh"
+            }
+        }
+
+        match topology {
+            None => rsx! {
+                p { class: "row-subtitle", "Loading topology…" }
+            },
+            Some(topology) => {
+                let graph = layout(&topology);
+                let current = selected();
+                let is_highlighted = |network_id: &str, container_id: &str| match &current {
+                    Some((NodeKind::Network, id)) => id == network_id,
+                    Some((NodeKind::Container, id)) => id == container_id,
+                    None => true,
+                };
+
+                rsx! {
+                    if !topology.skipped.is_empty() {
+                        div { class: "topology-skipped",
+                            for skipped in topology.skipped.iter() {
+                                p { "{skipped.name} is missing from the graph: {skipped.reason}" }
+                            }
+                        }
+                    }
+                    div { class: "card topology-card",
+                        svg {
+                            class: "topology-graph",
+                            width: "{graph.width}",
+                            height: "{graph.height}",
+                            "viewBox": "0 0 {graph.width} {graph.height}",
+                            for edge in graph.edges.iter() {
+                                g {
+                                    class: if is_highlighted(&edge.network_id, &edge.container_id) { "edge active" } else { "edge" },
+                                    line {
+                                        x1: "{edge.from.0}",
+                                        y1: "{edge.from.1}",
+                                        x2: "{edge.to.0}",
+                                        y2: "{edge.to.1}",
+                                    }
+                                    text {
+                                        x: "{(edge.from.0 + edge.to.0) / 2.0}",
+                                        y: "{(edge.from.1 + edge.to.1) / 2.0 - 6.0}",
+                                        text_anchor: "middle",
+                                        "{edge.label}"
+                                    }
+                                }
+                            }
+                            for node in graph.nodes.iter() {
+                                {
+                                    let key = (node.kind, node.id.clone());
+                                    let is_selected = current.as_ref() == Some(&key);
+                                    let class_name = match (node.kind, is_selected) {
+                                        (NodeKind::Network, true) => "node network selected",
+                                        (NodeKind::Network, false) => "node network",
+                                        (NodeKind::Container, true) => "node container selected",
+                                        (NodeKind::Container, false) => "node container",
+                                    };
+                                    rsx! {
+                                        g {
+                                            class: class_name,
+                                            onclick: move |_| {
+                                                if selected().as_ref() == Some(&key) {
+                                                    selected.set(None);
+                                                } else {
+                                                    selected.set(Some(key.clone()));
+                                                }
+                                            },
+                                            circle { cx: "{node.x}", cy: "{node.y}", r: "14" }
+                                            text {
+                                                x: if node.kind == NodeKind::Network { "{node.x - 22.0}" } else { "{node.x + 22.0}" },
+                                                y: "{node.y + 4.0}",
+                                                text_anchor: if node.kind == NodeKind::Network { "end" } else { "start" },
+                                                "{node.label}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    if let Some((kind, id)) = current {
+                        TopologyDetails { topology: topology.clone(), kind, id }
+                    } else {
+                        p { class: "row-subtitle", "Select a network or container to see its connections" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TopologyDetails(topology: NetworkTopology, kind: NodeKind, id: String) -> Element {
+    match kind {
+        NodeKind::Network => {
+            let name = topology.network_name(&id);
+            rsx! {
+                div { class: "card details-card",
+                    h3 { "{name}" }
+                    div { class: "table",
+                        div { class: "row header links-row",
+                            span { "Container" }
+                            span { "IPv4 address" }
+                            span { "Aliases" }
+                        }
+                        for link in topology.links_for_network(&id) {
+                            div { class: "row item links-row",
+                                span { "{topology.container_name(&link.container_id)}" }
+                                span { "{link.ip_address}" }
+                                span { "{link.aliases.join(\", \")}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        NodeKind::Container => {
+            let Some(container) = topology
+                .containers
+                .iter()
+                .find(|container| container.id == id)
+            else {
+                return rsx! {};
+            };
+            let ports = if container.ports.is_empty() {
+                "No published ports".to_string()
+            } else {
+                container.ports.join(", ")
+            };
+            rsx! {
+                div { class: "card details-card",
+                    div { class: "file-browser-header",
+                        div {
+                            h3 { "{container.name}" }
+                            p { class: "row-subtitle", "{ports}" }
+                        }
+                        StatusPill {
+                            label: container.state.label().to_string(),
+                            class_name: container.state.css_class().to_string()
+                        }
+                    }
+                    div { class: "table",
+                        div { class: "row header links-row",
+                            span { "Network" }
+                            span { "IPv4 address" }
+                            span { "Aliases" }
+                        }
+                        for link in topology.links_for_container(&id) {
+                            div { class: "row item links-row",
+                                span { "{topology.network_name(&link.network_id)}" }
+                                span { "{link.ip_address}" }
+                                span { "{link.aliases.join(\", \")}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
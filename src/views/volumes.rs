@@ -1,12 +1,19 @@
 use dioxus::prelude::*;
 
-use crate::components::SectionHeader;
-use crate::utils::AppState;
+use crate::components::{
+    BulkBar, ResourceStatusBar, SectionHeader, SelectBox, SkeletonRows, TableToolbar,
+};
+use crate::services::{FileKind, parent_path};
+use crate::utils::{AppState, TableKind, TableQuery};
 
 #[component]
 pub fn Volumes() -> Element {
     let app_state = use_context::<AppState>();
-    let volumes = (app_state.volumes)();
+    let query = (app_state.volume_query)();
+    let volumes = query.apply(&app_state.volume_rows());
+    let filtered = query != TableQuery::default();
+    let status = (app_state.volumes_status)();
+    let app_state_for_retry = app_state.clone();
 
     rsx! {
         SectionHeader {
@@ -15,27 +22,172 @@ pub fn Volumes() -> Element {
         }
 
         div { class: "action-bar",
+            ResourceStatusBar {
+                status: status.clone(),
+                on_retry: move |_| app_state_for_retry.refresh_volumes(),
+            }
             button {
                 class: "button primary",
+                disabled: status.is_loading,
                 onclick: move |_| app_state.refresh_volumes(),
                 "Refresh"
             }
         }
 
+        TableToolbar { table: TableKind::Volumes }
+        BulkBar {
+            table: TableKind::Volumes,
+            visible: volumes.iter().map(|volume| volume.name.clone()).collect::<Vec<_>>(),
+        }
+
         div { class: "table",
-            div { class: "row header",
+            div { class: "row header volumes-row",
                 span { "Name" }
                 span { "Driver" }
                 span { "Mountpoint" }
                 span { "Size" }
+                span { "Files" }
             }
-            for volume in volumes {
-                div { class: "row item volumes-row",
-                    span { "{volume.name}" }
-                    span { "{volume.driver}" }
-                    span { "{volume.mountpoint}" }
-                    span { "{volume.size}" }
+            if status.is_first_load() {
+                SkeletonRows { row_class: "volumes-row".to_string(), columns: 5 }
+            }
+            if filtered && volumes.is_empty() && !status.is_loading {
+                p { class: "row-subtitle", "Nothing matches the current filters" }
+            }
+            {volumes.iter().map(|volume| {
+                let name = volume.name.clone();
+                let app_state_for_btn = app_state.clone();
+
+                rsx! {
+                    div { class: "row item volumes-row",
+                        label { class: "row-select",
+                            SelectBox { table: TableKind::Volumes, id: volume.name.clone() }
+                            "{volume.name}"
+                        }
+                        span { "{volume.driver}" }
+                        span { "{volume.mountpoint}" }
+                        span { "{volume.size}" }
+                        button {
+                            class: "button secondary",
+                            onclick: move |_| app_state_for_btn.browse_volume(name.clone(), "/".to_string()),
+                            "Browse"
+                        }
+                    }
+                }
+            })}
+        }
+
+        VolumeFileBrowser {}
+    }
+}
+
+/// Lists, downloads and uploads files inside the volume selected in the table above.
+#[component]
+fn VolumeFileBrowser() -> Element {
+    let app_state = use_context::<AppState>();
+    let Some(browser) = (app_state.volume_browser)() else {
+        return rsx! {};
+    };
+
+    let volume = browser.target.clone();
+    let path = browser.path.clone();
+    let parent = parent_path(&path);
+
+    let app_state_for_up = app_state.clone();
+    let volume_for_up = volume.clone();
+    let app_state_for_upload = app_state.clone();
+    let volume_for_upload = volume.clone();
+    let path_for_upload = path.clone();
+    let app_state_for_close = app_state.clone();
+
+    rsx! {
+        div { class: "card file-browser",
+            div { class: "file-browser-header",
+                div {
+                    h3 { "{volume}" }
+                    p { class: "row-subtitle", "{path}" }
                 }
+                div { class: "button-row",
+                    button {
+                        class: "button secondary",
+                        disabled: path == "/",
+                        onclick: move |_| app_state_for_up.browse_volume(volume_for_up.clone(), parent.clone()),
+                        "Up"
+                    }
+                    button {
+                        class: "button primary",
+                        onclick: move |_| {
+                            let app_state = app_state_for_upload.clone();
+                            let volume = volume_for_upload.clone();
+                            let dir = path_for_upload.clone();
+                            spawn(async move {
+                                if let Some(file) = rfd::AsyncFileDialog::new().pick_file().await {
+                                    app_state.upload_to_volume(volume, dir, file.path().to_path_buf());
+                                }
+                            });
+                        },
+                        "Upload file"
+                    }
+                    button {
+                        class: "button secondary",
+                        onclick: move |_| app_state_for_close.close_volume_browser(),
+                        "Close"
+                    }
+                }
+            }
+
+            if browser.is_loading {
+                p { class: "row-subtitle", "Loading…" }
+            } else if browser.entries.is_empty() {
+                p { class: "row-subtitle", "This directory is empty" }
+            }
+
+            div { class: "table",
+                {browser.entries.iter().map(|entry| {
+                    let entry_path = entry.path.clone();
+                    let is_dir = entry.kind == FileKind::Directory;
+                    let app_state_for_open = app_state.clone();
+                    let volume_for_open = volume.clone();
+                    let path_for_open = entry_path.clone();
+                    let app_state_for_download = app_state.clone();
+                    let volume_for_download = volume.clone();
+                    let file_name = format!("{}.tar", entry.name);
+
+                    rsx! {
+                        div { class: "row item files-row",
+                            if is_dir {
+                                button {
+                                    class: "link-button",
+                                    onclick: move |_| app_state_for_open.browse_volume(volume_for_open.clone(), path_for_open.clone()),
+                                    "{entry.name}/"
+                                }
+                            } else {
+                                span { "{entry.name}" }
+                            }
+                            span { "{entry.kind.label()}" }
+                            span { "{entry.size}" }
+                            button {
+                                class: "button secondary",
+                                onclick: move |_| {
+                                    let app_state = app_state_for_download.clone();
+                                    let volume = volume_for_download.clone();
+                                    let path = entry_path.clone();
+                                    let file_name = file_name.clone();
+                                    spawn(async move {
+                                        if let Some(file) = rfd::AsyncFileDialog::new()
+                                            .set_file_name(file_name)
+                                            .save_file()
+                                            .await
+                                        {
+                                            app_state.download_volume_path(volume, path, file.path().to_path_buf());
+                                        }
+                                    });
+                                },
+                                "Download"
+                            }
+                        }
+                    }
+                })}
             }
         }
     }